@@ -0,0 +1,221 @@
+//! Command-line arguments.
+
+use std::path::PathBuf;
+
+pub(crate) const USAGE: &str = "\
+Inspect, lint and format DBus XML definitions.
+
+Usage: zbus-lockstep <COMMAND> [OPTIONS]
+
+Commands:
+  list                    List the interfaces and members in the XML directory
+  sig <Interface.Member>  Print the signatures of a member at each object path it is found at
+  find-sig <signature>    List the members whose body, arguments, reply or type has a signature
+  common-sigs             List the signatures shared by several members, the most used first
+  explain <signature>     Describe a signature in plain language, with the Rust type of each part
+  lint [FILE]...          Lint XML files, those in the XML directory by default
+  diff <OLD> <NEW>        Report the changes between two versions of an XML file
+  fmt [FILE]...           Format XML files in canonical form, those in the XML directory by default
+  help                    Print this message
+
+Options:
+  --xml <DIR>             The XML directory, instead of `LOCKSTEP_XML_PATH`, `xml/` or `XML/`
+  --check                 With `fmt`, only check whether the files are in canonical form
+  --sort-members          With `fmt`, sort the members of interfaces
+  -h, --help              Print this message
+  -V, --version           Print the version
+";
+
+/// A parsed command line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Command {
+    List {
+        xml: Option<PathBuf>,
+    },
+    Sig {
+        member: String,
+        xml: Option<PathBuf>,
+    },
+    FindSig {
+        signature: String,
+        xml: Option<PathBuf>,
+    },
+    CommonSigs {
+        xml: Option<PathBuf>,
+    },
+    Explain {
+        signature: String,
+    },
+    Lint {
+        files: Vec<PathBuf>,
+        xml: Option<PathBuf>,
+    },
+    Diff {
+        old: PathBuf,
+        new: PathBuf,
+    },
+    Fmt {
+        files: Vec<PathBuf>,
+        xml: Option<PathBuf>,
+        check: bool,
+        sort_members: bool,
+    },
+    Help,
+    Version,
+}
+
+impl Command {
+    /// Parse the arguments, without the program name.
+    pub(crate) fn parse(args: &[String]) -> Result<Self, String> {
+        let mut xml = None;
+        let mut check = false;
+        let mut sort_members = false;
+        let mut positional = Vec::new();
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-h" | "--help" => return Ok(Command::Help),
+                "-V" | "--version" => return Ok(Command::Version),
+                "--xml" => {
+                    let dir = args.next().ok_or("`--xml` needs a directory")?;
+                    xml = Some(PathBuf::from(dir));
+                }
+                "--check" => check = true,
+                "--sort-members" => sort_members = true,
+                // Anything after `--` is positional, even if it starts with `-`.
+                "--" => positional.extend(args.by_ref().cloned()),
+                arg => match arg.strip_prefix("--xml=") {
+                    Some(dir) => xml = Some(PathBuf::from(dir)),
+                    None if arg.starts_with('-') && arg.len() > 1 => {
+                        return Err(format!("Unknown option `{arg}`"))
+                    }
+                    None => positional.push(arg.to_string()),
+                },
+            }
+        }
+
+        let Some((command, operands)) = positional.split_first() else {
+            return Ok(Command::Help);
+        };
+
+        let exact = |count: usize| {
+            if operands.len() == count {
+                Ok(())
+            } else {
+                Err(format!(
+                    "`{command}` takes {count} argument(s), {} given",
+                    operands.len()
+                ))
+            }
+        };
+        let only_with_fmt = |used: bool, option: &str| {
+            if used && command != "fmt" {
+                Err(format!("`{option}` is only used with `fmt`"))
+            } else {
+                Ok(())
+            }
+        };
+        only_with_fmt(check, "--check")?;
+        only_with_fmt(sort_members, "--sort-members")?;
+
+        let command = match command.as_str() {
+            "list" => {
+                exact(0)?;
+                Command::List { xml }
+            }
+            "sig" => {
+                exact(1)?;
+                Command::Sig {
+                    member: operands[0].clone(),
+                    xml,
+                }
+            }
+            "find-sig" => {
+                exact(1)?;
+                Command::FindSig {
+                    signature: operands[0].clone(),
+                    xml,
+                }
+            }
+            "common-sigs" => {
+                exact(0)?;
+                Command::CommonSigs { xml }
+            }
+            "explain" => {
+                exact(1)?;
+                Command::Explain {
+                    signature: operands[0].clone(),
+                }
+            }
+            "lint" => Command::Lint {
+                files: operands.iter().map(PathBuf::from).collect(),
+                xml,
+            },
+            "diff" => {
+                exact(2)?;
+                Command::Diff {
+                    old: PathBuf::from(&operands[0]),
+                    new: PathBuf::from(&operands[1]),
+                }
+            }
+            "fmt" => Command::Fmt {
+                files: operands.iter().map(PathBuf::from).collect(),
+                xml,
+                check,
+                sort_members,
+            },
+            "help" => Command::Help,
+            command => return Err(format!("Unknown command `{command}`")),
+        };
+        Ok(command)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Command, String> {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        Command::parse(&args)
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            parse(&["sig", "--xml", "xml", "org.example.Node.AddNode"]),
+            Ok(Command::Sig {
+                member: "org.example.Node.AddNode".to_string(),
+                xml: Some(PathBuf::from("xml")),
+            })
+        );
+        assert_eq!(
+            parse(&["fmt", "--check", "--xml=specs"]),
+            Ok(Command::Fmt {
+                files: Vec::new(),
+                xml: Some(PathBuf::from("specs")),
+                check: true,
+                sort_members: false,
+            })
+        );
+        assert_eq!(
+            parse(&["explain", "a{sv}"]),
+            Ok(Command::Explain {
+                signature: "a{sv}".to_string(),
+            })
+        );
+        assert_eq!(parse(&[]), Ok(Command::Help));
+        assert_eq!(parse(&["list", "--help"]), Ok(Command::Help));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse(&["sig"]).is_err());
+        assert!(parse(&["diff", "old.xml"]).is_err());
+        assert!(parse(&["list", "--check"]).is_err());
+        assert!(parse(&["list", "--verbose"]).is_err());
+        assert!(parse(&["frobnicate"]).is_err());
+        assert!(parse(&["list", "--xml"]).is_err());
+    }
+}
@@ -0,0 +1,275 @@
+//! The commands, each returns whether it found no problems.
+
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use zbus_lockstep::{
+    access_str, args_signature,
+    compat::compare,
+    explain,
+    fmt::Formatter,
+    get_method_args_type_at, get_method_return_type_at, get_property_type_at,
+    get_signal_body_type_at, interfaces_with_paths, lint, load_nodes, load_xml,
+    registry::Registry,
+    zbus_xml::{ArgDirection, Node},
+    LockstepError, Scanner, Severity,
+};
+use zvariant::Signature;
+
+use crate::{args::Command, Result, USAGE};
+
+pub(crate) fn run(command: Command) -> Result<bool> {
+    match command {
+        Command::List { xml } => list(&xml_dir(xml)?),
+        Command::Sig { member, xml } => sig(&xml_dir(xml)?, &member),
+        Command::FindSig { signature, xml } => find_sig(&xml_dir(xml)?, &signature),
+        Command::CommonSigs { xml } => common_sigs(&xml_dir(xml)?),
+        Command::Explain { signature } => explain_signature(&signature),
+        Command::Lint { files, xml } => lint_files(&files_or_dir(files, xml)?),
+        Command::Diff { old, new } => diff(&old, &new),
+        Command::Fmt {
+            files,
+            xml,
+            check,
+            sort_members,
+        } => {
+            let formatter = Formatter::new().sort_members(sort_members);
+            let from_dir = files.is_empty();
+            let mut files = files_or_dir(files, xml)?;
+            // Compressed files cannot be formatted, skip those found in the directory.
+            if from_dir {
+                files.retain(|file| file.extension().is_some_and(|ext| ext == "xml"));
+            }
+            format_files(&files, &formatter, check)
+        }
+        Command::Help => {
+            print!("{USAGE}");
+            Ok(true)
+        }
+        Command::Version => {
+            println!("zbus-lockstep {}", env!("CARGO_PKG_VERSION"));
+            Ok(true)
+        }
+    }
+}
+
+/// The XML directory: the one given, `LOCKSTEP_XML_PATH`, or `xml/` or `XML/` in the current
+/// directory.
+fn xml_dir(xml: Option<PathBuf>) -> Result<PathBuf> {
+    if let Some(xml) = xml.or_else(|| std::env::var_os("LOCKSTEP_XML_PATH").map(PathBuf::from)) {
+        return Ok(xml);
+    }
+    ["xml", "XML"]
+        .into_iter()
+        .map(PathBuf::from)
+        .find(|dir| dir.is_dir())
+        .ok_or_else(|| "No XML directory found, pass one with `--xml <DIR>`".into())
+}
+
+/// The files given, or the XML files in the XML directory.
+fn files_or_dir(files: Vec<PathBuf>, xml: Option<PathBuf>) -> Result<Vec<PathBuf>> {
+    if !files.is_empty() {
+        return Ok(files);
+    }
+    Ok(Scanner::new().scan(xml_dir(xml)?)?.files)
+}
+
+/// The nodes in each XML file in `dir`.
+fn load_dir(dir: &Path) -> Result<Vec<(PathBuf, Vec<Node<'static>>)>> {
+    Scanner::new()
+        .scan(dir)?
+        .files
+        .into_iter()
+        .map(|file| {
+            let nodes = load_nodes(&file).map_err(|e| format!("{}: {e}", file.display()))?;
+            Ok((file, nodes))
+        })
+        .collect()
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .unwrap_or(path.as_os_str())
+        .to_string_lossy()
+        .into_owned()
+}
+
+fn list(dir: &Path) -> Result<bool> {
+    for (file, nodes) in load_dir(dir)? {
+        for (path, interface) in nodes.iter().flat_map(interfaces_with_paths) {
+            println!("{} {path} ({})", interface.name(), file_name(&file));
+            for method in interface.methods() {
+                println!(
+                    "  method {}: {} -> {}",
+                    method.name(),
+                    display(&args_signature(method.args(), Some(ArgDirection::In))),
+                    display(&args_signature(method.args(), Some(ArgDirection::Out))),
+                );
+            }
+            for signal in interface.signals() {
+                println!(
+                    "  signal {}: {}",
+                    signal.name(),
+                    display(&args_signature(signal.args(), None))
+                );
+            }
+            for property in interface.properties() {
+                println!(
+                    "  property {}: {} ({})",
+                    property.name(),
+                    property.ty().to_string(),
+                    access_str(property.access())
+                );
+            }
+        }
+    }
+    Ok(true)
+}
+
+/// A signature for display, where an empty one would be invisible.
+fn display(signature: &str) -> &str {
+    if signature.is_empty() {
+        "()"
+    } else {
+        signature
+    }
+}
+
+fn sig(dir: &Path, member: &str) -> Result<bool> {
+    let (interface_name, member_name) = member
+        .rsplit_once('.')
+        .ok_or_else(|| format!("Expected `Interface.Member`, got \"{member}\""))?;
+
+    let mut found = false;
+    for (file, nodes) in load_dir(dir)? {
+        // Each object path implementing the interface may declare the member differently.
+        let interfaces: Vec<_> = nodes
+            .iter()
+            .flat_map(interfaces_with_paths)
+            .filter(|(_, interface)| interface.name() == interface_name)
+            .collect();
+        if interfaces.is_empty() {
+            continue;
+        }
+
+        // Ask the `get_*_at` functions, so the output is what they return.
+        let xml = load_xml(&file)?;
+        let xml = xml.as_bytes();
+        let name = file_name(&file);
+
+        for (path, interface) in interfaces {
+            if interface.methods().iter().any(|m| m.name() == member_name) {
+                let args = get_method_args_type_at(xml, &path, interface_name, member_name, None)?;
+                let reply =
+                    get_method_return_type_at(xml, &path, interface_name, member_name, None)?;
+                println!("{name} {path}: method {member}");
+                println!("  args: {}", display(&args.to_string()));
+                println!("  return: {}", display(&reply.to_string()));
+                found = true;
+            }
+            if interface.signals().iter().any(|s| s.name() == member_name) {
+                let body = get_signal_body_type_at(xml, &path, interface_name, member_name, None)?;
+                println!("{name} {path}: signal {member}");
+                println!("  body: {}", display(&body.to_string()));
+                found = true;
+            }
+            if let Some(property) = interface
+                .properties()
+                .iter()
+                .find(|p| p.name() == member_name)
+            {
+                let ty = get_property_type_at(xml, &path, interface_name, member_name)?;
+                println!("{name} {path}: property {member}");
+                println!("  type: {ty}");
+                println!("  access: {}", access_str(property.access()));
+                found = true;
+            }
+        }
+    }
+
+    if !found {
+        return Err(LockstepError::MemberNotFound(member.to_owned()).into());
+    }
+    Ok(true)
+}
+
+fn find_sig(dir: &Path, signature: &str) -> Result<bool> {
+    let wanted = Signature::from_str(signature)
+        .map_err(|e| format!("Invalid signature \"{signature}\": {e}"))?;
+
+    let registry = Registry::load(dir)?;
+    let mut found = false;
+    for member in registry.members_with_signature(&wanted) {
+        println!("{member}");
+        found = true;
+    }
+    Ok(found)
+}
+
+/// The signatures shared by several members, with the members using them.
+fn common_sigs(dir: &Path) -> Result<bool> {
+    let registry = Registry::load(dir)?;
+    for (signature, members) in registry.most_common_signatures(2) {
+        println!("{signature} ({} members)", members.len());
+        for member in members {
+            println!("  {member}");
+        }
+    }
+    Ok(true)
+}
+
+fn explain_signature(signature: &str) -> Result<bool> {
+    let signature = Signature::from_str(signature)
+        .map_err(|e| format!("Invalid signature \"{signature}\": {e}"))?;
+    print!("{}", explain(&signature));
+    Ok(true)
+}
+
+fn lint_files(files: &[PathBuf]) -> Result<bool> {
+    let mut errors = 0;
+    for file in files {
+        for diagnostic in lint(file) {
+            if diagnostic.severity == Severity::Error {
+                errors += 1;
+            }
+            println!("{diagnostic}");
+        }
+    }
+    Ok(errors == 0)
+}
+
+/// The single node in an XML file.
+fn load_node(path: &Path) -> Result<Node<'static>> {
+    let mut nodes = load_nodes(path)?;
+    match nodes.len() {
+        1 => Ok(nodes.remove(0)),
+        count => Err(format!(
+            "\"{}\" holds {count} documents, expected one",
+            path.display()
+        )
+        .into()),
+    }
+}
+
+fn diff(old: &Path, new: &Path) -> Result<bool> {
+    let report = compare(&load_node(old)?, &load_node(new)?);
+    print!("{report}");
+    Ok(!report.is_breaking_for_clients() && !report.is_breaking_for_servers())
+}
+
+fn format_files(files: &[PathBuf], formatter: &Formatter, check: bool) -> Result<bool> {
+    let mut canonical = true;
+    for file in files {
+        if check {
+            if let Err(e) = formatter.check_file(file) {
+                println!("{e}");
+                canonical = false;
+            }
+        } else if formatter.format_file(file)? {
+            println!("Formatted {}", file.display());
+        }
+    }
+    Ok(canonical)
+}
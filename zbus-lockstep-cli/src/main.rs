@@ -0,0 +1,37 @@
+//! # zbus-lockstep
+//!
+//! A command-line tool, built on `zbus-lockstep`, to inspect, lint and format `DBus` XML
+//! definitions. Run `zbus-lockstep help` for the commands.
+//!
+//! The exit status is 0 on success, 1 when a command finds a problem, e.g. lint errors, a
+//! file that is not in canonical form or a breaking change, and 2 on errors.
+
+mod args;
+mod commands;
+
+use std::process::ExitCode;
+
+use args::{Command, USAGE};
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    let command = match Command::parse(&args) {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("error: {e}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    match commands::run(command) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(1),
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::from(2)
+        }
+    }
+}
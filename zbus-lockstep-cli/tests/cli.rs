@@ -0,0 +1,176 @@
+use std::process::{Command, Output};
+
+fn zbus_lockstep(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_zbus-lockstep"))
+        .args(args)
+        .env_remove("LOCKSTEP_XML_PATH")
+        .output()
+        .expect("Failed to run zbus-lockstep")
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+fn test_list() {
+    let output = zbus_lockstep(&["list", "--xml", "../xml"]);
+
+    assert!(output.status.success());
+    let stdout = stdout(&output);
+    assert!(stdout.contains("org.example.Node / (test_definition_file.xml)"));
+    assert!(stdout.contains("  method RequestName: su -> u"));
+    assert!(stdout.contains("  signal RemoveNode: (so)"));
+    assert!(stdout.contains("  property Features: as (read)"));
+}
+
+#[test]
+fn test_sig() {
+    let output = zbus_lockstep(&["sig", "--xml", "../xml", "org.example.Node.RequestName"]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "test_definition_file.xml /: method org.example.Node.RequestName\n  \
+         args: (su)\n  \
+         return: u\n"
+    );
+
+    // Arguments without a direction are input arguments, as for `find-sig`.
+    let output = zbus_lockstep(&["sig", "--xml", "../xml", "org.example.Dial.Turn"]);
+    assert!(stdout(&output).contains("  args: (ib)\n"));
+    let output = zbus_lockstep(&["find-sig", "--xml", "../xml", "ib"]);
+    assert!(stdout(&output).contains("org.example.Dial.Turn (method args, directions.xml)"));
+
+    // Each object path implementing the interface is shown.
+    let output = zbus_lockstep(&["sig", "--xml", "../xml", "org.example.Lamp.Dim"]);
+    let stdout = stdout(&output);
+    assert!(stdout.contains(
+        "object_paths.xml /org/example/lamp/ceiling: method org.example.Lamp.Dim\n  args: u\n"
+    ));
+    assert!(stdout.contains(
+        "object_paths.xml /org/example/lamp/desk: method org.example.Lamp.Dim\n  args: d\n"
+    ));
+
+    let output = zbus_lockstep(&["sig", "--xml", "../xml", "org.example.Node.Missing"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn test_find_sig() {
+    let output = zbus_lockstep(&["find-sig", "--xml", "../xml", "(so)"]);
+
+    assert!(output.status.success());
+    let stdout = stdout(&output);
+    assert!(stdout.contains("org.example.Node.AddNode (signal body, test_definition_file.xml)"));
+    assert!(stdout.contains("org.example.Node.RemoveNode (signal body, test_definition_file.xml)"));
+
+    let output = zbus_lockstep(&["find-sig", "--xml", "../xml", "a{tt}(xxx)"]);
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn test_common_sigs() {
+    let output = zbus_lockstep(&["common-sigs", "--xml", "../xml"]);
+
+    assert!(output.status.success());
+    let stdout = stdout(&output);
+    assert!(stdout.lines().any(|line| line.starts_with("(so) (")));
+    assert!(
+        stdout.contains("  org.example.Node.RemoveNode (signal body, test_definition_file.xml)\n")
+    );
+}
+
+#[test]
+fn test_explain() {
+    let output = zbus_lockstep(&["explain", "a{sv}"]);
+
+    assert!(output.status.success());
+    assert!(stdout(&output)
+        .starts_with("`a{sv}`: dict: string → variant, `HashMap<String, OwnedValue>`\n"));
+
+    let output = zbus_lockstep(&["explain", "(s"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn test_lint() {
+    let output = zbus_lockstep(&["lint", "../xml/test_definition_file.xml"]);
+    assert!(output.status.success());
+
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("broken.xml");
+    std::fs::write(&file, r#"<node><interface name="Broken"/></node>"#).unwrap();
+
+    let output = zbus_lockstep(&["lint", file.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout(&output).contains("error"));
+}
+
+#[test]
+fn test_diff() {
+    let dir = tempfile::tempdir().unwrap();
+    let old = dir.path().join("old.xml");
+    let new = dir.path().join("new.xml");
+    std::fs::write(
+        &old,
+        r#"<node><interface name="org.example.Node"><signal name="Gone"/></interface></node>"#,
+    )
+    .unwrap();
+    std::fs::write(&new, r#"<node><interface name="org.example.Node"/></node>"#).unwrap();
+
+    let output = zbus_lockstep(&["diff", old.to_str().unwrap(), old.to_str().unwrap()]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "No changes.\n");
+
+    let output = zbus_lockstep(&["diff", old.to_str().unwrap(), new.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout(&output).contains("Gone"));
+}
+
+#[test]
+fn test_fmt() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("org.example.Node.xml");
+    std::fs::write(&file, "<node><interface name='org.example.Node'/></node>").unwrap();
+    let dir = dir.path().to_str().unwrap();
+
+    let output = zbus_lockstep(&["fmt", "--check", "--xml", dir]);
+    assert_eq!(output.status.code(), Some(1));
+
+    let output = zbus_lockstep(&["fmt", "--xml", dir]);
+    assert!(output.status.success());
+    assert!(stdout(&output).starts_with("Formatted"));
+
+    let output = zbus_lockstep(&["fmt", "--check", "--xml", dir]);
+    assert!(output.status.success());
+    assert_eq!(
+        std::fs::read_to_string(file).unwrap(),
+        "<node>\n  <interface name=\"org.example.Node\"/>\n</node>\n"
+    );
+}
+
+#[test]
+fn test_fmt_skips_compressed_files_in_dir() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("org.example.Node.xml.gz");
+    std::fs::write(&file, [0x1f, 0x8b, 0x08, 0x00]).unwrap();
+    let dir_str = dir.path().to_str().unwrap();
+
+    let output = zbus_lockstep(&["fmt", "--check", "--xml", dir_str]);
+    assert!(output.status.success());
+
+    // Named explicitly, a compressed file is an error.
+    let output = zbus_lockstep(&["fmt", file.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn test_usage_errors() {
+    let output = zbus_lockstep(&["frobnicate"]);
+    assert_eq!(output.status.code(), Some(2));
+
+    let output = zbus_lockstep(&["--help"]);
+    assert!(output.status.success());
+    assert!(stdout(&output).starts_with("Inspect, lint and format"));
+}
@@ -0,0 +1,85 @@
+//! `#[validate(allowed_values: ...)]`, for enums serialized as one of the strings an `s` allows.
+
+use std::path::PathBuf;
+
+use quote::quote;
+use syn::{Ident, ItemEnum};
+use zbus_lockstep::ALLOWED_VALUES;
+
+use crate::{dict::find_annotated_member, enums::unit_variants, Result, ValidateArgs};
+
+pub(crate) fn validate_allowed_values(
+    args: &ValidateArgs,
+    item_enum: &ItemEnum,
+    xml_files: Vec<(PathBuf, Vec<zbus_xml::Node<'static>>)>,
+    skipped: &str,
+) -> Result<proc_macro2::TokenStream> {
+    let error = |message: &str| syn::Error::new(proc_macro2::Span::call_site(), message);
+    let name = args
+        .allowed_values
+        .as_deref()
+        .expect("validate_allowed_values requires an allowed_values argument");
+
+    if args.rename_all.is_some() {
+        return Err(error(
+            "The strings of the variants follow their serialization, use `#[serde(rename_all = \"...\")]` instead of `rename_all`.",
+        ));
+    }
+
+    let (xml_file_path, found) =
+        find_annotated_member(args, &xml_files, name, ALLOWED_VALUES, skipped)?;
+    let (interface, member, arg, msg_type) = found.call_args();
+
+    // The strings are taken from the serialized variants, the names are not needed.
+    let item_enum_name = &item_enum.ident;
+    let variants = unit_variants(item_enum, None)?
+        .into_iter()
+        .map(|(_, ident)| quote!((stringify!(#ident), serialized(&#item_enum_name::#ident))));
+
+    let allowed = args.getter(
+        "get_allowed_values",
+        quote!(#interface),
+        quote!(#member, #arg, zbus_lockstep::MsgType::#msg_type),
+    );
+    let test_name = Ident::new(
+        &format!("test_{item_enum_name}_allowed_values"),
+        proc_macro2::Span::call_site(),
+    );
+
+    Ok(quote! {
+        #item_enum
+
+        #[cfg(test)]
+        #[test]
+        fn #test_name() {
+            use zvariant::Type;
+
+            assert_eq!(
+                <#item_enum_name as Type>::SIGNATURE.to_string(),
+                "s",
+                "`{}` should be serialized as a string, e.g. with `#[zvariant(signature = \"s\")]`.",
+                stringify!(#item_enum_name),
+            );
+
+            let xml = zbus_lockstep::load_xml(#xml_file_path).expect(concat!("\"", #xml_file_path, "\" expected to be a valid XML file."));
+            let allowed = #allowed.expect("Failed to get allowed values from XML file.");
+
+            // The string a variant is sent as.
+            let ctxt = zvariant::serialized::Context::new_dbus(zvariant::LE, 0);
+            let serialized = |variant: &#item_enum_name| -> String {
+                let data = zvariant::to_bytes(ctxt, variant).expect("Failed to serialize variant.");
+                let (string, _) = data.deserialize::<String>().expect("Failed to read variant as a string.");
+                string
+            };
+            let variants = [#(#variants),*];
+            let variants: Vec<(&str, &str)> = variants
+                .iter()
+                .map(|(name, string)| (*name, string.as_str()))
+                .collect();
+
+            allowed
+                .validate(stringify!(#item_enum_name), &variants)
+                .unwrap_or_else(|e| panic!("{e}"));
+        }
+    })
+}
@@ -0,0 +1,217 @@
+//! `#[validate(dict_schema: ...)]`, for structs holding an `a{sv}` with a schema annotation.
+
+use std::path::PathBuf;
+
+use quote::quote;
+use syn::{Ident, ItemStruct};
+use zbus_lockstep::{MsgType, DICT_SCHEMA};
+use zbus_xml::Annotation;
+
+use crate::{
+    fields::{dict_fields, DictStructField},
+    Result, ValidateArgs,
+};
+
+/// An argument or property with an annotation.
+#[derive(PartialEq)]
+pub(crate) struct Found {
+    interface: String,
+    member: String,
+    arg: Option<String>,
+    msg_type: MsgType,
+}
+
+impl Found {
+    /// The interface, the member, the argument and the message type, as arguments of
+    /// `zbus_lockstep::get_dict_schema` and the like.
+    pub(crate) fn call_args(
+        &self,
+    ) -> (
+        &str,
+        &str,
+        proc_macro2::TokenStream,
+        proc_macro2::TokenStream,
+    ) {
+        let arg = match &self.arg {
+            Some(arg) => quote!(Some(#arg)),
+            None => quote!(None),
+        };
+        let msg_type = match self.msg_type {
+            MsgType::Method => quote!(Method),
+            MsgType::Signal => quote!(Signal),
+            MsgType::Property => quote!(Property),
+        };
+
+        (&self.interface, &self.member, arg, msg_type)
+    }
+}
+
+pub(crate) fn validate_dict_schema(
+    args: &ValidateArgs,
+    item_struct: &ItemStruct,
+    xml_files: Vec<(PathBuf, Vec<zbus_xml::Node<'static>>)>,
+    skipped: &str,
+) -> Result<proc_macro2::TokenStream> {
+    let name = args
+        .dict_schema
+        .as_deref()
+        .expect("validate_dict_schema requires a dict_schema argument");
+
+    let (xml_file_path, found) =
+        find_annotated_member(args, &xml_files, name, DICT_SCHEMA, skipped)?;
+
+    let fields = dict_fields(item_struct, "dict_schema")?.into_iter().map(
+        |DictStructField { name, key, ty, .. }| {
+            quote! {
+                zbus_lockstep::DictField {
+                    field: #name,
+                    key: #key,
+                    signature: <#ty as zvariant::Type>::SIGNATURE,
+                }
+            }
+        },
+    );
+
+    let (interface, member, arg, msg_type) = found.call_args();
+
+    let item_struct_name = &item_struct.ident;
+    let schema = args.getter(
+        "get_dict_schema",
+        quote!(#interface),
+        quote!(#member, #arg, zbus_lockstep::MsgType::#msg_type),
+    );
+    let test_name = Ident::new(
+        &format!("test_{item_struct_name}_dict_schema"),
+        proc_macro2::Span::call_site(),
+    );
+
+    Ok(quote! {
+        #item_struct
+
+        #[cfg(test)]
+        #[test]
+        fn #test_name() {
+            use zvariant::Type;
+
+            assert_eq!(
+                <#item_struct_name as Type>::SIGNATURE.to_string(),
+                "a{sv}",
+                "`{}` should have the signature of a dictionary, e.g. with `#[zvariant(signature = \"dict\")]`.",
+                stringify!(#item_struct_name),
+            );
+
+            let xml = zbus_lockstep::load_xml(#xml_file_path).expect(concat!("\"", #xml_file_path, "\" expected to be a valid XML file."));
+            let schema = #schema.expect("Failed to get dictionary schema from XML file.");
+            let fields = [#(#fields),*];
+
+            schema
+                .validate(stringify!(#item_struct_name), &fields)
+                .unwrap_or_else(|e| panic!("{e}"));
+        }
+    })
+}
+
+/// The argument or property named `name` with an annotation named `annotation`, along with the
+/// first file, in order of their names, that defines it. The `member`, `interface` and `path`
+/// arguments narrow the search, more than one match is an error.
+pub(crate) fn find_annotated_member<'f>(
+    args: &ValidateArgs,
+    xml_files: &'f [(PathBuf, Vec<zbus_xml::Node<'static>>)],
+    name: &str,
+    annotation: &str,
+    skipped: &str,
+) -> Result<(&'f str, Found)> {
+    let error = |message: &str| syn::Error::new(proc_macro2::Span::call_site(), message);
+    let is_annotated =
+        |annotations: &[Annotation]| annotations.iter().any(|other| other.name() == annotation);
+    let is_member = |member: &str| args.member.as_deref().is_none_or(|name| name == member);
+
+    // Each annotated argument or property, along with the first file defining it.
+    let mut found: Vec<(&PathBuf, Found)> = Vec::new();
+    for (file, nodes) in xml_files {
+        for (path, interface) in nodes.iter().flat_map(zbus_lockstep::interfaces_with_paths) {
+            if !args.is_at_path(&path)
+                || args
+                    .interface
+                    .as_ref()
+                    .is_some_and(|iface| interface.name().as_str() != iface)
+            {
+                continue;
+            }
+
+            let mut candidates = Vec::new();
+            for method in interface.methods() {
+                if is_member(method.name().as_str()) {
+                    for arg in method.args() {
+                        candidates.push((method.name().to_string(), arg, MsgType::Method));
+                    }
+                }
+            }
+            for signal in interface.signals() {
+                if is_member(signal.name().as_str()) {
+                    for arg in signal.args() {
+                        candidates.push((signal.name().to_string(), arg, MsgType::Signal));
+                    }
+                }
+            }
+
+            let mut matches: Vec<Found> = candidates
+                .into_iter()
+                .filter(|(_, arg, _)| arg.name() == Some(name) && is_annotated(arg.annotations()))
+                .map(|(member, _, msg_type)| Found {
+                    interface: interface.name().to_string(),
+                    member,
+                    arg: Some(name.to_owned()),
+                    msg_type,
+                })
+                .collect();
+
+            // Properties have no member, the name is the property's.
+            if args.member.is_none() {
+                matches.extend(
+                    interface
+                        .properties()
+                        .iter()
+                        .filter(|property| {
+                            property.name() == name && is_annotated(property.annotations())
+                        })
+                        .map(|property| Found {
+                            interface: interface.name().to_string(),
+                            member: property.name().to_string(),
+                            arg: None,
+                            msg_type: MsgType::Property,
+                        }),
+                );
+            }
+
+            for candidate in matches {
+                if !found.iter().any(|(_, other)| *other == candidate) {
+                    found.push((file, candidate));
+                }
+            }
+        }
+    }
+
+    let (xml_file_path, found) = match found.len() {
+        0 => {
+            return Err(error(&format!(
+                "No argument or property '{name}' with a {annotation} annotation found.{skipped}"
+            )))
+        }
+        1 => found.remove(0),
+        _ => {
+            let members: String = found
+                .iter()
+                .map(|(_, found)| format!("\n  {}.{}", found.interface, found.member))
+                .collect();
+            return Err(error(&format!(
+                "Multiple members with a {annotation} annotation for '{name}', please disambiguate with `interface` or `member`:{members}"
+            )));
+        }
+    };
+    let xml_file_path = xml_file_path
+        .to_str()
+        .expect("XML file path should be valid UTF-8");
+
+    Ok((xml_file_path, found))
+}
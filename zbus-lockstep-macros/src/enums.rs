@@ -0,0 +1,135 @@
+//! `#[validate(enum: ...)]`, for enums whose discriminants follow an enumeration in XML.
+
+use std::path::PathBuf;
+
+use quote::quote;
+use syn::{Fields, Ident, ItemEnum};
+use zbus_lockstep::{RenameRule, ENUM_PREFIX};
+
+use crate::{Result, ValidateArgs};
+
+pub(crate) fn validate_enum(
+    args: &ValidateArgs,
+    item_enum: &ItemEnum,
+    xml_files: Vec<(PathBuf, Vec<zbus_xml::Node<'static>>)>,
+    skipped: &str,
+) -> Result<proc_macro2::TokenStream> {
+    // Without an `enum` argument, the enumeration has the name of the enum.
+    let item_enum_name = &item_enum.ident;
+    let enum_name = args
+        .enum_name
+        .clone()
+        .unwrap_or_else(|| item_enum_name.to_string());
+
+    let (xml_file_path, interface_name) = find_declaring_interface(
+        args,
+        &xml_files,
+        &format!("{ENUM_PREFIX}{enum_name}"),
+        &format!("enumeration '{enum_name}'"),
+        skipped,
+    )?;
+
+    let variants = unit_variants(item_enum, args.rename_all)?
+        .into_iter()
+        .map(|(name, ident)| quote!((#name, #item_enum_name::#ident as i64)));
+
+    let enumeration = args.getter(
+        "get_enumeration",
+        quote!(#interface_name),
+        quote!(#enum_name),
+    );
+    let test_name = Ident::new(
+        &format!("test_{item_enum_name}_enumeration"),
+        proc_macro2::Span::call_site(),
+    );
+
+    Ok(quote! {
+        #item_enum
+
+        #[cfg(test)]
+        #[test]
+        fn #test_name() {
+            let xml = zbus_lockstep::load_xml(#xml_file_path).expect(concat!("\"", #xml_file_path, "\" expected to be a valid XML file."));
+            let enumeration = #enumeration.expect("Failed to get enumeration from XML file.");
+
+            enumeration
+                .validate(stringify!(#item_enum_name), &[#(#variants),*])
+                .unwrap_or_else(|e| panic!("{e}"));
+        }
+    })
+}
+
+/// The interface with an annotation named `annotation`, along with the first file, in order of
+/// their names, that defines it. `declared` names what the annotation declares, in errors.
+pub(crate) fn find_declaring_interface<'f>(
+    args: &ValidateArgs,
+    xml_files: &'f [(PathBuf, Vec<zbus_xml::Node<'static>>)],
+    annotation: &str,
+    declared: &str,
+    skipped: &str,
+) -> Result<(&'f str, String)> {
+    let error = |message: &str| syn::Error::new(proc_macro2::Span::call_site(), message);
+
+    let mut found: Option<(&PathBuf, String)> = None;
+    for (file, nodes) in xml_files {
+        for (path, interface) in nodes.iter().flat_map(zbus_lockstep::interfaces_with_paths) {
+            if !args.is_at_path(&path)
+                || args
+                    .interface
+                    .as_ref()
+                    .is_some_and(|iface| interface.name().as_str() != iface)
+                || !interface
+                    .annotations()
+                    .iter()
+                    .any(|a| a.name() == annotation)
+            {
+                continue;
+            }
+
+            match &found {
+                None => found = Some((file, interface.name().to_string())),
+                Some((_, name)) if name != interface.name().as_str() => {
+                    return Err(error(&format!(
+                        "Multiple interfaces declare {declared}. Please disambiguate."
+                    )));
+                }
+                Some(_) => {}
+            }
+        }
+    }
+
+    let (xml_file_path, interface_name) = found.ok_or_else(|| {
+        error(&format!(
+            "No interface declaring {declared} found.{skipped}"
+        ))
+    })?;
+    let xml_file_path = xml_file_path
+        .to_str()
+        .expect("XML file path should be valid UTF-8");
+
+    Ok((xml_file_path, interface_name))
+}
+
+/// The name of each variant of an enum with unit variants, following `rename_all`, along with
+/// its identifier.
+pub(crate) fn unit_variants(
+    item_enum: &ItemEnum,
+    rename_all: Option<RenameRule>,
+) -> Result<Vec<(String, &Ident)>> {
+    let rename_all = rename_all.unwrap_or(RenameRule::None);
+    item_enum
+        .variants
+        .iter()
+        .map(|variant| {
+            if !matches!(variant.fields, Fields::Unit) {
+                return Err(syn::Error::new_spanned(
+                    variant,
+                    "Only enums with unit variants can be validated against a declaration in XML.",
+                ));
+            }
+
+            let ident = &variant.ident;
+            Ok((rename_all.apply_to_variant(&ident.to_string()), ident))
+        })
+        .collect()
+}
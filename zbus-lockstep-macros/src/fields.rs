@@ -0,0 +1,171 @@
+//! The fields of structs holding an `a{sv}` dictionary, and the keys they hold.
+//!
+//! Keys follow serde's `rename_all` and `rename` attributes, as zvariant's `dict` signature
+//! serializes fields with the names serde gives them.
+
+use syn::{
+    meta::ParseNestedMeta, Attribute, Fields, GenericArgument, ItemStruct, LitStr, PathArguments,
+    Type,
+};
+use zbus_lockstep::RenameRule;
+
+use crate::Result;
+
+/// A field of a struct holding an `a{sv}` dictionary.
+pub(crate) struct DictStructField<'s> {
+    /// The name of the field.
+    pub(crate) name: String,
+    /// The key of the dictionary the field holds.
+    pub(crate) key: String,
+    /// The type of the field, or `T` for an `Option<T>`.
+    pub(crate) ty: &'s Type,
+    /// Whether the field is an `Option`.
+    pub(crate) optional: bool,
+}
+
+/// The fields of `item_struct` that hold a key, skipping `#[serde(skip)]` fields.
+///
+/// `argument` names the `#[validate]` argument, for errors.
+pub(crate) fn dict_fields<'s>(
+    item_struct: &'s ItemStruct,
+    argument: &str,
+) -> Result<Vec<DictStructField<'s>>> {
+    let Fields::Named(named) = &item_struct.fields else {
+        return Err(syn::Error::new_spanned(
+            &item_struct.ident,
+            format!("The `{argument}` argument requires a struct with named fields."),
+        ));
+    };
+
+    let rename_all = serde_rename_all(&item_struct.attrs)?.unwrap_or_default();
+
+    let mut fields = Vec::new();
+    for field in &named.named {
+        let serde = SerdeField::from_attrs(&field.attrs)?;
+        if serde.skip {
+            continue;
+        }
+
+        let ident = field
+            .ident
+            .as_ref()
+            .expect("Named fields have an identifier");
+        let name = ident.to_string().trim_start_matches("r#").to_owned();
+        let key = serde.rename.unwrap_or_else(|| rename_all.apply(&name));
+        let (ty, optional) = match option_inner(&field.ty) {
+            Some(inner) => (inner, true),
+            None => (&field.ty, false),
+        };
+
+        fields.push(DictStructField {
+            name,
+            key,
+            ty,
+            optional,
+        });
+    }
+
+    Ok(fields)
+}
+
+/// Remove the `#[variant_type(T)]` attributes from the fields of `item_struct`.
+///
+/// Returns the index of each field with the attribute, along with `T`, the type its variant holds.
+pub(crate) fn take_variant_types(item_struct: &mut ItemStruct) -> Result<Vec<(usize, Type)>> {
+    let mut variant_types = Vec::new();
+    for (index, field) in item_struct.fields.iter_mut().enumerate() {
+        let (attrs, others): (Vec<_>, Vec<_>) = field
+            .attrs
+            .drain(..)
+            .partition(|attr| attr.path().is_ident("variant_type"));
+        field.attrs = others;
+
+        if let Some(duplicate) = attrs.get(1) {
+            return Err(syn::Error::new_spanned(
+                duplicate,
+                "Only one `#[variant_type]` attribute per field.",
+            ));
+        }
+        if let Some(attr) = attrs.first() {
+            variant_types.push((index, attr.parse_args::<Type>()?));
+        }
+    }
+    Ok(variant_types)
+}
+
+/// `T` if `ty` is an `Option<T>`.
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first()? {
+        GenericArgument::Type(inner) if args.args.len() == 1 => Some(inner),
+        _ => None,
+    }
+}
+
+/// The rule of a `#[serde(rename_all = "...")]` attribute, if any.
+fn serde_rename_all(attrs: &[Attribute]) -> Result<Option<RenameRule>> {
+    let mut rename_all = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("serde")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename_all") && meta.input.peek(syn::Token![=]) {
+                let lit = meta.value()?.parse::<LitStr>()?;
+                let rule = lit
+                    .value()
+                    .parse()
+                    .map_err(|e: String| syn::Error::new(lit.span(), e))?;
+                rename_all = Some(rule);
+                Ok(())
+            } else {
+                skip_meta(&meta)
+            }
+        })?;
+    }
+    Ok(rename_all)
+}
+
+/// What the serde attributes of a field tell about its name in the dictionary.
+#[derive(Default)]
+struct SerdeField {
+    rename: Option<String>,
+    skip: bool,
+}
+
+impl SerdeField {
+    fn from_attrs(attrs: &[Attribute]) -> Result<Self> {
+        let mut field = SerdeField::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("serde")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") && meta.input.peek(syn::Token![=]) {
+                    field.rename = Some(meta.value()?.parse::<LitStr>()?.value());
+                    Ok(())
+                } else if meta.path.is_ident("skip") {
+                    field.skip = true;
+                    Ok(())
+                } else {
+                    skip_meta(&meta)
+                }
+            })?;
+        }
+        Ok(field)
+    }
+}
+
+/// Skip the value of a serde attribute that does not affect names, e.g. `default = "..."` or
+/// `rename(serialize = "...")`.
+fn skip_meta(meta: &ParseNestedMeta<'_>) -> Result<()> {
+    if meta.input.peek(syn::Token![=]) {
+        meta.value()?.parse::<syn::Expr>()?;
+    } else if meta.input.peek(syn::token::Paren) {
+        meta.parse_nested_meta(|nested| skip_meta(&nested))?;
+    }
+    Ok(())
+}
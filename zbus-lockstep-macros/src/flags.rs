@@ -0,0 +1,127 @@
+//! `#[validate(flags: ...)]`, for `bitflags!` types and `enumflags2` enums following a flag set
+//! in XML.
+
+use std::path::PathBuf;
+
+use proc_macro2::TokenTree;
+use quote::quote;
+use syn::{Ident, Item, ItemMacro};
+use zbus_lockstep::FLAGS_PREFIX;
+
+use crate::{
+    enums::{find_declaring_interface, unit_variants},
+    Result, ValidateArgs,
+};
+
+pub(crate) fn validate_flags(
+    args: &ValidateArgs,
+    item: &Item,
+    xml_files: Vec<(PathBuf, Vec<zbus_xml::Node<'static>>)>,
+    skipped: &str,
+) -> Result<proc_macro2::TokenStream> {
+    let error = |message: &str| syn::Error::new(proc_macro2::Span::call_site(), message);
+
+    // The name of the type, and an expression for its flags, a `&[(&str, u64)]`.
+    let (type_name, flags) = match item {
+        Item::Enum(item_enum) => {
+            let item_enum_name = &item_enum.ident;
+            let variants = unit_variants(item_enum, args.rename_all)?
+                .into_iter()
+                .map(|(name, ident)| quote!((#name, #item_enum_name::#ident as u64)));
+
+            (item_enum_name.clone(), quote!(&[#(#variants),*]))
+        }
+        Item::Macro(item_macro) if is_bitflags(item_macro) => {
+            if args.rename_all.is_some() {
+                return Err(error(
+                    "The names of the flags of `bitflags!` are compared as written, `rename_all` is not supported.",
+                ));
+            }
+
+            // Unnamed flags, `const _ = ...;`, are not part of the flag set.
+            let type_name = bitflags_name(item_macro)?;
+            let flags = quote! {
+                &<#type_name as bitflags::Flags>::FLAGS
+                    .iter()
+                    .filter(|flag| !flag.name().is_empty())
+                    .map(|flag| (flag.name(), bitflags::Flags::bits(flag.value()) as u64))
+                    .collect::<Vec<_>>()
+            };
+
+            (type_name, flags)
+        }
+        _ => {
+            return Err(syn::Error::new_spanned(
+                item,
+                "The `flags` argument expects an enum or a `bitflags!` invocation.",
+            ))
+        }
+    };
+
+    let flags_name = args
+        .flags
+        .as_deref()
+        .expect("validate_flags requires a flags argument");
+
+    let (xml_file_path, interface_name) = find_declaring_interface(
+        args,
+        &xml_files,
+        &format!("{FLAGS_PREFIX}{flags_name}"),
+        &format!("flag set '{flags_name}'"),
+        skipped,
+    )?;
+
+    let flag_set = args.getter("get_flag_set", quote!(#interface_name), quote!(#flags_name));
+    let test_name = Ident::new(
+        &format!("test_{type_name}_flags"),
+        proc_macro2::Span::call_site(),
+    );
+
+    Ok(quote! {
+        #item
+
+        #[cfg(test)]
+        #[test]
+        fn #test_name() {
+            let xml = zbus_lockstep::load_xml(#xml_file_path).expect(concat!("\"", #xml_file_path, "\" expected to be a valid XML file."));
+            let flag_set = #flag_set.expect("Failed to get flag set from XML file.");
+
+            flag_set
+                .validate(stringify!(#type_name), #flags)
+                .unwrap_or_else(|e| panic!("{e}"));
+        }
+    })
+}
+
+/// Whether the macro invoked is `bitflags!`.
+fn is_bitflags(item_macro: &ItemMacro) -> bool {
+    item_macro
+        .mac
+        .path
+        .segments
+        .last()
+        .is_some_and(|segment| segment.ident == "bitflags")
+}
+
+/// The name of the type a `bitflags!` invocation declares.
+fn bitflags_name(item_macro: &ItemMacro) -> Result<Ident> {
+    let mut names = Vec::new();
+    let mut tokens = item_macro.mac.tokens.clone().into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        if let TokenTree::Ident(ident) = &token {
+            if ident == "struct" {
+                if let Some(TokenTree::Ident(name)) = tokens.peek() {
+                    names.push(name.clone());
+                }
+            }
+        }
+    }
+
+    match names.as_slice() {
+        [name] => Ok(name.clone()),
+        _ => Err(syn::Error::new_spanned(
+            &item_macro.mac,
+            "Expected a `bitflags!` invocation declaring a single type.",
+        )),
+    }
+}
@@ -1,15 +1,25 @@
 //! # zbus-lockstep-macros
 //!
-//! This provides the `validate` macro that builds on `zbus-lockstep`.
+//! This provides the `validate` and `lockstep_types` macros that build on `zbus-lockstep`.
 #![doc(html_root_url = "https://docs.rs/zbus-lockstep-macros/0.5.0")]
 
+mod allowed_values;
+mod dict;
+mod enums;
+mod fields;
+mod flags;
+mod properties;
+mod types;
+
 type Result<T> = std::result::Result<T, syn::Error>;
 
-use std::{collections::HashMap, path::PathBuf};
+use std::path::PathBuf;
 
 use proc_macro::TokenStream;
-use quote::quote;
-use syn::{parse::ParseStream, parse_macro_input, Ident, ItemStruct, LitStr, Token};
+use quote::{format_ident, quote};
+use syn::{
+    ext::IdentExt, parse::ParseStream, parse_macro_input, Ident, Item, ItemStruct, LitStr, Token,
+};
 
 /// Validate a struct's type signature against XML signal body type.
 ///
@@ -23,11 +33,22 @@ use syn::{parse::ParseStream, parse_macro_input, Ident, ItemStruct, LitStr, Toke
 ///
 /// # Arguments
 ///
-/// `#[validate]` can take three optional arguments:
+/// `#[validate]` can take these optional arguments:
 ///
 /// * `xml`: Path to XML file(s) containing the signal definition.
 /// * `interface`: Interface name of the signal.
 /// * `signal`: Signal name.
+/// * `path`: Object path of the node implementing the interface.
+/// * `property`: Property name, validates against the property's type instead of a signal body.
+/// * `access`: Access mode of the property, one of `read`, `write` or `readwrite`.
+/// * `properties_of`: Interface name, validates the struct against all its properties.
+/// * `dict_schema`: Argument or property name, validates the struct against its schema.
+/// * `member`: Method or signal name of the `dict_schema` or `allowed_values` argument.
+/// * `rename_all`: Case conversion from variant names to the names of an enumeration or flag set.
+/// * `enum`: Enumeration name, validates an enum's discriminants against the enumeration.
+/// * `flags`: Flag set name, validates an enum or `bitflags!` type against the flag set.
+/// * `allowed_values`: Argument or property name, validates an enum's strings against the
+///   values it allows.
 ///
 /// `#[validate(xml: <xml_path>, interface: <interface_name>, member: <member_name>)]`
 ///
@@ -51,6 +72,10 @@ use syn::{parse::ParseStream, parse_macro_input, Ident, ItemStruct, LitStr, Toke
 /// Alternatively, you can provide the XML directory path as environment variable,
 /// `LOCKSTEP_XML_PATH`, which will override both default and the path argument.
 ///
+/// Compressed files, `.xml.gz` and `.xml.zst`, are read given the `gzip` or `zstd` feature.
+/// Other files, subdirectories and files matching a glob in `.lockstepignore` are skipped,
+/// see `zbus_lockstep::Scanner`.
+///
 /// ## `interface`
 ///
 /// If more than one signal with the same name is defined in the XML file(s),
@@ -79,6 +104,229 @@ use syn::{parse::ParseStream, parse_macro_input, Ident, ItemStruct, LitStr, Toke
 /// }
 /// ```
 ///
+/// ## `path`
+///
+/// Introspection data may nest `<node>`s, each implementing interfaces at its own object path.
+/// All nodes are searched, unless an object path is provided.
+///
+/// ```ignore
+/// #[validate(path: "/org/a11y/atspi/cache")]
+/// #[derive(Type)]
+/// struct RemoveNodeSignal {
+///    name: String,
+///    path: OwnedObjectPath,
+/// }
+/// ```
+///
+/// ## `property`
+///
+/// Validates the struct against the type of a property instead of a signal body.
+///
+/// ```ignore
+/// #[validate(property: "Features")]
+/// #[derive(Type)]
+/// struct Features(Vec<String>);
+/// ```
+///
+/// ## `access`
+///
+/// Along with `property`, the access mode the implementation provides can be checked against
+/// the access mode declared in XML: `read`, `write` or `readwrite`.
+///
+/// ```ignore
+/// #[validate(property: "Features", access: read)]
+/// #[derive(Type)]
+/// struct Features(Vec<String>);
+/// ```
+///
+/// ## `#[variant_type(T)]`
+///
+/// A field holding a `v`, e.g. a `zvariant::OwnedValue`, can declare the type `T` its variant
+/// holds. `T` is checked against the `org.zbus.lockstep.VariantType` annotation of the
+/// corresponding argument, or of the property, see `zbus_lockstep::get_variant_type`. Fields
+/// correspond to the signal's arguments by position.
+///
+/// ```ignore
+/// #[validate(signal: "BoundsChanged")]
+/// #[derive(Type)]
+/// struct BoundsChanged {
+///    detail: String,
+///    #[variant_type((i32, i32, i32, i32))]
+///    bounds: OwnedValue,
+/// }
+/// ```
+///
+/// ## `properties_of`
+///
+/// Validates a struct holding all properties of an interface, as returned by
+/// `org.freedesktop.DBus.Properties.GetAll`. The struct must have the signature `a{sv}`, e.g.
+/// with zvariant's `dict` signature. Each field must hold a readable property with the field's
+/// type, and each readable property must have a field. Fields of type `Option<T>` may be
+/// absent, which is not allowed for `const` properties. See `zbus_lockstep::validate_properties`.
+///
+/// Field names map to property names following `#[serde(rename_all = "...")]` and
+/// `#[serde(rename = "...")]`, fields with `#[serde(skip)]` are ignored.
+///
+/// ```ignore
+/// #[validate(properties_of: "org.example.Device")]
+/// #[derive(Deserialize, Type)]
+/// #[serde(rename_all = "PascalCase")]
+/// #[zvariant(signature = "dict")]
+/// struct DeviceProperties {
+///    name: String,
+///    power_saving: Option<bool>,
+/// }
+/// ```
+///
+/// The generated test is named `test_<struct name>_properties`.
+///
+/// ## `dict_schema`
+///
+/// Validates a struct holding an `a{sv}` option dictionary against the keys and value types
+/// listed by the `org.zbus.lockstep.DictSchema` annotation of an argument or property, see
+/// `zbus_lockstep::DictSchema`. The struct must have the signature `a{sv}`, each field must
+/// hold a key of the schema with the key's type. Field names map to keys as with
+/// `properties_of`.
+///
+/// ```xml
+/// <method name="Notify">
+///   <arg name="hints" type="a{sv}" direction="in">
+///     <annotation name="org.zbus.lockstep.DictSchema" value="urgency:y category:s"/>
+///   </arg>
+/// </method>
+/// ```
+///
+/// ```ignore
+/// #[validate(dict_schema: "hints", member: "Notify")]
+/// #[derive(Serialize, Type)]
+/// #[zvariant(signature = "dict")]
+/// struct Hints {
+///    urgency: Option<u8>,
+///    category: Option<String>,
+/// }
+/// ```
+///
+/// If arguments of several members share the name, `member` and `interface` disambiguate.
+/// The generated test is named `test_<struct name>_dict_schema`.
+///
+/// ## `enum`
+///
+/// On an enum, validates its discriminants against an enumeration declared on an interface by
+/// an `org.zbus.lockstep.Enum.<name>` annotation, see `zbus_lockstep::get_enumeration`. Without
+/// the argument, the enumeration has the name of the enum. All variants must be unit variants,
+/// and each must be in the enumeration with its value, and the other way around.
+///
+/// ```xml
+/// <interface name="org.a11y.atspi.Accessible">
+///   <annotation name="org.zbus.lockstep.Enum.Role" value="Invalid AcceleratorLabel Alert"/>
+/// </interface>
+/// ```
+///
+/// ```ignore
+/// #[validate(enum: "Role")]
+/// #[repr(u32)]
+/// enum AccessibleRole {
+///    Invalid,
+///    AcceleratorLabel,
+///    Alert,
+/// }
+/// ```
+///
+/// If several interfaces declare the enumeration, `interface` disambiguates.
+/// The generated test is named `test_<enum name>_enumeration`.
+///
+/// ## `flags`
+///
+/// Validates the flags of an `enumflags2` enum, or of a type declared with `bitflags!`, against
+/// a flag set declared on an interface by an `org.zbus.lockstep.Flags.<name>` annotation, see
+/// `zbus_lockstep::FlagSet`. Each flag must be a single bit at the declared position, flags may
+/// not overlap, and each flag in the set must be there.
+///
+/// ```xml
+/// <interface name="org.a11y.atspi.Accessible">
+///   <annotation name="org.zbus.lockstep.Flags.State" value="Invalid Active Armed Busy"/>
+/// </interface>
+/// ```
+///
+/// ```ignore
+/// #[validate(flags: "State")]
+/// #[bitflags]
+/// #[repr(u64)]
+/// #[derive(Clone, Copy)]
+/// enum State {
+///    Invalid = 1 << 0,
+///    Active = 1 << 1,
+///    Armed = 1 << 2,
+///    Busy = 1 << 3,
+/// }
+/// ```
+///
+/// The names of `bitflags!` constants are compared as written. Composite constants, the union
+/// of other flags, are skipped unless the flag set names them:
+///
+/// ```ignore
+/// #[validate(flags: "State")]
+/// bitflags! {
+///     struct StateSet: u64 {
+///         const Invalid = 1 << 0;
+///         const Active = 1 << 1;
+///         const Armed = 1 << 2;
+///         const Busy = 1 << 3;
+///         const Ready = Self::Active.bits() | Self::Armed.bits();
+///     }
+/// }
+/// ```
+///
+/// If several interfaces declare the flag set, `interface` disambiguates.
+/// The generated test is named `test_<type name>_flags`.
+///
+/// ## `allowed_values`
+///
+/// Validates an enum serialized as a string, e.g. with `#[zvariant(signature = "s")]`, against
+/// the strings listed by the `org.zbus.lockstep.AllowedValues` annotation of an `s` argument or
+/// property, see `zbus_lockstep::AllowedValues`. The strings the variants serialize to must
+/// match the allowed values exactly. The variants are serialized, so serde attributes such as
+/// `#[serde(rename_all = "...")]` are taken into account.
+///
+/// ```xml
+/// <property name="Access" type="s" access="read">
+///   <annotation name="org.zbus.lockstep.AllowedValues" value="read write readwrite"/>
+/// </property>
+/// ```
+///
+/// ```ignore
+/// #[validate(allowed_values: "Access")]
+/// #[derive(Serialize, Type)]
+/// #[serde(rename_all = "lowercase")]
+/// #[zvariant(signature = "s")]
+/// enum Access {
+///    Read,
+///    Write,
+///    ReadWrite,
+/// }
+/// ```
+///
+/// As with `dict_schema`, `member` and `interface` disambiguate.
+/// The generated test is named `test_<enum name>_allowed_values`.
+///
+/// ## `rename_all`
+///
+/// On an enum, sets how variant names map to the names in the enumeration or the flag set.
+/// Takes the values of serde's `rename_all`.
+///
+/// ```ignore
+/// #[validate(enum: "Layer", rename_all: "lowercase")]
+/// enum Layer {
+///    Invalid,
+///    Widget = 3,
+///    Mdi,
+/// }
+/// ```
+///
+/// The keys of a struct validated with `properties_of` or `dict_schema` are the names serde
+/// gives its fields, so they follow the struct's `#[serde(rename_all = "...")]` and the
+/// fields' `#[serde(rename = "...")]`, and `rename_all` is rejected there.
+///
 /// ## Multiple arguments
 ///
 /// You can provide multiple arguments with a comma separated list.
@@ -98,9 +346,8 @@ pub fn validate(args: TokenStream, input: TokenStream) -> TokenStream {
     // Parse the macro arguments.
     let args = parse_macro_input!(args as ValidateArgs);
 
-    // Parse the item struct.
-    let item_struct = parse_macro_input!(input as ItemStruct);
-    let item_name = item_struct.ident.to_string();
+    // Parse the item, a struct or an enum.
+    let item = parse_macro_input!(input as Item);
 
     let xml_str = args.xml.as_ref().and_then(|p| p.to_str());
 
@@ -116,63 +363,153 @@ pub fn validate(args: TokenStream, input: TokenStream) -> TokenStream {
         }
     };
 
-    // Store each file's XML as a string in a with the XML's file path as key.
-    let mut xml_files: HashMap<PathBuf, String> = HashMap::new();
-    let read_dir = std::fs::read_dir(xml);
+    // The XML files in the directory, in order of their names.
+    let scan = match zbus_lockstep::Scanner::new().scan(&xml) {
+        Ok(scan) => scan,
+        // If the path does not exist, the process lacks permissions to read the path,
+        // or the path is not a directory, return an error.
+        Err(e) => {
+            return syn::Error::new(
+                proc_macro2::Span::call_site(),
+                format!("Failed to read XML directory: {e}"),
+            )
+            .to_compile_error()
+            .into();
+        }
+    };
+
+    // Listed in errors, in case the definition is in a skipped file.
+    let skipped: String = scan
+        .skipped
+        .iter()
+        .map(|skipped| format!("\n  skipped {skipped}"))
+        .collect();
 
-    // If the path does not exist, the process lacks permissions to read the path,
-    // or the path is not a directory, return an error.
-    if let Err(e) = read_dir {
+    // Load the nodes of each XML file, with includes resolved.
+    let mut xml_files: Vec<(PathBuf, Vec<zbus_xml::Node<'static>>)> = Vec::new();
+    for path in &scan.files {
+        let nodes = match zbus_lockstep::load_nodes(path) {
+            Ok(nodes) => nodes,
+            Err(e) => {
+                return syn::Error::new(
+                    proc_macro2::Span::call_site(),
+                    format!(
+                        "Failed to parse XML file: \"{}\" Err: {e}",
+                        path.to_string_lossy()
+                    ),
+                )
+                .to_compile_error()
+                .into();
+            }
+        };
+        xml_files.push((path.clone(), nodes));
+    }
+
+    // Flag sets are declared for enums and `bitflags!` types.
+    if args.flags.is_some() {
+        return into_tokens(flags::validate_flags(&args, &item, xml_files, &skipped));
+    }
+
+    // Enums are validated against an enumeration or allowed values.
+    let mut item_struct = match item {
+        Item::Struct(item_struct) => item_struct,
+        Item::Enum(item_enum) => match args.mode() {
+            Some("allowed_values") => {
+                return into_tokens(allowed_values::validate_allowed_values(
+                    &args, &item_enum, xml_files, &skipped,
+                ));
+            }
+            None | Some("enum") => {
+                return into_tokens(enums::validate_enum(&args, &item_enum, xml_files, &skipped));
+            }
+            Some(mode) => {
+                return syn::Error::new(
+                    proc_macro2::Span::call_site(),
+                    format!("The `{mode}` argument requires a struct, an enum is validated against an enumeration, a flag set or allowed values."),
+                )
+                .to_compile_error()
+                .into();
+            }
+        },
+        item => {
+            return syn::Error::new_spanned(item, "`#[validate]` expects a struct or an enum.")
+                .to_compile_error()
+                .into();
+        }
+    };
+    let item_name = item_struct.ident.to_string();
+
+    if args.enum_name.is_some() || args.allowed_values.is_some() {
         return syn::Error::new(
             proc_macro2::Span::call_site(),
-            format!("Failed to read XML directory: {e}"),
+            "The `enum` and `allowed_values` arguments require an enum.",
         )
         .to_compile_error()
         .into();
     }
 
-    // Iterate over the directory and store each XML file as a string.
-    for entry in read_dir.expect("Failed to read XML directory") {
-        let entry = entry.expect("Failed to read XML file");
+    // Fields holding a variant, along with the type the variant holds, from `#[variant_type(T)]`.
+    let variant_types = match fields::take_variant_types(&mut item_struct) {
+        Ok(variant_types) => variant_types,
+        Err(e) => return e.to_compile_error().into(),
+    };
 
-        // Skip directories.
-        if entry.path().is_dir() {
-            continue;
-        }
+    if !variant_types.is_empty() && (args.properties_of.is_some() || args.dict_schema.is_some()) {
+        return syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "The `#[variant_type]` attribute requires validation against a signal or a property.",
+        )
+        .to_compile_error()
+        .into();
+    }
 
-        if entry.path().extension().expect("File has no extension.") == "xml" {
-            let xml =
-                std::fs::read_to_string(entry.path()).expect("Unable to read XML file to string");
-            xml_files.insert(entry.path().clone(), xml);
-        }
+    if args.rename_all.is_some() {
+        return syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "The names of fields follow their serialization, use `#[serde(rename_all = \"...\")]` instead of `rename_all`.",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    if args.properties_of.is_some() {
+        return into_tokens(properties::validate_properties_of(
+            &args,
+            &item_struct,
+            xml_files,
+            &skipped,
+        ));
+    }
+
+    if args.dict_schema.is_some() {
+        return into_tokens(dict::validate_dict_schema(
+            &args,
+            &item_struct,
+            xml_files,
+            &skipped,
+        ));
+    }
+
+    if args.property.is_some() {
+        return validate_property(&args, &item_struct, &variant_types, xml_files, &skipped);
     }
 
     // These are later needed to call `get_signal_body_type`.
     let mut xml_file_path = None;
     let mut interface_name = None;
     let mut signal_name = None;
+    let mut signal_args = None;
 
     // Iterate over `xml_files` and find the signal that is contained in the struct's name.
     // Or if `signal_arg` is provided, use that.
-    for (path_key, xml_string) in xml_files {
-        let node = zbus_xml::Node::try_from(xml_string.as_str());
-
-        if node.is_err() {
-            return syn::Error::new(
-                proc_macro2::Span::call_site(),
-                format!(
-                    "Failed to parse XML file: \"{}\" Err: {}",
-                    path_key.to_str().unwrap(),
-                    node.err().unwrap()
-                ),
-            )
-            .to_compile_error()
-            .into();
-        }
-
-        let node = node.unwrap();
+    for (path_key, nodes) in xml_files {
+        // Interfaces implemented by child nodes, or by any document in the file, are considered too.
+        for (path, interface) in nodes.iter().flat_map(zbus_lockstep::interfaces_with_paths) {
+            // We were called with a path argument, skip interfaces at other object paths.
+            if !args.is_at_path(&path) {
+                continue;
+            }
 
-        for interface in node.interfaces() {
             // We were called with an interface argument, so if the interface name does not match,
             // skip it.
             if args.interface.is_some()
@@ -194,13 +531,18 @@ pub fn validate(args: TokenStream, input: TokenStream) -> TokenStream {
                 {
                     interface_name = Some(interface.name().to_string());
                     signal_name = Some(xml_signal_name.to_string());
+                    signal_args = Some(arg_names(signal.args()));
                     xml_file_path = Some(path_key.clone());
                     continue;
                 }
 
                 if item_name.contains(xml_signal_name.as_str()) {
                     // If we have found a signal with the same name in an earlier iteration:
-                    if interface_name.is_some() && signal_name.is_some() {
+                    // The same interface may be implemented at several object paths.
+                    if interface_name.is_some()
+                        && signal_name.is_some()
+                        && interface_name.as_deref() != Some(interface.name().as_str())
+                    {
                         return syn::Error::new(
                             proc_macro2::Span::call_site(),
                             "Multiple interfaces with the same signal name. Please disambiguate.",
@@ -210,6 +552,7 @@ pub fn validate(args: TokenStream, input: TokenStream) -> TokenStream {
                     }
                     interface_name = Some(interface.name().to_string());
                     signal_name = Some(xml_signal_name.to_string());
+                    signal_args = Some(arg_names(signal.args()));
                     xml_file_path = Some(path_key.clone());
                 }
             }
@@ -223,7 +566,7 @@ pub fn validate(args: TokenStream, input: TokenStream) -> TokenStream {
         return syn::Error::new(
             proc_macro2::Span::call_site(),
             format!(
-                "No interface matching signal name '{}' found.",
+                "No interface matching signal name '{}' found.{skipped}",
                 args.signal.unwrap_or_else(|| item_name.clone())
             ),
         )
@@ -235,12 +578,47 @@ pub fn validate(args: TokenStream, input: TokenStream) -> TokenStream {
 
     let interface_name = interface_name.expect("Interface should have been found in search loop.");
     let signal_name = signal_name.expect("Signal should have been found in search loop.");
+    let signal_args = signal_args.expect("Signal should have been found in search loop.");
 
     let xml_file_path = xml_file_path.expect("XML file path should be found in search loop.");
     let xml_file_path = xml_file_path
         .to_str()
         .expect("XML file path should be valid UTF-8");
 
+    let object_path = args.object_path();
+
+    // Each variant field corresponds to the signal argument at its index.
+    if !variant_types.is_empty() && item_struct.fields.len() != signal_args.len() {
+        return syn::Error::new(
+            proc_macro2::Span::call_site(),
+            format!(
+                "The `#[variant_type]` attribute requires a field for each of the {} arguments of '{signal_name}'.",
+                signal_args.len()
+            ),
+        )
+        .to_compile_error()
+        .into();
+    }
+    let mut variant_checks = Vec::new();
+    for (index, ty) in &variant_types {
+        let Some(arg_name) = &signal_args[*index] else {
+            return syn::Error::new_spanned(
+                ty,
+                format!("Argument {index} of '{signal_name}' has no name."),
+            )
+            .to_compile_error()
+            .into();
+        };
+        variant_checks.push(variant_check(
+            &args,
+            &interface_name,
+            &signal_name,
+            Some(arg_name.as_str()),
+            quote!(Signal),
+            ty,
+        ));
+    }
+
     // Create a block to return the item struct with a uniquely named validation test.
     let test_name = format!("test_{item_name}_type_signature");
     let test_name = Ident::new(&test_name, proc_macro2::Span::call_site());
@@ -251,6 +629,11 @@ pub fn validate(args: TokenStream, input: TokenStream) -> TokenStream {
         proc_macro2::Span::call_site(),
     );
 
+    let signal_body_type = args.getter(
+        "get_signal_body_type",
+        quote!(#interface_name),
+        quote!(#signal_name, None),
+    );
     let item_plus_validation_test = quote! {
         #item_struct
 
@@ -259,22 +642,260 @@ pub fn validate(args: TokenStream, input: TokenStream) -> TokenStream {
         fn #test_name() {
             use zvariant::Type;
 
-            let xml_file = std::fs::File::open(#xml_file_path).expect("\"#xml_file_path\" expected to be a valid file path." );
-            let item_signature_from_xml = zbus_lockstep::get_signal_body_type(
-                xml_file,
-                #interface_name,
-                #signal_name,
-                None
-            ).expect("Failed to get signal body type from XML file.");
+            let xml = zbus_lockstep::load_xml(#xml_file_path).expect(concat!("\"", #xml_file_path, "\" expected to be a valid XML file."));
+            let item_signature_from_xml = #signal_body_type.expect("Failed to get signal body type from XML file.");
             let item_signature_from_struct = <#item_struct_name as Type>::SIGNATURE;
 
-            assert_eq!(&item_signature_from_xml, item_signature_from_struct);
+            assert_eq!(
+                &item_signature_from_xml,
+                item_signature_from_struct,
+                "{}\nA type that matches the XML:\n\n{}",
+                zbus_lockstep::explain_mismatch(&item_signature_from_xml, item_signature_from_struct),
+                zbus_lockstep::codegen::suggest_type(
+                    xml.as_bytes(),
+                    #interface_name,
+                    #object_path,
+                    &zbus_lockstep::codegen::TypeSource::SignalBody(#signal_name.to_string()),
+                    stringify!(#item_struct_name),
+                ).unwrap_or_else(|e| format!("None, {e}\n"))
+            );
+
+            #(#variant_checks)*
         }
     };
 
     item_plus_validation_test.into()
 }
 
+/// The tokens of a validated item, or the compile error it failed with.
+fn into_tokens(result: Result<proc_macro2::TokenStream>) -> TokenStream {
+    result.unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+/// Validate the struct against the type, and optionally the access mode, of a property.
+fn validate_property(
+    args: &ValidateArgs,
+    item_struct: &ItemStruct,
+    variant_types: &[(usize, syn::Type)],
+    xml_files: Vec<(PathBuf, Vec<zbus_xml::Node<'static>>)>,
+    skipped: &str,
+) -> TokenStream {
+    let item_name = item_struct.ident.to_string();
+    let property_name = args
+        .property
+        .as_deref()
+        .expect("validate_property requires a property argument");
+
+    let mut xml_file_path = None;
+    let mut interface_name = None;
+
+    for (path_key, nodes) in xml_files {
+        for (path, interface) in nodes.iter().flat_map(zbus_lockstep::interfaces_with_paths) {
+            if !args.is_at_path(&path) {
+                continue;
+            }
+
+            if args
+                .interface
+                .as_ref()
+                .is_some_and(|iface| interface.name().as_str() != iface)
+            {
+                continue;
+            }
+
+            if interface
+                .properties()
+                .iter()
+                .any(|property| property.name().as_str() == property_name)
+            {
+                if interface_name
+                    .as_deref()
+                    .is_some_and(|name| name != interface.name().as_str())
+                {
+                    return syn::Error::new(
+                        proc_macro2::Span::call_site(),
+                        "Multiple interfaces with the same property name. Please disambiguate.",
+                    )
+                    .to_compile_error()
+                    .into();
+                }
+                interface_name = Some(interface.name().to_string());
+                xml_file_path = Some(path_key.clone());
+            }
+        }
+    }
+
+    let Some(interface_name) = interface_name else {
+        return syn::Error::new(
+            proc_macro2::Span::call_site(),
+            format!("No interface with property '{property_name}' found.{skipped}"),
+        )
+        .to_compile_error()
+        .into();
+    };
+
+    let xml_file_path = xml_file_path.expect("XML file path should be found in search loop.");
+    let xml_file_path = xml_file_path
+        .to_str()
+        .expect("XML file path should be valid UTF-8");
+
+    let test_name = format!("test_{item_name}_type_signature");
+    let test_name = Ident::new(&test_name, proc_macro2::Span::call_site());
+    let item_struct_name = &item_struct.ident;
+    let object_path = args.object_path();
+
+    // A property is a single value, held by the only field.
+    let variant_check = match variant_types {
+        [] => None,
+        [(0, ty)] if item_struct.fields.len() == 1 => Some(variant_check(
+            args,
+            &interface_name,
+            property_name,
+            None,
+            quote!(Property),
+            ty,
+        )),
+        _ => {
+            return syn::Error::new(
+                proc_macro2::Span::call_site(),
+                "The `#[variant_type]` attribute of a property requires a struct with a single field.",
+            )
+            .to_compile_error()
+            .into();
+        }
+    };
+
+    let access_check = args.access.as_ref().map(|access| {
+        let variant = match access.to_string().as_str() {
+            "read" => quote!(Read),
+            "write" => quote!(Write),
+            _ => quote!(ReadWrite),
+        };
+
+        let property_access = args.getter("get_property_access", quote!(#interface_name), quote!(#property_name));
+        quote! {
+            let xml = zbus_lockstep::load_xml(#xml_file_path).expect(concat!("\"", #xml_file_path, "\" expected to be a valid XML file."));
+            let access_from_xml = #property_access.expect("Failed to get property access from XML file.");
+            let declared = zbus_lockstep::PropertyAccess::#variant;
+
+            zbus_lockstep::validate_property_access(
+                #property_name,
+                access_from_xml,
+                declared.read(),
+                declared.write(),
+            ).unwrap_or_else(|e| panic!("{e}"));
+        }
+    });
+
+    let property_type = args.getter(
+        "get_property_type",
+        quote!(#interface_name),
+        quote!(#property_name),
+    );
+    quote! {
+        #item_struct
+
+        #[cfg(test)]
+        #[test]
+        fn #test_name() {
+            use zvariant::Type;
+
+            let xml = zbus_lockstep::load_xml(#xml_file_path).expect(concat!("\"", #xml_file_path, "\" expected to be a valid XML file."));
+            let item_signature_from_xml = #property_type.expect("Failed to get property type from XML file.");
+            let item_signature_from_struct = <#item_struct_name as Type>::SIGNATURE;
+
+            assert_eq!(
+                &item_signature_from_xml,
+                item_signature_from_struct,
+                "{}\nA type that matches the XML:\n\n{}",
+                zbus_lockstep::explain_mismatch(&item_signature_from_xml, item_signature_from_struct),
+                zbus_lockstep::codegen::suggest_type(
+                    xml.as_bytes(),
+                    #interface_name,
+                    #object_path,
+                    &zbus_lockstep::codegen::TypeSource::Property(#property_name.to_string()),
+                    stringify!(#item_struct_name),
+                ).unwrap_or_else(|e| format!("None, {e}\n"))
+            );
+
+            #access_check
+
+            #variant_check
+        }
+    }
+    .into()
+}
+
+/// The names of `args`, `None` for unnamed arguments.
+fn arg_names(args: &[zbus_xml::Arg]) -> Vec<Option<String>> {
+    args.iter()
+        .map(|arg| arg.name().map(str::to_owned))
+        .collect()
+}
+
+/// Check the type held by a variant field against the `VariantType` annotation in XML.
+fn variant_check(
+    args: &ValidateArgs,
+    interface_name: &str,
+    member_name: &str,
+    arg_name: Option<&str>,
+    msg_type: proc_macro2::TokenStream,
+    ty: &syn::Type,
+) -> proc_macro2::TokenStream {
+    let name = arg_name.unwrap_or(member_name);
+    let arg_name = match arg_name {
+        Some(arg_name) => quote!(Some(#arg_name)),
+        None => quote!(None),
+    };
+
+    let variant_type = args.getter(
+        "get_variant_type",
+        quote!(#interface_name),
+        quote!(#member_name, #arg_name, zbus_lockstep::MsgType::#msg_type),
+    );
+    quote! {
+        let variant_type_from_xml = #variant_type.expect("Failed to get variant type from XML file.");
+
+        zbus_lockstep::validate_variant_type(
+            #name,
+            &variant_type_from_xml,
+            <#ty as Type>::SIGNATURE,
+        ).unwrap_or_else(|e| panic!("{e}"));
+    }
+}
+
+/// Generate Rust types for the members of an interface, from its XML definition.
+///
+/// For every signal body, method arguments, method reply and property of the interface a
+/// struct is generated that derives `zvariant::Type`, `serde::Serialize` and
+/// `serde::Deserialize`, along with a test that validates its signature against the XML, like
+/// `#[validate]` does. See `zbus_lockstep::codegen` for the names and shape of the types.
+///
+/// The crate using the macro needs `zvariant` and `serde` as dependencies.
+///
+/// # Arguments
+///
+/// `interface` is required. `xml` is an optional path to the directory of XML files, resolved
+/// like `#[validate]` resolves it.
+///
+/// # Examples
+///
+/// ```ignore
+/// zbus_lockstep_macros::lockstep_types!(interface: "org.example.Node");
+///
+/// // Generated from the `Alert` signal with `urgent`, `color` and `volume` arguments.
+/// let alert = AlertSignal {
+///     urgent: true,
+///     color: "red".to_string(),
+///     volume: 0.5,
+/// };
+/// ```
+#[proc_macro]
+pub fn lockstep_types(input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(input as types::TypesArgs);
+    types::lockstep_types(args)
+}
+
 struct ValidateArgs {
     // Optional path to XML file
     xml: Option<PathBuf>,
@@ -284,6 +905,36 @@ struct ValidateArgs {
 
     // Optional signal name
     signal: Option<String>,
+
+    // Optional object path to limit the search to
+    path: Option<String>,
+
+    // Optional property name
+    property: Option<String>,
+
+    // Optional property access mode: `read`, `write` or `readwrite`
+    access: Option<Ident>,
+
+    // Optional interface name, to validate against all its properties
+    properties_of: Option<String>,
+
+    // Optional argument or property name, to validate against its dictionary schema
+    dict_schema: Option<String>,
+
+    // Optional method or signal name of the `dict_schema` argument
+    member: Option<String>,
+
+    // Optional case conversion from variant names to the names of an enumeration or flag set
+    rename_all: Option<zbus_lockstep::RenameRule>,
+
+    // Optional name of the enumeration to validate an enum against
+    enum_name: Option<String>,
+
+    // Optional name of the flag set to validate an enum or `bitflags!` type against
+    flags: Option<String>,
+
+    // Optional argument or property name, to validate an enum against its allowed values
+    allowed_values: Option<String>,
 }
 
 impl syn::parse::Parse for ValidateArgs {
@@ -291,9 +942,20 @@ impl syn::parse::Parse for ValidateArgs {
         let mut xml = None;
         let mut interface = None;
         let mut signal = None;
+        let mut path = None;
+        let mut property = None;
+        let mut access = None;
+        let mut properties_of = None;
+        let mut dict_schema = None;
+        let mut member = None;
+        let mut rename_all = None;
+        let mut enum_name = None;
+        let mut flags = None;
+        let mut allowed_values = None;
 
         while !input.is_empty() {
-            let ident = input.parse::<Ident>()?;
+            // `enum` is a keyword.
+            let ident = input.call(Ident::parse_any)?;
             match ident.to_string().as_str() {
                 "xml" => {
                     input.parse::<Token![:]>()?;
@@ -310,6 +972,66 @@ impl syn::parse::Parse for ValidateArgs {
                     let lit = input.parse::<LitStr>()?;
                     signal = Some(lit.value());
                 }
+                "path" => {
+                    input.parse::<Token![:]>()?;
+                    let lit = input.parse::<LitStr>()?;
+                    path = Some(lit.value());
+                }
+                "property" => {
+                    input.parse::<Token![:]>()?;
+                    let lit = input.parse::<LitStr>()?;
+                    property = Some(lit.value());
+                }
+                "access" => {
+                    input.parse::<Token![:]>()?;
+                    let mode = input.parse::<Ident>()?;
+                    if !matches!(mode.to_string().as_str(), "read" | "write" | "readwrite") {
+                        return Err(syn::Error::new(
+                            mode.span(),
+                            format!("Unexpected access mode: {mode}, expected `read`, `write` or `readwrite`"),
+                        ));
+                    }
+                    access = Some(mode);
+                }
+                "properties_of" => {
+                    input.parse::<Token![:]>()?;
+                    let lit = input.parse::<LitStr>()?;
+                    properties_of = Some(lit.value());
+                }
+                "dict_schema" => {
+                    input.parse::<Token![:]>()?;
+                    let lit = input.parse::<LitStr>()?;
+                    dict_schema = Some(lit.value());
+                }
+                "member" => {
+                    input.parse::<Token![:]>()?;
+                    let lit = input.parse::<LitStr>()?;
+                    member = Some(lit.value());
+                }
+                "rename_all" => {
+                    input.parse::<Token![:]>()?;
+                    let lit = input.parse::<LitStr>()?;
+                    let rule = lit
+                        .value()
+                        .parse()
+                        .map_err(|e: String| syn::Error::new(lit.span(), e))?;
+                    rename_all = Some(rule);
+                }
+                "enum" => {
+                    input.parse::<Token![:]>()?;
+                    let lit = input.parse::<LitStr>()?;
+                    enum_name = Some(lit.value());
+                }
+                "flags" => {
+                    input.parse::<Token![:]>()?;
+                    let lit = input.parse::<LitStr>()?;
+                    flags = Some(lit.value());
+                }
+                "allowed_values" => {
+                    input.parse::<Token![:]>()?;
+                    let lit = input.parse::<LitStr>()?;
+                    allowed_values = Some(lit.value());
+                }
                 _ => {
                     return Err(syn::Error::new(
                         ident.span(),
@@ -323,10 +1045,146 @@ impl syn::parse::Parse for ValidateArgs {
             }
         }
 
-        Ok(ValidateArgs {
+        let args = ValidateArgs {
             xml,
             interface,
             signal,
+            path,
+            property,
+            access,
+            properties_of,
+            dict_schema,
+            member,
+            rename_all,
+            enum_name,
+            flags,
+            allowed_values,
+        };
+        args.check_modes()?;
+
+        Ok(args)
+    }
+}
+
+impl ValidateArgs {
+    /// The arguments that each name something to validate against, and whether they are given.
+    fn modes(&self) -> Vec<(&'static str, bool)> {
+        vec![
+            ("signal", self.signal.is_some()),
+            ("property", self.property.is_some()),
+            ("properties_of", self.properties_of.is_some()),
+            ("dict_schema", self.dict_schema.is_some()),
+            ("enum", self.enum_name.is_some()),
+            ("flags", self.flags.is_some()),
+            ("allowed_values", self.allowed_values.is_some()),
+        ]
+    }
+
+    /// The argument naming what to validate against, if any.
+    fn mode(&self) -> Option<&'static str> {
+        self.modes()
+            .into_iter()
+            .find_map(|(mode, given)| given.then_some(mode))
+    }
+
+    /// Reject more than one thing to validate against, and arguments that refine one that is
+    /// not given.
+    fn check_modes(&self) -> Result<()> {
+        let error = |message: String| syn::Error::new(proc_macro2::Span::call_site(), message);
+
+        let modes = self.modes();
+        let given: Vec<_> = modes
+            .iter()
+            .filter(|(_, given)| *given)
+            .map(|(mode, _)| format!("`{mode}`"))
+            .collect();
+        if given.len() > 1 {
+            let all: Vec<_> = modes.iter().map(|(mode, _)| format!("`{mode}`")).collect();
+            return Err(error(format!(
+                "Only one of {} can be given, found {}.",
+                all.join(", "),
+                given.join(", ")
+            )));
+        }
+        if self.access.is_some() && self.property.is_none() {
+            return Err(error(
+                "The `access` argument requires a `property` argument.".to_owned(),
+            ));
+        }
+        if self.member.is_some() && self.dict_schema.is_none() && self.allowed_values.is_none() {
+            return Err(error(
+                "The `member` argument requires a `dict_schema` or `allowed_values` argument."
+                    .to_owned(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Whether an interface found at `object_path` is within the requested object path, if any.
+    fn is_at_path(&self, object_path: &str) -> bool {
+        self.path.as_deref().is_none_or(|path| {
+            let path = path.trim_end_matches('/');
+            object_path.trim_end_matches('/') == path
         })
     }
+
+    /// A call of the `zbus_lockstep` getter on `xml` for generated tests, or of its `_at` variant
+    /// given an object path. `rest` are the arguments following the interface name.
+    fn getter(
+        &self,
+        getter: &str,
+        interface: proc_macro2::TokenStream,
+        rest: proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream {
+        match &self.path {
+            Some(path) => {
+                let getter = format_ident!("{getter}_at");
+                quote!(zbus_lockstep::#getter(xml.as_bytes(), #path, #interface, #rest))
+            }
+            None => {
+                let getter = format_ident!("{getter}");
+                quote!(zbus_lockstep::#getter(xml.as_bytes(), #interface, #rest))
+            }
+        }
+    }
+
+    /// The requested object path, if any, as an `Option<&str>` expression for generated tests.
+    fn object_path(&self) -> proc_macro2::TokenStream {
+        match &self.path {
+            Some(path) => quote!(Some(#path)),
+            None => quote!(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_check_modes() {
+        let parses = |args: &str| syn::parse_str::<ValidateArgs>(args).is_ok();
+
+        for valid in [
+            "interface: \"org.example.Node\"",
+            "property: \"Name\", access: read",
+            "dict_schema: \"hints\", member: \"Open\"",
+            "allowed_values: \"mode\", member: \"Open\"",
+        ] {
+            assert!(parses(valid), "\"{valid}\" should parse");
+        }
+        for invalid in [
+            "signal: \"Alert\", property: \"Name\"",
+            "properties_of: \"org.example.Node\", signal: \"Alert\"",
+            "dict_schema: \"hints\", property: \"Name\"",
+            "dict_schema: \"hints\", enum: \"Role\"",
+            "enum: \"Role\", flags: \"State\"",
+            "allowed_values: \"mode\", enum: \"Role\"",
+            "access: read",
+            "signal: \"Alert\", member: \"Open\"",
+        ] {
+            assert!(!parses(invalid), "\"{invalid}\" should not parse");
+        }
+    }
 }
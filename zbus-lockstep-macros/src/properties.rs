@@ -0,0 +1,99 @@
+//! `#[validate(properties_of: ...)]`, for structs holding the properties of an interface.
+
+use std::path::PathBuf;
+
+use quote::quote;
+use syn::{Ident, ItemStruct};
+
+use crate::{
+    fields::{dict_fields, DictStructField},
+    Result, ValidateArgs,
+};
+
+pub(crate) fn validate_properties_of(
+    args: &ValidateArgs,
+    item_struct: &ItemStruct,
+    xml_files: Vec<(PathBuf, Vec<zbus_xml::Node<'static>>)>,
+    skipped: &str,
+) -> Result<proc_macro2::TokenStream> {
+    let error = |message: &str| syn::Error::new(proc_macro2::Span::call_site(), message);
+    let interface_name = args
+        .properties_of
+        .as_deref()
+        .expect("validate_properties_of requires a properties_of argument");
+
+    if args.interface.is_some() {
+        return Err(error(
+            "The `properties_of` argument names the interface, `interface` is not needed.",
+        ));
+    }
+
+    // The first file, in order of their names, that defines the interface.
+    let xml_file_path = xml_files
+        .iter()
+        .find(|(_, nodes)| {
+            nodes
+                .iter()
+                .flat_map(zbus_lockstep::interfaces_with_paths)
+                .any(|(path, interface)| {
+                    args.is_at_path(&path) && interface.name().as_str() == interface_name
+                })
+        })
+        .map(|(path, _)| path)
+        .ok_or_else(|| error(&format!("Interface '{interface_name}' not found.{skipped}")))?;
+    let xml_file_path = xml_file_path
+        .to_str()
+        .expect("XML file path should be valid UTF-8");
+
+    let fields = dict_fields(item_struct, "properties_of")?.into_iter().map(
+        |DictStructField {
+             name,
+             key,
+             ty,
+             optional,
+         }| {
+            quote! {
+                zbus_lockstep::PropertyField {
+                    field: #name,
+                    property: #key,
+                    signature: <#ty as zvariant::Type>::SIGNATURE,
+                    optional: #optional,
+                }
+            }
+        },
+    );
+
+    let item_struct_name = &item_struct.ident;
+    let object_path = args.object_path();
+    let test_name = Ident::new(
+        &format!("test_{item_struct_name}_properties"),
+        proc_macro2::Span::call_site(),
+    );
+
+    Ok(quote! {
+        #item_struct
+
+        #[cfg(test)]
+        #[test]
+        fn #test_name() {
+            use zvariant::Type;
+
+            assert_eq!(
+                <#item_struct_name as Type>::SIGNATURE.to_string(),
+                "a{sv}",
+                "`{}` should have the signature of `GetAll`, e.g. with `#[zvariant(signature = \"dict\")]`.",
+                stringify!(#item_struct_name),
+            );
+
+            let xml = zbus_lockstep::load_xml(#xml_file_path).expect(concat!("\"", #xml_file_path, "\" expected to be a valid XML file."));
+            let fields = [#(#fields),*];
+
+            zbus_lockstep::validate_properties(
+                xml.as_bytes(),
+                #interface_name,
+                #object_path,
+                &fields,
+            ).unwrap_or_else(|e| panic!("{e}"));
+        }
+    })
+}
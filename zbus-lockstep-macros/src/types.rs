@@ -0,0 +1,156 @@
+//! The `lockstep_types!` macro.
+
+use std::path::PathBuf;
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse::ParseStream, Ident, LitStr, Token};
+use zbus_lockstep::codegen::{self, TypeSource};
+
+use crate::Result;
+
+/// Arguments of `lockstep_types!`.
+pub(crate) struct TypesArgs {
+    // Optional path to XML file
+    xml: Option<PathBuf>,
+
+    // Name of the interface to generate types for
+    interface: LitStr,
+}
+
+impl syn::parse::Parse for TypesArgs {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut xml = None;
+        let mut interface = None;
+
+        while !input.is_empty() {
+            let ident = input.parse::<Ident>()?;
+            match ident.to_string().as_str() {
+                "xml" => {
+                    input.parse::<Token![:]>()?;
+                    let lit = input.parse::<LitStr>()?;
+                    xml = Some(PathBuf::from(lit.value()));
+                }
+                "interface" => {
+                    input.parse::<Token![:]>()?;
+                    interface = Some(input.parse::<LitStr>()?);
+                }
+                _ => {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        format!("Unexpected argument: {ident}"),
+                    ))
+                }
+            }
+
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+
+        let interface = interface
+            .ok_or_else(|| syn::Error::new(input.span(), "Missing `interface` argument."))?;
+
+        Ok(TypesArgs { xml, interface })
+    }
+}
+
+pub(crate) fn lockstep_types(args: TypesArgs) -> TokenStream {
+    match expand(&args) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn expand(args: &TypesArgs) -> Result<proc_macro2::TokenStream> {
+    let error = |message: String| syn::Error::new(args.interface.span(), message);
+    let interface_name = args.interface.value();
+
+    let xml_str = args.xml.as_ref().and_then(|p| p.to_str());
+    let xml = zbus_lockstep::resolve_xml_path(xml_str)
+        .map_err(|e| error(format!("Failed to resolve XML path: {e}")))?;
+    let scan = zbus_lockstep::Scanner::new()
+        .scan(&xml)
+        .map_err(|e| error(format!("Failed to read XML directory: {e}")))?;
+
+    // The first file, in order of their names, that defines the interface.
+    let mut found = None;
+    for path in &scan.files {
+        let nodes = zbus_lockstep::load_nodes(path).map_err(|e| {
+            error(format!(
+                "Failed to parse XML file: \"{}\" Err: {e}",
+                path.to_string_lossy()
+            ))
+        })?;
+        let types = nodes
+            .iter()
+            .flat_map(zbus_lockstep::interfaces_with_paths)
+            .find(|(_, interface)| interface.name() == interface_name.as_str())
+            .map(|(_, interface)| codegen::interface_types(interface));
+
+        if let Some(types) = types {
+            found = Some((path.clone(), types.map_err(|e| error(e.to_string()))?));
+            break;
+        }
+    }
+
+    let Some((xml_file_path, types)) = found else {
+        return Err(error(format!("Interface '{interface_name}' not found.")));
+    };
+    let xml_file_path = xml_file_path
+        .to_str()
+        .expect("XML file path should be valid UTF-8");
+
+    let mut tokens = proc_macro2::TokenStream::new();
+    for generated in types {
+        let item: proc_macro2::TokenStream = generated
+            .to_rust()
+            .parse()
+            .map_err(|e| error(format!("Failed to generate `{}`: {e}", generated.name)))?;
+
+        let type_name = Ident::new(&generated.name, proc_macro2::Span::call_site());
+        let test_name = Ident::new(
+            &format!("test_{}_type_signature", generated.name),
+            proc_macro2::Span::call_site(),
+        );
+        let member = generated.source.member();
+        let signature_from_xml = match &generated.source {
+            TypeSource::SignalBody(_) => quote! {
+                zbus_lockstep::get_signal_body_type(xml.as_bytes(), #interface_name, #member, None)
+            },
+            TypeSource::MethodArgs(_) => quote! {
+                zbus_lockstep::get_method_args_type(xml.as_bytes(), #interface_name, #member, None)
+            },
+            TypeSource::MethodReply(_) => quote! {
+                zbus_lockstep::get_method_return_type(xml.as_bytes(), #interface_name, #member, None)
+            },
+            TypeSource::Property(_) => quote! {
+                zbus_lockstep::get_property_type(xml.as_bytes(), #interface_name, #member)
+            },
+        };
+
+        tokens.extend(quote! {
+            #item
+
+            #[cfg(test)]
+            #[test]
+            fn #test_name() {
+                use zvariant::Type;
+
+                let xml = zbus_lockstep::load_xml(#xml_file_path).expect(concat!("\"", #xml_file_path, "\" expected to be a valid XML file."));
+                let item_signature_from_xml = #signature_from_xml
+                    .expect("Failed to get type signature from XML file.");
+                let item_signature_from_type = <#type_name as Type>::SIGNATURE;
+
+                assert_eq!(
+                    &item_signature_from_xml,
+                    item_signature_from_type,
+                    "{}",
+                    zbus_lockstep::explain_mismatch(&item_signature_from_xml, item_signature_from_type)
+                );
+            }
+        });
+    }
+
+    Ok(tokens)
+}
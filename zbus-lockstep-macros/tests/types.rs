@@ -0,0 +1,43 @@
+use zbus_lockstep_macros::lockstep_types;
+
+lockstep_types!(interface: "org.example.Node");
+
+// `Turn` has arguments without a direction, which are input arguments.
+lockstep_types!(interface: "org.example.Dial");
+
+// The generated tests run along with the others.
+mod with_xml_path {
+    zbus_lockstep_macros::lockstep_types!(xml: "./xml", interface: "org.example.Node");
+}
+
+#[test]
+fn generated_types_match_xml() {
+    test_AddNodeSignal_type_signature();
+    test_AlertSignal_type_signature();
+    test_RemoveNodeSignal_type_signature();
+    test_RequestNameArgs_type_signature();
+    test_RequestNameReply_type_signature();
+    test_FeaturesProperty_type_signature();
+
+    test_TurnArgs_type_signature();
+    test_TurnReply_type_signature();
+}
+
+#[test]
+fn generated_types_are_usable() {
+    let alert = AlertSignal {
+        urgent: true,
+        color: "red".to_string(),
+        volume: 0.5,
+    };
+    assert!(alert.urgent);
+
+    let args = RequestNameArgs {
+        apple: "org.example".to_string(),
+        orange: 4,
+    };
+    assert_eq!(args.orange, 4);
+
+    let features = FeaturesProperty(vec!["a11y".to_string()]);
+    assert_eq!(features.0.len(), 1);
+}
@@ -1,8 +1,10 @@
 // tests/attribute_macro.rs
 #![allow(unnameable_test_items)]
 
+use bitflags::bitflags;
+use serde::{Deserialize, Serialize};
 use zbus_lockstep_macros::validate;
-use zvariant::{OwnedObjectPath, Type};
+use zvariant::{OwnedObjectPath, OwnedValue, Type};
 
 #[test]
 fn test_validate_macro_node_add_path_as_env_variable() {
@@ -92,3 +94,415 @@ fn test_validate_macro_non_matching_signature_of_type() {
     // now call the test generated by the `validate` macro
     test_RemoveNode_type_signature();
 }
+
+#[test]
+fn test_validate_macro_property() {
+    #[validate(property: "Features")]
+    #[derive(Debug, Type)]
+    struct Features(#[allow(dead_code)] Vec<String>);
+
+    // now call the test generated by the `validate` macro
+    test_Features_type_signature();
+}
+
+#[test]
+fn test_validate_macro_property_with_access() {
+    #[validate(property: "Features", access: read)]
+    #[derive(Debug, Type)]
+    struct Features(#[allow(dead_code)] Vec<String>);
+
+    // now call the test generated by the `validate` macro
+    test_Features_type_signature();
+}
+
+#[should_panic]
+#[test]
+fn test_validate_macro_property_with_non_matching_access() {
+    #[validate(property: "Features", access: readwrite)]
+    #[derive(Debug, Type)]
+    struct Features(#[allow(dead_code)] Vec<String>);
+
+    // now call the test generated by the `validate` macro
+    test_Features_type_signature();
+}
+
+#[test]
+fn test_validate_macro_signal_of_child_node_with_path() {
+    #[validate(signal: "BranchGrown", path: "/org/example/tree/leaf")]
+    #[derive(Debug, Type)]
+    struct Growth {
+        _branch: (String, OwnedObjectPath),
+        _length: u32,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_Growth_type_signature();
+}
+
+#[test]
+fn test_validate_macro_with_path_of_differing_implementations() {
+    // `org.example.Lamp` has a `Switched` signal and a `Glow` property at two object paths,
+    // with different types.
+    #[validate(signal: "Switched", path: "/org/example/lamp/desk")]
+    #[derive(Debug, Type)]
+    struct DeskSwitched {
+        _on: bool,
+        _reason: String,
+    }
+
+    #[validate(signal: "Switched", path: "/org/example/lamp/ceiling")]
+    #[derive(Debug, Type)]
+    struct CeilingSwitched {
+        _on: bool,
+        _level: u32,
+    }
+
+    #[validate(property: "Glow", access: read, path: "/org/example/lamp/desk")]
+    #[derive(Debug, Type)]
+    struct DeskGlow(#[allow(dead_code)] f64);
+
+    // now call the tests generated by the `validate` macro
+    test_DeskSwitched_type_signature();
+    test_CeilingSwitched_type_signature();
+    test_DeskGlow_type_signature();
+}
+
+#[test]
+fn test_validate_macro_property_from_included_file() {
+    #[validate(property: "Leaves", access: read)]
+    #[derive(Debug, Type)]
+    struct Leaves(#[allow(dead_code)] u32);
+
+    // now call the test generated by the `validate` macro
+    test_Leaves_type_signature();
+}
+
+#[test]
+#[should_panic(expected = "In XML:\n`(so)`: struct (string, object path)")]
+fn test_validate_macro_mismatch_explains_signatures() {
+    #[validate(xml: "./xml", signal: "RemoveNode")]
+    #[derive(Debug, Type)]
+    struct MisspelledRemoveNodeEvent {
+        _name: String,
+        _path: String,
+    }
+
+    test_MisspelledRemoveNodeEvent_type_signature();
+}
+
+#[test]
+#[should_panic(expected = "pub struct MistypedFeatures(pub Vec<String>);")]
+fn test_validate_macro_mismatch_suggests_type() {
+    #[validate(xml: "./xml", property: "Features")]
+    #[derive(Debug, Type)]
+    struct MistypedFeatures(#[allow(dead_code)] Vec<u32>);
+
+    test_MistypedFeatures_type_signature();
+}
+
+#[test]
+fn test_validate_macro_properties_of() {
+    #[validate(properties_of: "org.example.Device")]
+    #[derive(Debug, Deserialize, Type)]
+    #[serde(rename_all = "PascalCase")]
+    #[zvariant(signature = "dict")]
+    #[allow(dead_code)]
+    struct DeviceProperties {
+        name: String,
+        brightness: Option<u32>,
+        power_saving: bool,
+    }
+
+    test_DeviceProperties_properties();
+}
+
+#[test]
+fn test_validate_macro_properties_of_with_rename() {
+    #[validate(properties_of: "org.example.Device")]
+    #[derive(Debug, Deserialize, Type)]
+    #[serde(rename_all = "PascalCase")]
+    #[zvariant(signature = "dict")]
+    #[allow(dead_code)]
+    struct DeviceProperties {
+        name: String,
+        #[serde(rename = "Brightness")]
+        level: u32,
+        power_saving: Option<bool>,
+    }
+
+    test_DeviceProperties_properties();
+}
+
+#[test]
+#[should_panic(expected = "Property \"PowerSaving\" of type \"b\" has no field.")]
+fn test_validate_macro_properties_of_missing_property() {
+    #[validate(properties_of: "org.example.Device")]
+    #[derive(Debug, Deserialize, Type)]
+    #[serde(rename_all = "PascalCase")]
+    #[zvariant(signature = "dict")]
+    #[allow(dead_code)]
+    struct PartialDeviceProperties {
+        name: String,
+        brightness: u32,
+    }
+
+    test_PartialDeviceProperties_properties();
+}
+
+#[test]
+#[should_panic(expected = "Field \"name\" is optional, but property \"Name\" is const.")]
+fn test_validate_macro_properties_of_optional_const_property() {
+    #[validate(properties_of: "org.example.Device")]
+    #[derive(Debug, Deserialize, Type)]
+    #[serde(rename_all = "PascalCase")]
+    #[zvariant(signature = "dict")]
+    #[allow(dead_code)]
+    struct LooseDeviceProperties {
+        name: Option<String>,
+        brightness: u32,
+        power_saving: bool,
+    }
+
+    test_LooseDeviceProperties_properties();
+}
+
+#[test]
+fn test_validate_macro_dict_schema() {
+    #[validate(dict_schema: "hints", member: "Notify")]
+    #[derive(Debug, Deserialize, Type)]
+    #[serde(rename_all = "kebab-case")]
+    #[zvariant(signature = "dict")]
+    #[allow(dead_code)]
+    struct Hints {
+        urgency: Option<u8>,
+        image_path: Option<String>,
+    }
+
+    test_Hints_dict_schema();
+}
+
+#[test]
+#[should_panic(expected = "Field \"urgency\" has type \"u\", but key \"urgency\" has type \"y\".")]
+fn test_validate_macro_dict_schema_mistyped_key() {
+    #[validate(dict_schema: "hints")]
+    #[derive(Debug, Deserialize, Type)]
+    #[zvariant(signature = "dict")]
+    #[allow(dead_code)]
+    struct MistypedHints {
+        urgency: Option<u32>,
+    }
+
+    test_MistypedHints_dict_schema();
+}
+
+#[test]
+fn test_validate_macro_variant_type_of_signal_arg() {
+    #[validate(signal: "BoundsChanged")]
+    #[derive(Debug, Type)]
+    struct Bounds {
+        _detail: String,
+        #[variant_type((i32, i32, i32, i32))]
+        _bounds: OwnedValue,
+    }
+
+    test_Bounds_type_signature();
+}
+
+#[test]
+fn test_validate_macro_variant_type_of_property() {
+    #[validate(property: "Opacity")]
+    #[derive(Debug, Type)]
+    struct Opacity(
+        #[variant_type(f64)]
+        #[allow(dead_code)]
+        OwnedValue,
+    );
+
+    test_Opacity_type_signature();
+}
+
+#[test]
+#[should_panic(
+    expected = "Variant \"bounds\" holds \"(iiii)\" in XML, but \"(uu)\" in the implementation."
+)]
+fn test_validate_macro_variant_type_mismatch() {
+    #[validate(signal: "BoundsChanged")]
+    #[derive(Debug, Type)]
+    struct Size {
+        _detail: String,
+        #[variant_type((u32, u32))]
+        _bounds: OwnedValue,
+    }
+
+    test_Size_type_signature();
+}
+
+#[test]
+fn test_validate_macro_enum() {
+    #[validate]
+    #[allow(dead_code)]
+    #[repr(u32)]
+    enum Role {
+        Invalid,
+        TitleBar,
+        MenuBar,
+        ScrollBar,
+    }
+
+    test_Role_enumeration();
+}
+
+#[test]
+fn test_validate_macro_enum_with_rename_all() {
+    #[validate(enum: "Layer", interface: "org.example.Accessible", rename_all: "lowercase")]
+    #[allow(dead_code)]
+    enum ComponentLayer {
+        Invalid,
+        Widget = 3,
+        Mdi,
+        Popup = 16,
+    }
+
+    test_ComponentLayer_enumeration();
+}
+
+#[test]
+fn test_validate_macro_enum_with_path() {
+    // `org.example.Lamp` declares another `Mode` enumeration at each object path.
+    #[validate(enum: "Mode", path: "/org/example/lamp/desk")]
+    #[allow(dead_code)]
+    enum DeskMode {
+        Off,
+        On,
+        Reading,
+    }
+
+    test_DeskMode_enumeration();
+}
+
+#[test]
+#[should_panic(expected = "Variant \"ScrollBar\" is 3 in XML, but 4 in the implementation.")]
+fn test_validate_macro_enum_mismatch() {
+    #[validate(enum: "Role")]
+    #[allow(dead_code)]
+    #[repr(u32)]
+    enum RenumberedRole {
+        Invalid,
+        TitleBar,
+        MenuBar,
+        ScrollBar = 4,
+    }
+
+    test_RenumberedRole_enumeration();
+}
+
+#[test]
+fn test_validate_macro_flags_of_enum() {
+    #[validate(flags: "State")]
+    #[allow(dead_code)]
+    #[repr(u64)]
+    enum AccessibleState {
+        Invalid = 1 << 0,
+        Active = 1 << 1,
+        Armed = 1 << 2,
+        Busy = 1 << 5,
+    }
+
+    test_AccessibleState_flags();
+}
+
+#[test]
+fn test_validate_macro_flags_of_enumflags2() {
+    #[validate(flags: "State")]
+    #[enumflags2::bitflags]
+    #[repr(u64)]
+    #[derive(Clone, Copy)]
+    #[allow(dead_code)]
+    enum BitflagsState {
+        Invalid = 1 << 0,
+        Active = 1 << 1,
+        Armed = 1 << 2,
+        Busy = 1 << 5,
+    }
+
+    test_BitflagsState_flags();
+}
+
+#[test]
+#[should_panic(expected = "Flag \"Busy\" is 1 << 5 in XML, but 0x8 in the implementation.")]
+fn test_validate_macro_flags_mismatch() {
+    #[validate(flags: "State", interface: "org.example.Accessible")]
+    #[allow(dead_code)]
+    #[repr(u64)]
+    enum ShiftedState {
+        Invalid = 1 << 0,
+        Active = 1 << 1,
+        Armed = 1 << 2,
+        Busy = 1 << 3,
+    }
+
+    test_ShiftedState_flags();
+}
+
+#[test]
+fn test_validate_macro_allowed_values_of_property() {
+    #[validate(allowed_values: "Access")]
+    #[derive(Serialize, Type)]
+    #[serde(rename_all = "lowercase")]
+    #[zvariant(signature = "s")]
+    #[allow(dead_code)]
+    enum Access {
+        Read,
+        Write,
+        ReadWrite,
+    }
+
+    test_Access_allowed_values();
+}
+
+#[test]
+fn test_validate_macro_allowed_values_of_arg() {
+    #[validate(allowed_values: "relation", member: "AddRelation")]
+    #[derive(Serialize, Type)]
+    #[serde(rename_all = "kebab-case")]
+    #[zvariant(signature = "s")]
+    #[allow(dead_code)]
+    enum RelationType {
+        LabelFor,
+        LabelledBy,
+        ControllerFor,
+    }
+
+    test_RelationType_allowed_values();
+}
+
+#[test]
+#[should_panic(
+    expected = "Variant \"ReadWrite\" serializes to \"read-write\", which is not allowed."
+)]
+fn test_validate_macro_allowed_values_mismatch() {
+    #[validate(allowed_values: "Access")]
+    #[derive(Serialize, Type)]
+    #[serde(rename_all = "kebab-case")]
+    #[zvariant(signature = "s")]
+    #[allow(dead_code)]
+    enum KebabAccess {
+        Read,
+        Write,
+        ReadWrite,
+    }
+
+    test_KebabAccess_allowed_values();
+}
+
+// Attributes on macro invocations are only allowed on items, the generated test runs on its own.
+#[validate(flags: "State")]
+bitflags! {
+    struct StateSet: u64 {
+        const Invalid = 1 << 0;
+        const Active = 1 << 1;
+        const Armed = 1 << 2;
+        const Busy = 1 << 5;
+        const Ready = Self::Active.bits() | Self::Armed.bits();
+    }
+}
@@ -0,0 +1,252 @@
+//! The values allowed for a string, declared with an annotation.
+//!
+//! Some `s` arguments and properties hold one of a fixed set of strings, such as an access
+//! mode, `"read"` or `"write"`, or the name of a relation. The [`ALLOWED_VALUES`] annotation
+//! lists them, so an enum serialized as a string, e.g. with `#[zvariant(signature = "s")]`, can
+//! be kept in lockstep with the XML.
+
+use std::{fmt, io::Read, str::FromStr};
+
+use crate::{
+    annotations::ListAnnotation,
+    LockstepError::{self, AllowedValuesMismatch},
+    MsgType, Result,
+};
+
+/// Name of the annotation that lists the values allowed for a string.
+///
+/// The value is a list of the allowed strings, separated by whitespace or commas, e.g.
+/// `read write readwrite`.
+pub const ALLOWED_VALUES: &str = "org.zbus.lockstep.AllowedValues";
+
+/// The values allowed for a string argument or property.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct AllowedValues {
+    values: Vec<String>,
+}
+
+impl AllowedValues {
+    /// The allowed values, in the order of the annotation.
+    pub fn values(&self) -> impl Iterator<Item = &str> {
+        self.values.iter().map(String::as_str)
+    }
+
+    /// Whether `value` is allowed.
+    pub fn contains(&self, value: &str) -> bool {
+        self.values.iter().any(|allowed| allowed == value)
+    }
+
+    /// Check the variants of an enum, named `enum_name`, against the allowed values.
+    ///
+    /// `variants` holds the name of each variant and the string it serializes to. The strings
+    /// must match the allowed values exactly: values no variant serializes to, strings that are
+    /// not allowed and variants sharing a string are reported.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use zbus_lockstep::AllowedValues;
+    ///
+    /// let allowed: AllowedValues = "read write".parse().unwrap();
+    ///
+    /// assert!(allowed
+    ///     .validate("Access", &[("Read", "read"), ("Write", "write")])
+    ///     .is_ok());
+    /// assert!(allowed
+    ///     .validate("Access", &[("Read", "read"), ("Write", "Write")])
+    ///     .is_err());
+    /// ```
+    pub fn validate(
+        &self,
+        enum_name: &str,
+        variants: &[(&str, &str)],
+    ) -> std::result::Result<(), LockstepError> {
+        let mut problems = Vec::new();
+        for value in self.values() {
+            if !variants.iter().any(|(_, used)| *used == value) {
+                problems.push(format!("No variant serializes to \"{value}\"."));
+            }
+        }
+        for (index, (name, used)) in variants.iter().enumerate() {
+            if !self.contains(used) {
+                problems.push(format!(
+                    "Variant \"{name}\" serializes to \"{used}\", which is not allowed."
+                ));
+            }
+            if let Some((other, _)) = variants[..index].iter().find(|(_, other)| other == used) {
+                problems.push(format!(
+                    "Variant \"{name}\" serializes to \"{used}\", as does variant \"{other}\"."
+                ));
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(AllowedValuesMismatch(enum_name.to_owned(), problems))
+        }
+    }
+}
+
+impl ListAnnotation for AllowedValues {
+    const NAME: &'static str = ALLOWED_VALUES;
+
+    type Item = String;
+
+    fn parse_item(value: &str, _: &[Self::Item]) -> std::result::Result<Self::Item, String> {
+        Ok(value.to_owned())
+    }
+
+    fn item_name(value: &Self::Item) -> &str {
+        value
+    }
+
+    fn fmt_item(value: &Self::Item, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(value)
+    }
+
+    fn items(&self) -> &[Self::Item] {
+        &self.values
+    }
+
+    fn from_items(values: Vec<Self::Item>) -> std::result::Result<Self, String> {
+        if values.is_empty() {
+            return Err("No values".to_owned());
+        }
+        Ok(AllowedValues { values })
+    }
+}
+
+impl FromStr for AllowedValues {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Self::parse_list(s)
+    }
+}
+
+impl fmt::Display for AllowedValues {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_list(f)
+    }
+}
+
+/// Retrieve the values allowed for an `s` argument or property, from its [`ALLOWED_VALUES`]
+/// annotation.
+///
+/// For a method or signal, `arg_name` names the argument. Without it, the first argument with
+/// allowed values is used. For a property, `arg_name` is ignored.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::{get_allowed_values, MsgType};
+///
+/// let xml = r#"
+/// <node>
+/// <interface name="org.example.Files">
+///   <method name="Open">
+///     <arg name="path" type="s" direction="in"/>
+///     <arg name="mode" type="s" direction="in">
+///       <annotation name="org.zbus.lockstep.AllowedValues" value="read write"/>
+///     </arg>
+///   </method>
+/// </interface>
+/// </node>
+/// "#;
+///
+/// let allowed = get_allowed_values(
+///     xml.as_bytes(),
+///     "org.example.Files",
+///     "Open",
+///     Some("mode"),
+///     MsgType::Method,
+/// )
+/// .unwrap();
+///
+/// assert!(allowed.contains("write"));
+/// assert_eq!(allowed.to_string(), "read write");
+/// ```
+pub fn get_allowed_values(
+    xml: impl Read,
+    interface_name: &str,
+    member_name: &str,
+    arg_name: Option<&str>,
+    msg_type: MsgType,
+) -> Result<AllowedValues> {
+    AllowedValues::read_annotated(
+        xml,
+        interface_name,
+        None,
+        member_name,
+        arg_name,
+        msg_type,
+        "s",
+    )
+}
+
+/// Like [`get_allowed_values`], from the interface at `object_path`.
+pub fn get_allowed_values_at(
+    xml: impl Read,
+    object_path: &str,
+    interface_name: &str,
+    member_name: &str,
+    arg_name: Option<&str>,
+    msg_type: MsgType,
+) -> Result<AllowedValues> {
+    AllowedValues::read_annotated(
+        xml,
+        interface_name,
+        Some(object_path),
+        member_name,
+        arg_name,
+        msg_type,
+        "s",
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_allowed_values() {
+        let allowed: AllowedValues = "read, write\n  readwrite".parse().unwrap();
+        assert_eq!(allowed.to_string(), "read write readwrite");
+        assert!(allowed.contains("readwrite"));
+        assert!(!allowed.contains("Read"));
+
+        for invalid in ["", " , "] {
+            assert!(
+                invalid.parse::<AllowedValues>().is_err(),
+                "\"{invalid}\" should not parse"
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_allowed_values() {
+        let allowed: AllowedValues = "read write readwrite".parse().unwrap();
+        let variants = [
+            ("Read", "read"),
+            ("Write", "Write"),
+            ("ReadWrite", "readwrite"),
+            ("ReadOnly", "read"),
+        ];
+
+        match allowed.validate("Access", &variants) {
+            Err(AllowedValuesMismatch(name, problems)) => {
+                assert_eq!(name, "Access");
+                assert_eq!(
+                    problems,
+                    [
+                        "No variant serializes to \"write\".",
+                        "Variant \"Write\" serializes to \"Write\", which is not allowed.",
+                        "Variant \"ReadOnly\" serializes to \"read\", as does variant \"Read\".",
+                    ]
+                );
+            }
+            result => panic!("Unexpected result: {result:?}"),
+        }
+    }
+}
@@ -0,0 +1,432 @@
+//! Well-known `DBus` annotations that affect how members are used on the bus.
+
+use std::{fmt, io::Read, str::FromStr};
+
+use zbus_xml::{Annotation, Interface};
+use zvariant::Signature;
+
+use crate::{
+    lint::split_signature,
+    load, tree,
+    LockstepError::{self, ArgumentNotFound, MemberNotFound, PropertyNotFound},
+    MsgType,
+};
+
+/// Name of the annotation that tells how a property emits `PropertiesChanged`.
+pub const EMITS_CHANGED_SIGNAL: &str = "org.freedesktop.DBus.Property.EmitsChangedSignal";
+
+/// Name of the annotation that marks a method as not expecting a reply.
+pub const NO_REPLY: &str = "org.freedesktop.DBus.Method.NoReply";
+
+/// Name of the annotation that declares the concrete type of a variant argument or property.
+///
+/// Specifications often fix the type of the value a `v` carries by convention, e.g.
+/// `<annotation name="org.zbus.lockstep.VariantType" value="(iiii)"/>`.
+pub const VARIANT_TYPE: &str = "org.zbus.lockstep.VariantType";
+
+/// The value of the `org.freedesktop.DBus.Property.EmitsChangedSignal` annotation.
+///
+/// The annotation tells clients whether, and how, a property's changes are announced through
+/// `org.freedesktop.DBus.Properties.PropertiesChanged`. If the annotation is absent on both the
+/// property and its interface, the value is `true`.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum EmitsChangedSignal {
+    /// The signal is emitted with the new value.
+    #[default]
+    True,
+    /// The signal is emitted, but the value is not included.
+    Invalidates,
+    /// The property never changes during the lifetime of the object.
+    Const,
+    /// The signal is not emitted.
+    False,
+}
+
+impl EmitsChangedSignal {
+    /// The effective value given the annotations of a property and those of its interface.
+    ///
+    /// An annotation on the property takes precedence over one on the interface.
+    pub fn from_annotations(
+        property: &[Annotation],
+        interface: &[Annotation],
+    ) -> Result<Self, LockstepError> {
+        property
+            .iter()
+            .chain(interface)
+            .find(|annotation| annotation.name() == EMITS_CHANGED_SIGNAL)
+            .map_or(Ok(Self::default()), |annotation| annotation.value().parse())
+    }
+
+    /// The value as written in XML and in zbus' `emits_changed_signal` property attribute.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EmitsChangedSignal::True => "true",
+            EmitsChangedSignal::Invalidates => "invalidates",
+            EmitsChangedSignal::Const => "const",
+            EmitsChangedSignal::False => "false",
+        }
+    }
+}
+
+impl FromStr for EmitsChangedSignal {
+    type Err = LockstepError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "true" => Ok(EmitsChangedSignal::True),
+            "invalidates" => Ok(EmitsChangedSignal::Invalidates),
+            "const" => Ok(EmitsChangedSignal::Const),
+            "false" => Ok(EmitsChangedSignal::False),
+            _ => Err(LockstepError::InvalidAnnotationValue(
+                EMITS_CHANGED_SIGNAL.to_owned(),
+                s.to_owned(),
+            )),
+        }
+    }
+}
+
+impl fmt::Display for EmitsChangedSignal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Whether the `org.freedesktop.DBus.Method.NoReply` annotation is set to `true`.
+pub(crate) fn no_reply(annotations: &[Annotation]) -> Result<bool, LockstepError> {
+    match annotations
+        .iter()
+        .find(|annotation| annotation.name() == NO_REPLY)
+        .map(Annotation::value)
+    {
+        None | Some("false") => Ok(false),
+        Some("true") => Ok(true),
+        Some(value) => Err(LockstepError::InvalidAnnotationValue(
+            NO_REPLY.to_owned(),
+            value.to_owned(),
+        )),
+    }
+}
+
+/// The type declared by the [`VARIANT_TYPE`] annotation, if any.
+pub(crate) fn variant_type(annotations: &[Annotation]) -> Result<Option<Signature>, LockstepError> {
+    let Some(value) = annotations
+        .iter()
+        .find(|annotation| annotation.name() == VARIANT_TYPE)
+        .map(Annotation::value)
+    else {
+        return Ok(None);
+    };
+
+    // A variant holds a single complete type.
+    let invalid =
+        || LockstepError::InvalidAnnotationValue(VARIANT_TYPE.to_owned(), value.to_owned());
+    if !split_signature(value).is_ok_and(|types| types.len() == 1) {
+        return Err(invalid());
+    }
+    Signature::from_str(value).map(Some).map_err(|_| invalid())
+}
+
+/// An annotation whose value is a list of named items, separated by whitespace or commas.
+///
+/// Implemented by the types of the lists declared by lockstep's own annotations, which share
+/// how they are parsed, looked up and displayed.
+pub(crate) trait ListAnnotation: Sized {
+    /// The name of the annotation or, for lists declared on an interface under a name, the
+    /// prefix followed by that name.
+    const NAME: &'static str;
+
+    type Item;
+
+    /// Parse an item, which follows `previous` in the list.
+    fn parse_item(item: &str, previous: &[Self::Item]) -> Result<Self::Item, String>;
+
+    /// The name of an item, unique in the list.
+    fn item_name(item: &Self::Item) -> &str;
+
+    fn fmt_item(item: &Self::Item, f: &mut fmt::Formatter<'_>) -> fmt::Result;
+
+    fn items(&self) -> &[Self::Item];
+
+    fn from_items(items: Vec<Self::Item>) -> Result<Self, String>;
+
+    /// Parse the value of the annotation.
+    fn parse_list(value: &str) -> Result<Self, String> {
+        let mut items: Vec<Self::Item> = Vec::new();
+        for item in value
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|item| !item.is_empty())
+        {
+            let parsed = Self::parse_item(item, &items)?;
+            let name = Self::item_name(&parsed);
+            if name.is_empty() {
+                return Err(format!("No name in \"{item}\""));
+            }
+            if items.iter().any(|other| Self::item_name(other) == name) {
+                return Err(format!("Duplicate \"{name}\""));
+            }
+            items.push(parsed);
+        }
+        Self::from_items(items)
+    }
+
+    /// Write the items, separated by spaces.
+    fn fmt_list(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, item) in self.items().iter().enumerate() {
+            if index > 0 {
+                f.write_str(" ")?;
+            }
+            Self::fmt_item(item, f)?;
+        }
+        Ok(())
+    }
+
+    /// The list in the annotation named [`Self::NAME`], if any.
+    fn from_annotations(annotations: &[Annotation]) -> Result<Option<Self>, LockstepError> {
+        find_list(annotations, |name| name == Self::NAME)
+    }
+
+    /// The list named `name` declared on `interface`, if any.
+    fn from_interface(
+        interface: &Interface<'_>,
+        name: &str,
+    ) -> Result<Option<Self>, LockstepError> {
+        find_list(interface.annotations(), |annotation| {
+            annotation.strip_prefix(Self::NAME) == Some(name)
+        })
+    }
+
+    /// Read the list of an argument or property, which must be of type `ty`.
+    ///
+    /// Arguments are looked up like [`find_annotated`] does.
+    fn read_annotated(
+        mut xml: impl Read,
+        interface_name: &str,
+        object_path: Option<&str>,
+        member_name: &str,
+        arg_name: Option<&str>,
+        msg_type: MsgType,
+        ty: &str,
+    ) -> crate::Result<Self> {
+        let nodes = load::read_nodes(&mut xml)?;
+        let (_, interface) = tree::find_interface_in(&nodes, interface_name, object_path)?;
+
+        let (declared, annotations) =
+            find_annotated(interface, member_name, arg_name, msg_type, Self::NAME)?;
+        let list = Self::from_annotations(annotations)?.ok_or(format!(
+            "\"{member_name}\" has no {} annotation.",
+            Self::NAME
+        ))?;
+        if declared != ty {
+            return Err(format!(
+                "\"{member_name}\" has the {} annotation, but type \"{declared}\" instead of \"{ty}\".",
+                Self::NAME
+            )
+            .into());
+        }
+        Ok(list)
+    }
+
+    /// Read the list named `name` declared on an interface, where `kind` tells what it is in
+    /// errors.
+    fn read_declared(
+        mut xml: impl Read,
+        interface_name: &str,
+        object_path: Option<&str>,
+        name: &str,
+        kind: &str,
+    ) -> crate::Result<Self> {
+        let nodes = load::read_nodes(&mut xml)?;
+        let (_, interface) = tree::find_interface_in(&nodes, interface_name, object_path)?;
+
+        Ok(Self::from_interface(interface, name)?.ok_or(format!(
+            "Interface \"{interface_name}\" declares no {kind} \"{name}\"."
+        ))?)
+    }
+}
+
+/// The list in the first annotation whose name matches, if any.
+fn find_list<L: ListAnnotation>(
+    annotations: &[Annotation],
+    matches: impl Fn(&str) -> bool,
+) -> Result<Option<L>, LockstepError> {
+    annotations
+        .iter()
+        .find(|annotation| matches(annotation.name()))
+        .map(|annotation| {
+            L::parse_list(annotation.value()).map_err(|_| {
+                LockstepError::InvalidAnnotationValue(
+                    annotation.name().to_owned(),
+                    annotation.value().to_owned(),
+                )
+            })
+        })
+        .transpose()
+}
+
+/// The type and the annotations of an argument or a property.
+///
+/// For a method or signal, `arg_name` names the argument. Without it, the first argument with
+/// `annotation` is used. For a property, `arg_name` is ignored.
+pub(crate) fn find_annotated<'i>(
+    interface: &'i Interface<'_>,
+    member_name: &str,
+    arg_name: Option<&str>,
+    msg_type: MsgType,
+    annotation: &str,
+) -> crate::Result<(String, &'i [Annotation])> {
+    let args = match msg_type {
+        MsgType::Property => {
+            let properties = interface.properties();
+            let property = properties
+                .iter()
+                .find(|property| property.name() == member_name)
+                .ok_or(PropertyNotFound(member_name.to_owned()))?;
+            return Ok((property.ty().to_string(), property.annotations()));
+        }
+        MsgType::Method => {
+            let methods = interface.methods();
+            let method = methods
+                .iter()
+                .find(|method| method.name() == member_name)
+                .ok_or(MemberNotFound(member_name.to_owned()))?;
+            method.args()
+        }
+        MsgType::Signal => {
+            let signals = interface.signals();
+            let signal = signals
+                .iter()
+                .find(|signal| signal.name() == member_name)
+                .ok_or(MemberNotFound(member_name.to_owned()))?;
+            signal.args()
+        }
+    };
+
+    let arg = match arg_name {
+        Some(arg_name) => args
+            .iter()
+            .find(|arg| arg.name() == Some(arg_name))
+            .ok_or(ArgumentNotFound(arg_name.to_owned()))?,
+        None => args
+            .iter()
+            .find(|arg| arg.annotations().iter().any(|a| a.name() == annotation))
+            .ok_or(format!(
+                "No argument of \"{member_name}\" has a {annotation} annotation."
+            ))?,
+    };
+
+    Ok((arg.ty().to_string(), arg.annotations()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Enumeration;
+
+    /// A list of names, to test what all lists share.
+    #[derive(Debug, PartialEq)]
+    struct Names(Vec<String>);
+
+    impl ListAnnotation for Names {
+        const NAME: &'static str = "org.example.Names";
+
+        type Item = String;
+
+        fn parse_item(name: &str, _: &[Self::Item]) -> Result<Self::Item, String> {
+            Ok(name.trim_start_matches('=').to_owned())
+        }
+
+        fn item_name(name: &Self::Item) -> &str {
+            name
+        }
+
+        fn fmt_item(name: &Self::Item, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(name)
+        }
+
+        fn items(&self) -> &[Self::Item] {
+            &self.0
+        }
+
+        fn from_items(names: Vec<Self::Item>) -> Result<Self, String> {
+            Ok(Names(names))
+        }
+    }
+
+    impl fmt::Display for Names {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            self.fmt_list(f)
+        }
+    }
+
+    #[test]
+    fn test_parse_list() {
+        let names = Names::parse_list("a, b\n  c,,d").unwrap();
+        assert_eq!(names.to_string(), "a b c d");
+        assert_eq!(Names::parse_list(" , ").unwrap(), Names(Vec::new()));
+
+        assert_eq!(
+            Names::parse_list("a b a"),
+            Err("Duplicate \"a\"".to_string())
+        );
+        assert_eq!(
+            Names::parse_list("a ="),
+            Err("No name in \"=\"".to_string())
+        );
+    }
+
+    #[test]
+    fn test_read_lists() {
+        let xml = r#"
+<node>
+  <interface name="org.example.Lamp">
+    <annotation name="org.zbus.lockstep.Enum.Colors" value="Red Green"/>
+    <annotation name="org.zbus.lockstep.Enum.Broken" value="Red Red"/>
+    <method name="Paint">
+      <arg name="brush" type="u" direction="in"/>
+      <arg name="color" type="s" direction="in">
+        <annotation name="org.example.Names" value="red green"/>
+      </arg>
+    </method>
+    <property name="Color" type="u" access="read">
+      <annotation name="org.example.Names" value="red green"/>
+    </property>
+  </interface>
+</node>"#;
+        // Lists declared on interfaces are named after a prefix, as for enumerations.
+        let declared = |name| {
+            Enumeration::read_declared(xml.as_bytes(), "org.example.Lamp", None, name, "list")
+                .map(|names| names.to_string())
+                .map_err(|e| e.to_string())
+        };
+        let annotated = |member, msg_type| {
+            Names::read_annotated(
+                xml.as_bytes(),
+                "org.example.Lamp",
+                None,
+                member,
+                None,
+                msg_type,
+                "s",
+            )
+            .map(|names| names.to_string())
+            .map_err(|e| e.to_string())
+        };
+
+        assert_eq!(declared("Colors").unwrap(), "Red=0 Green=1");
+        assert_eq!(
+            declared("Broken").unwrap_err(),
+            "Invalid value \"Red Red\" for annotation \"org.zbus.lockstep.Enum.Broken\"."
+        );
+        assert_eq!(
+            declared("Sizes").unwrap_err(),
+            "Interface \"org.example.Lamp\" declares no list \"Sizes\"."
+        );
+
+        assert_eq!(annotated("Paint", MsgType::Method).unwrap(), "red green");
+        assert_eq!(
+            annotated("Color", MsgType::Property).unwrap_err(),
+            "\"Color\" has the org.example.Names annotation, but type \"u\" instead of \"s\"."
+        );
+        assert!(annotated("Paint", MsgType::Signal).is_err());
+    }
+}
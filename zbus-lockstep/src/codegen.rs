@@ -0,0 +1,998 @@
+//! Generation of Rust types and proxies from `DBus` XML.
+//!
+//! Every signal body, method's arguments and reply, and property gets a type whose signature
+//! matches the XML:
+//!
+//! - Members with a single argument, and properties, get a newtype, e.g. `pub struct
+//!   NameProperty(pub String);`.
+//! - Members with several arguments get a struct with a field per argument, named after the
+//!   argument in `snake_case`.
+//!
+//! Signatures map to owned Rust types, `o` to `OwnedObjectPath`, `a{sv}` to
+//! `HashMap<String, OwnedValue>` and so on, see [`rust_type`]. The generated code derives
+//! `zvariant::Type` and `serde`'s `Serialize` and `Deserialize`, so the crate using it needs
+//! those as dependencies.
+//!
+//! [`generate_proxy`] generates a `zbus` proxy trait for an interface, and
+//! [`generate_proxy_tests`] tests that keep it in lockstep with the XML. [`write_proxy`] writes
+//! both to `OUT_DIR` from a build script, so regenerating after an XML update is a rebuild.
+
+use std::{
+    collections::HashMap,
+    fmt::{self, Write},
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use zbus_xml::{Arg, ArgDirection, Interface};
+
+use crate::{
+    annotations::{no_reply, EmitsChangedSignal},
+    arg_direction, interfaces_with_paths,
+    lint::check_signature,
+    load::read_nodes,
+    load_nodes,
+    tree::find_interface_in,
+    LockstepError, Result, Scanner,
+};
+
+/// Where in an interface a generated type comes from.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub enum TypeSource {
+    /// The body of the named signal.
+    SignalBody(String),
+    /// The input arguments of the named method.
+    MethodArgs(String),
+    /// The output arguments of the named method.
+    MethodReply(String),
+    /// The type of the named property.
+    Property(String),
+}
+
+impl TypeSource {
+    /// The name of the member the type comes from.
+    pub fn member(&self) -> &str {
+        match self {
+            TypeSource::SignalBody(member)
+            | TypeSource::MethodArgs(member)
+            | TypeSource::MethodReply(member)
+            | TypeSource::Property(member) => member,
+        }
+    }
+
+    /// The name of the generated type, the member name followed by `Signal`, `Args`, `Reply`
+    /// or `Property`.
+    pub fn type_name(&self) -> String {
+        let suffix = match self {
+            TypeSource::SignalBody(_) => "Signal",
+            TypeSource::MethodArgs(_) => "Args",
+            TypeSource::MethodReply(_) => "Reply",
+            TypeSource::Property(_) => "Property",
+        };
+        format!("{}{suffix}", type_name(self.member()))
+    }
+}
+
+impl fmt::Display for TypeSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TypeSource::SignalBody(member) => write!(f, "Body of the `{member}` signal"),
+            TypeSource::MethodArgs(member) => write!(f, "Arguments of the `{member}` method"),
+            TypeSource::MethodReply(member) => write!(f, "Reply of the `{member}` method"),
+            TypeSource::Property(member) => write!(f, "Type of the `{member}` property"),
+        }
+    }
+}
+
+/// A field of a generated type.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Field {
+    /// The field name, `None` for the field of a newtype.
+    pub name: Option<String>,
+    /// The Rust type.
+    pub ty: String,
+}
+
+/// A Rust type generated from XML.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct GeneratedType {
+    /// The type name, see [`TypeSource::type_name`].
+    pub name: String,
+    pub interface: String,
+    pub source: TypeSource,
+    /// The `DBus` signature of the type.
+    pub signature: String,
+    pub fields: Vec<Field>,
+}
+
+impl GeneratedType {
+    /// The Rust source of the type.
+    pub fn to_rust(&self) -> String {
+        let mut rust = String::new();
+        let _ = writeln!(
+            rust,
+            "/// {} of `{}`, `{}`.",
+            self.source, self.interface, self.signature
+        );
+        rust.push_str(
+            "#[derive(Debug, ::zvariant::Type, ::serde::Serialize, ::serde::Deserialize)]\n",
+        );
+
+        match self.fields.as_slice() {
+            [Field { name: None, ty }] => {
+                let _ = writeln!(rust, "pub struct {}(pub {ty});", self.name);
+            }
+            fields => {
+                let _ = writeln!(rust, "pub struct {} {{", self.name);
+                for field in fields {
+                    let name = field.name.as_deref().unwrap_or("_");
+                    let _ = writeln!(rust, "    pub {name}: {},", field.ty);
+                }
+                rust.push_str("}\n");
+            }
+        }
+        rust
+    }
+}
+
+/// The owned Rust type of a single complete type signature.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::codegen::rust_type;
+///
+/// assert_eq!(rust_type("o").unwrap(), "::zvariant::OwnedObjectPath");
+/// assert_eq!(
+///     rust_type("a{sv}").unwrap(),
+///     "::std::collections::HashMap<String, ::zvariant::OwnedValue>"
+/// );
+/// assert_eq!(rust_type("(sas)").unwrap(), "(String, Vec<String>)");
+/// ```
+pub fn rust_type(signature: &str) -> Result<String> {
+    match check_signature(signature) {
+        Ok(1) => {}
+        Ok(count) => {
+            return Err(
+                format!("\"{signature}\" holds {count} complete types, expected one").into(),
+            )
+        }
+        Err(e) => return Err(format!("Invalid signature \"{signature}\": {e}").into()),
+    }
+
+    let (ty, _) = parse_type(signature.as_bytes());
+    Ok(ty)
+}
+
+/// Map the complete type at the start of a valid signature, returning the rest.
+fn parse_type(signature: &[u8]) -> (String, &[u8]) {
+    let rest = &signature[1..];
+    let ty = match signature[0] {
+        b'y' => "u8",
+        b'b' => "bool",
+        b'n' => "i16",
+        b'q' => "u16",
+        b'i' => "i32",
+        b'u' => "u32",
+        b'x' => "i64",
+        b't' => "u64",
+        b'd' => "f64",
+        b'h' => "::zvariant::OwnedFd",
+        b's' => "String",
+        b'o' => "::zvariant::OwnedObjectPath",
+        b'g' => "::zvariant::OwnedSignature",
+        b'v' => "::zvariant::OwnedValue",
+        b'a' if rest[0] == b'{' => {
+            let (key, rest) = parse_type(&rest[1..]);
+            let (value, rest) = parse_type(rest);
+            let ty = format!("::std::collections::HashMap<{key}, {value}>");
+            return (ty, &rest[1..]);
+        }
+        b'a' => {
+            let (element, rest) = parse_type(rest);
+            return (format!("Vec<{element}>"), rest);
+        }
+        b'(' => {
+            let mut fields = Vec::new();
+            let mut rest = rest;
+            while rest[0] != b')' {
+                let (field, remainder) = parse_type(rest);
+                fields.push(field);
+                rest = remainder;
+            }
+            // A one-element tuple needs a trailing comma.
+            let trailing = if fields.len() == 1 { "," } else { "" };
+            return (format!("({}{trailing})", fields.join(", ")), &rest[1..]);
+        }
+        other => unreachable!("checked signature holds '{}'", other as char),
+    };
+    (ty.to_string(), rest)
+}
+
+/// The types of every member of an interface, in the order of the XML.
+///
+/// Members without arguments, e.g. a method without reply, get no type.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::{codegen::interface_types, Node};
+///
+/// let node = Node::from_reader(r#"
+/// <node>
+///   <interface name="org.example.Node">
+///     <signal name="NodeMoved">
+///       <arg name="path" type="o"/>
+///       <arg name="newParent" type="o"/>
+///     </signal>
+///   </interface>
+/// </node>"#.as_bytes()).unwrap();
+///
+/// let types = interface_types(&node.interfaces()[0]).unwrap();
+///
+/// assert_eq!(types[0].name, "NodeMovedSignal");
+/// assert!(types[0].to_rust().contains("pub new_parent: ::zvariant::OwnedObjectPath,"));
+/// ```
+pub fn interface_types(interface: &Interface<'_>) -> Result<Vec<GeneratedType>> {
+    let interface_name = interface.name().to_string();
+    let mut types = Vec::new();
+
+    let mut push = |source: TypeSource, args: Vec<(Option<&str>, String)>| {
+        if args.is_empty() {
+            return Ok::<_, Box<dyn std::error::Error>>(());
+        }
+        let signature = args.iter().map(|(_, ty)| ty.as_str()).collect();
+        let fields = if let [(_, ty)] = args.as_slice() {
+            vec![Field {
+                name: None,
+                ty: rust_type(ty)?,
+            }]
+        } else {
+            args.iter()
+                .enumerate()
+                .map(|(index, (name, ty))| {
+                    Ok(Field {
+                        name: Some(field_name(*name, index)),
+                        ty: rust_type(ty)?,
+                    })
+                })
+                .collect::<Result<_>>()?
+        };
+
+        types.push(GeneratedType {
+            name: source.type_name(),
+            interface: interface_name.clone(),
+            source,
+            signature,
+            fields,
+        });
+        Ok(())
+    };
+
+    for method in interface.methods() {
+        let name = method.name().to_string();
+        let args = |direction| arg_types(method.args(), Some(direction));
+        push(TypeSource::MethodArgs(name.clone()), args(ArgDirection::In))?;
+        push(TypeSource::MethodReply(name), args(ArgDirection::Out))?;
+    }
+
+    for signal in interface.signals() {
+        let source = TypeSource::SignalBody(signal.name().to_string());
+        push(source, arg_types(signal.args(), None))?;
+    }
+
+    for property in interface.properties() {
+        let source = TypeSource::Property(property.name().to_string());
+        push(source, vec![(None, property.ty().to_string())])?;
+    }
+
+    Ok(types)
+}
+
+/// The names and signatures of the arguments in `direction`, or of all arguments.
+pub(crate) fn arg_types(
+    args: &[Arg],
+    direction: Option<ArgDirection>,
+) -> Vec<(Option<&str>, String)> {
+    args.iter()
+        .filter(|arg| direction.is_none_or(|direction| arg_direction(arg) == direction))
+        .map(|arg| (arg.name(), arg.ty().to_string()))
+        .collect()
+}
+
+/// The Rust source of the types of every member of an interface.
+pub fn generate_types(interface: &Interface<'_>) -> Result<String> {
+    let types = interface_types(interface)?;
+    Ok(types
+        .iter()
+        .map(GeneratedType::to_rust)
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+/// A ready-to-paste definition of a type named `type_name` with the signature of a member.
+///
+/// Fields are named after the arguments in the XML, the same way as in [`interface_types`].
+/// The `validate` attribute and [`assert_signature_eq!`](crate::assert_signature_eq) include
+/// it in the message of a failed check.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::codegen::{suggest_type, TypeSource};
+///
+/// let xml = std::fs::read_to_string("../xml/test_definition_file.xml").unwrap();
+/// let source = TypeSource::SignalBody("Alert".to_string());
+///
+/// let suggestion = suggest_type(xml.as_bytes(), "org.example.Node", None, &source, "Alert").unwrap();
+///
+/// assert!(suggestion.contains("pub struct Alert {\n    pub urgent: bool,\n    pub color: String,\n"));
+/// ```
+pub fn suggest_type(
+    xml: impl Read,
+    interface_name: &str,
+    object_path: Option<&str>,
+    source: &TypeSource,
+    type_name: &str,
+) -> Result<String> {
+    let nodes = read_nodes(xml)?;
+    let (_, interface) = find_interface_in(&nodes, interface_name, object_path)?;
+
+    let mut ty = interface_types(interface)?
+        .into_iter()
+        .find(|ty| ty.source == *source)
+        .ok_or_else(|| LockstepError::MemberNotFound(source.member().to_owned()))?;
+    type_name.clone_into(&mut ty.name);
+    Ok(ty.to_rust())
+}
+
+/// The Rust source of a `zbus` proxy trait for an interface.
+///
+/// Methods, signals and properties are named after their members in `snake_case`, with the
+/// `DBus` name given explicitly. Arguments and return values have the owned types of
+/// [`rust_type`]. Writable properties get a `set_` method.
+///
+/// Members whose methods would end up with the same name, e.g. a `SetLevel` method and the
+/// setter of a writable `Level` property, are an error.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::codegen::generate_proxy;
+/// use zbus_xml::Node;
+///
+/// let node = Node::from_reader(r#"
+/// <node>
+///   <interface name="org.example.Tree">
+///     <method name="Prune">
+///       <arg name="branch" type="o" direction="in"/>
+///       <arg name="pruned" type="u" direction="out"/>
+///     </method>
+///   </interface>
+/// </node>"#.as_bytes()).unwrap();
+///
+/// let proxy = generate_proxy(&node.interfaces()[0]).unwrap();
+///
+/// assert!(proxy.contains("#[::zbus::proxy(interface = \"org.example.Tree\")]"));
+/// assert!(proxy.contains(
+///     "fn prune(&self, branch: ::zvariant::OwnedObjectPath) -> ::zbus::Result<u32>;"
+/// ));
+/// ```
+pub fn generate_proxy(interface: &Interface<'_>) -> Result<String> {
+    let interface_name = interface.name().to_string();
+    let trait_name = type_name(interface_name.rsplit('.').next().unwrap_or_default());
+
+    let mut items = Vec::new();
+    let mut names = HashMap::new();
+
+    for method in interface.methods() {
+        let name = method.name().to_string();
+        let method_name = proxy_method_name(&name);
+        claim_name(&mut names, &method_name, format!("method \"{name}\""))?;
+        let mut attributes = vec![format!("name = \"{name}\"")];
+        if no_reply(method.annotations())? {
+            attributes.push("no_reply".to_string());
+        }
+        let args = params(&arg_types(method.args(), Some(ArgDirection::In)))?;
+        let reply = reply_type(&arg_types(method.args(), Some(ArgDirection::Out)))?;
+        items.push(format!(
+            "    /// `{name}` method.\n    #[zbus({})]\n    fn {method_name}(&self{args}) -> ::zbus::Result<{reply}>;\n",
+            attributes.join(", "),
+        ));
+    }
+
+    for signal in interface.signals() {
+        let name = signal.name().to_string();
+        let method_name = proxy_method_name(&name);
+        claim_name(&mut names, &method_name, format!("signal \"{name}\""))?;
+        let args = params(&arg_types(signal.args(), None))?;
+        items.push(format!(
+            "    /// `{name}` signal.\n    #[zbus(signal, name = \"{name}\")]\n    fn {method_name}(&self{args}) -> ::zbus::Result<()>;\n",
+        ));
+    }
+
+    for property in interface.properties() {
+        let name = property.name().to_string();
+        let ty = rust_type(&property.ty().to_string())?;
+        let method_name = proxy_method_name(&name);
+        let emits =
+            EmitsChangedSignal::from_annotations(property.annotations(), interface.annotations())?;
+
+        let mut item = format!("    /// `{name}` property.\n");
+        if property.access().read() {
+            claim_name(&mut names, &method_name, format!("property \"{name}\""))?;
+            let property_attribute = if emits == EmitsChangedSignal::default() {
+                "property".to_string()
+            } else {
+                format!("property(emits_changed_signal = \"{emits}\")")
+            };
+            let _ = writeln!(
+                item,
+                "    #[zbus({property_attribute}, name = \"{name}\")]\n    fn {method_name}(&self) -> ::zbus::Result<{ty}>;"
+            );
+        }
+        if property.access().write() {
+            let setter = format!("set_{method_name}");
+            claim_name(
+                &mut names,
+                &setter,
+                format!("the setter of property \"{name}\""),
+            )?;
+            let _ = writeln!(
+                item,
+                "    #[zbus(property, name = \"{name}\")]\n    fn {setter}(&self, value: {ty}) -> ::zbus::Result<()>;"
+            );
+        }
+        items.push(item);
+    }
+
+    Ok(format!(
+        "/// Proxy for `{interface_name}`.\n#[::zbus::proxy(interface = \"{interface_name}\")]\npub trait {trait_name} {{\n{}}}\n",
+        items.join("\n")
+    ))
+}
+
+/// The Rust source of a test module that validates the types of a proxy generated by
+/// [`generate_proxy`] against the XML file it was generated from.
+///
+/// The tests read `xml_file` when they run, so a proxy that is not regenerated after the XML
+/// changes fails them. Each test also refers to the method it checks on the blocking proxy, so
+/// it does not compile once the proxy and the checked types drift apart. The module is meant to
+/// follow the proxy in the same file, and `zbus` needs its default `blocking-api` feature.
+pub fn generate_proxy_tests(interface: &Interface<'_>, xml_file: &Path) -> Result<String> {
+    let interface_name = interface.name();
+    let xml_file = xml_file
+        .to_str()
+        .ok_or_else(|| format!("XML file path \"{}\" is not UTF-8", xml_file.display()))?;
+    let module = field_name(interface_name.rsplit('.').next(), 0);
+    let proxy = format!(
+        "super::{}ProxyBlocking",
+        type_name(interface_name.rsplit('.').next().unwrap_or_default())
+    );
+
+    let mut tests = String::new();
+    let mut push = |test: String, uses: &str, getter: &str, member: &str, ty: Option<String>| {
+        let Some(ty) = ty else { return };
+        let direction = if getter == "get_property_type" {
+            ""
+        } else {
+            ", None"
+        };
+        let _ = write!(
+            tests,
+            r#"
+    #[test]
+    fn {test}() {{
+        {uses}
+        let xml = ::zbus_lockstep::load_xml(XML_FILE).expect("Failed to load XML file.");
+        let signature = ::zbus_lockstep::{getter}(xml.as_bytes(), INTERFACE, "{member}"{direction})
+            .expect("Failed to get type signature from XML file.");
+        let signature_of_type = <{ty} as Type>::SIGNATURE;
+        assert_eq!(
+            &signature,
+            signature_of_type,
+            "{{}}",
+            ::zbus_lockstep::explain_mismatch(&signature, signature_of_type)
+        );
+    }}
+"#
+        );
+    };
+
+    for method in interface.methods() {
+        let name = method.name().to_string();
+        let method_name = proxy_method_name(&name);
+        let args = arg_types(method.args(), Some(ArgDirection::In));
+        let reply = arg_types(method.args(), Some(ArgDirection::Out));
+        let params = args
+            .iter()
+            .map(|(_, ty)| Ok(format!(", {}", rust_type(ty)?)))
+            .collect::<Result<String>>()?;
+        let uses = format!(
+            "let _: fn(&{proxy}<'static>{params}) -> ::zbus::Result<{}> = {proxy}::{method_name};",
+            reply_type(&reply)?
+        );
+        push(
+            format!("{}_args", method_name.trim_end_matches('_')),
+            &uses,
+            "get_method_args_type",
+            &name,
+            body_type(&args)?,
+        );
+        push(
+            format!("{}_reply", method_name.trim_end_matches('_')),
+            &uses,
+            "get_method_return_type",
+            &name,
+            body_type(&reply)?,
+        );
+    }
+
+    for signal in interface.signals() {
+        let name = signal.name().to_string();
+        let method_name = proxy_method_name(&name);
+        push(
+            format!("{}_signal", method_name.trim_end_matches('_')),
+            &format!("let _ = {proxy}::receive_{method_name};"),
+            "get_signal_body_type",
+            &name,
+            body_type(&arg_types(signal.args(), None))?,
+        );
+    }
+
+    for property in interface.properties() {
+        let name = property.name().to_string();
+        let method_name = proxy_method_name(&name);
+        let ty = rust_type(&property.ty().to_string())?;
+        let uses = if property.access().read() {
+            format!(
+                "let _: fn(&{proxy}<'static>) -> ::zbus::Result<{ty}> = {proxy}::{method_name};"
+            )
+        } else {
+            format!("let _: fn(&{proxy}<'static>, {ty}) -> ::zbus::Result<()> = {proxy}::set_{method_name};")
+        };
+        push(
+            format!("{}_property", method_name.trim_end_matches('_')),
+            &uses,
+            "get_property_type",
+            &name,
+            Some(ty),
+        );
+    }
+
+    Ok(format!(
+        "#[cfg(test)]\nmod {}_lockstep {{\n    use ::zvariant::Type;\n\n    const XML_FILE: &str = {xml_file:?};\n    const INTERFACE: &str = \"{interface_name}\";\n{tests}}}\n",
+        module.trim_start_matches("r#")
+    ))
+}
+
+/// Generate a proxy for `interface_name`, found in the XML files in `xml_dir`, along with its
+/// tests, and write it to `OUT_DIR`.
+///
+/// Meant to be called from a build script. The file is named after the interface and can be
+/// included with `include!`. Cargo is told to run the build script again when `xml_dir`
+/// changes. Returns the path of the written file.
+///
+/// # Examples
+///
+/// In `build.rs`:
+///
+/// ```rust,no_run
+/// zbus_lockstep::codegen::write_proxy("xml", "org.example.Node").unwrap();
+/// ```
+///
+/// And in the crate:
+///
+/// ```rust,ignore
+/// include!(concat!(env!("OUT_DIR"), "/org.example.Node.rs"));
+/// ```
+pub fn write_proxy(xml_dir: impl AsRef<Path>, interface_name: &str) -> Result<PathBuf> {
+    let xml_dir = xml_dir.as_ref();
+    let out_dir = std::env::var_os("OUT_DIR")
+        .ok_or("`OUT_DIR` is not set, `write_proxy` is meant to be called from a build script")?;
+
+    for path in Scanner::new().scan(xml_dir)?.files {
+        let nodes = load_nodes(&path)?;
+        let Some((_, interface)) = nodes
+            .iter()
+            .flat_map(interfaces_with_paths)
+            .find(|(_, interface)| interface.name() == interface_name)
+        else {
+            continue;
+        };
+
+        let xml_file = path.canonicalize()?;
+        let source = format!(
+            "{}\n{}",
+            generate_proxy(interface)?,
+            generate_proxy_tests(interface, &xml_file)?
+        );
+        let out_file = Path::new(&out_dir).join(format!("{interface_name}.rs"));
+        std::fs::write(&out_file, source)?;
+
+        println!("cargo:rerun-if-changed={}", xml_dir.display());
+        return Ok(out_file);
+    }
+
+    Err(LockstepError::InterfaceNotFound(interface_name.to_owned()).into())
+}
+
+/// Record that `member` gets the proxy method `method_name`, unless another member already has.
+fn claim_name(
+    names: &mut HashMap<String, String>,
+    method_name: &str,
+    member: String,
+) -> Result<()> {
+    match names.insert(method_name.to_owned(), member.clone()) {
+        Some(other) => Err(format!(
+            "Both {other} and {member} would be the proxy method `{method_name}`"
+        )
+        .into()),
+        None => Ok(()),
+    }
+}
+
+/// A proxy method name in `snake_case` for a member name.
+///
+/// `zbus` builds further names from it, e.g. `receive_` for signals, so keywords get a trailing
+/// underscore rather than being raw identifiers.
+fn proxy_method_name(member: &str) -> String {
+    let name = field_name(Some(member), 0);
+    match name.strip_prefix("r#") {
+        Some(keyword) => format!("{keyword}_"),
+        None => name,
+    }
+}
+
+/// Method parameters, each preceded by a comma to follow `&self`.
+fn params(args: &[(Option<&str>, String)]) -> Result<String> {
+    args.iter()
+        .enumerate()
+        .map(|(index, (name, ty))| {
+            Ok(format!(
+                ", {}: {}",
+                field_name(*name, index),
+                rust_type(ty)?
+            ))
+        })
+        .collect()
+}
+
+/// The return type of a method with the given output arguments.
+fn reply_type(args: &[(Option<&str>, String)]) -> Result<String> {
+    Ok(body_type(args)?.unwrap_or_else(|| "()".to_string()))
+}
+
+/// A type for a message body, a tuple for several arguments, `None` for an empty body.
+fn body_type(args: &[(Option<&str>, String)]) -> Result<Option<String>> {
+    let types = args
+        .iter()
+        .map(|(_, ty)| rust_type(ty))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(match types.as_slice() {
+        [] => None,
+        [ty] => Some(ty.clone()),
+        types => Some(format!("({})", types.join(", "))),
+    })
+}
+
+/// A type name in `UpperCamelCase` for a member name.
+pub(crate) fn type_name(member: &str) -> String {
+    member
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+/// A field name in `snake_case` for an argument name, or for the argument at `index`.
+pub(crate) fn field_name(arg: Option<&str>, index: usize) -> String {
+    let Some(arg) = arg.filter(|arg| !arg.is_empty()) else {
+        return format!("arg{index}");
+    };
+
+    let mut name = String::new();
+    let mut previous: Option<char> = None;
+    for c in arg.chars() {
+        if !c.is_alphanumeric() {
+            if !name.is_empty() && !name.ends_with('_') {
+                name.push('_');
+            }
+        } else if c.is_uppercase() {
+            if previous.is_some_and(|p| p.is_lowercase() || p.is_numeric()) && !name.ends_with('_')
+            {
+                name.push('_');
+            }
+            name.extend(c.to_lowercase());
+        } else {
+            name.push(c);
+        }
+        previous = Some(c);
+    }
+
+    let name = name.trim_end_matches('_');
+    if name.is_empty() {
+        format!("arg{index}")
+    } else if name.starts_with(|c: char| c.is_numeric()) {
+        format!("_{name}")
+    } else if is_keyword(name) {
+        format!("r#{name}")
+    } else {
+        name.to_string()
+    }
+}
+
+fn is_keyword(name: &str) -> bool {
+    matches!(
+        name,
+        "as" | "async"
+            | "await"
+            | "break"
+            | "const"
+            | "continue"
+            | "dyn"
+            | "else"
+            | "enum"
+            | "extern"
+            | "false"
+            | "fn"
+            | "for"
+            | "if"
+            | "impl"
+            | "in"
+            | "let"
+            | "loop"
+            | "match"
+            | "mod"
+            | "move"
+            | "mut"
+            | "pub"
+            | "ref"
+            | "return"
+            | "static"
+            | "struct"
+            | "trait"
+            | "true"
+            | "type"
+            | "unsafe"
+            | "use"
+            | "where"
+            | "while"
+            | "abstract"
+            | "become"
+            | "box"
+            | "do"
+            | "final"
+            | "gen"
+            | "macro"
+            | "override"
+            | "priv"
+            | "try"
+            | "typeof"
+            | "unsized"
+            | "virtual"
+            | "yield"
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_rust_type() {
+        assert_eq!(rust_type("ay").unwrap(), "Vec<u8>");
+        assert_eq!(rust_type("(i)").unwrap(), "(i32,)");
+        assert_eq!(
+            rust_type("a{oa{sv}}").unwrap(),
+            "::std::collections::HashMap<::zvariant::OwnedObjectPath, \
+             ::std::collections::HashMap<String, ::zvariant::OwnedValue>>"
+        );
+        assert_eq!(
+            rust_type("((so)(so)iias)").unwrap(),
+            "((String, ::zvariant::OwnedObjectPath), (String, ::zvariant::OwnedObjectPath), i32, i32, Vec<String>)"
+        );
+        assert!(rust_type("su").is_err());
+        assert!(rust_type("a{vs}").is_err());
+    }
+
+    #[test]
+    fn test_names() {
+        assert_eq!(type_name("GetRole"), "GetRole");
+        assert_eq!(type_name("state-changed"), "StateChanged");
+        assert_eq!(field_name(Some("nodeAdded"), 0), "node_added");
+        assert_eq!(field_name(Some("app_name"), 0), "app_name");
+        assert_eq!(field_name(Some("X11Display"), 0), "x11_display");
+        assert_eq!(field_name(Some("type"), 0), "r#type");
+        assert_eq!(field_name(None, 2), "arg2");
+    }
+
+    #[test]
+    fn test_to_rust() {
+        let generated = GeneratedType {
+            name: "RemoveNodeSignal".to_string(),
+            interface: "org.example.Node".to_string(),
+            source: TypeSource::SignalBody("RemoveNode".to_string()),
+            signature: "(so)".to_string(),
+            fields: vec![Field {
+                name: None,
+                ty: "(String, ::zvariant::OwnedObjectPath)".to_string(),
+            }],
+        };
+
+        assert_eq!(
+            generated.to_rust(),
+            "/// Body of the `RemoveNode` signal of `org.example.Node`, `(so)`.\n\
+             #[derive(Debug, ::zvariant::Type, ::serde::Serialize, ::serde::Deserialize)]\n\
+             pub struct RemoveNodeSignal(pub (String, ::zvariant::OwnedObjectPath));\n"
+        );
+    }
+
+    #[test]
+    fn test_suggest_type() {
+        let xml = r#"
+<node>
+  <interface name="org.example.Node">
+    <signal name="RemoveNode">
+      <arg name="nodeRemoved" type="(so)"/>
+    </signal>
+    <property name="Features" type="as" access="read"/>
+  </interface>
+</node>"#;
+
+        let source = TypeSource::SignalBody("RemoveNode".to_string());
+        let suggestion = suggest_type(xml.as_bytes(), "org.example.Node", None, &source, "Removed");
+        assert!(suggestion
+            .unwrap()
+            .ends_with("pub struct Removed(pub (String, ::zvariant::OwnedObjectPath));\n"));
+
+        let source = TypeSource::Property("Features".to_string());
+        let suggestion = suggest_type(
+            xml.as_bytes(),
+            "org.example.Node",
+            None,
+            &source,
+            "Features",
+        );
+        assert!(suggestion
+            .unwrap()
+            .ends_with("pub struct Features(pub Vec<String>);\n"));
+
+        let source = TypeSource::MethodArgs("RemoveNode".to_string());
+        assert!(suggest_type(xml.as_bytes(), "org.example.Node", None, &source, "Args").is_err());
+        assert!(suggest_type(xml.as_bytes(), "org.example.Tree", None, &source, "Args").is_err());
+    }
+
+    #[test]
+    fn test_generate_proxy() {
+        let node = zbus_xml::Node::from_reader(
+            r#"
+<node>
+  <interface name="org.example.Lamp">
+    <method name="Toggle">
+      <annotation name="org.freedesktop.DBus.Method.NoReply" value="true"/>
+    </method>
+    <method name="SetColor">
+      <arg name="red" type="y" direction="in"/>
+      <arg name="green" type="y" direction="in"/>
+      <arg name="blue" type="y" direction="in"/>
+    </method>
+    <signal name="Broken"/>
+    <property name="Brightness" type="d" access="readwrite"/>
+    <property name="Model" type="s" access="read">
+      <annotation name="org.freedesktop.DBus.Property.EmitsChangedSignal" value="const"/>
+    </property>
+  </interface>
+</node>"#
+                .as_bytes(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            generate_proxy(&node.interfaces()[0]).unwrap(),
+            r#"/// Proxy for `org.example.Lamp`.
+#[::zbus::proxy(interface = "org.example.Lamp")]
+pub trait Lamp {
+    /// `Toggle` method.
+    #[zbus(name = "Toggle", no_reply)]
+    fn toggle(&self) -> ::zbus::Result<()>;
+
+    /// `SetColor` method.
+    #[zbus(name = "SetColor")]
+    fn set_color(&self, red: u8, green: u8, blue: u8) -> ::zbus::Result<()>;
+
+    /// `Broken` signal.
+    #[zbus(signal, name = "Broken")]
+    fn broken(&self) -> ::zbus::Result<()>;
+
+    /// `Brightness` property.
+    #[zbus(property, name = "Brightness")]
+    fn brightness(&self) -> ::zbus::Result<f64>;
+    #[zbus(property, name = "Brightness")]
+    fn set_brightness(&self, value: f64) -> ::zbus::Result<()>;
+
+    /// `Model` property.
+    #[zbus(property(emits_changed_signal = "const"), name = "Model")]
+    fn model(&self) -> ::zbus::Result<String>;
+}
+"#
+        );
+    }
+
+    #[test]
+    fn test_generate_proxy_tests() {
+        let xml = std::fs::read_to_string("../xml/test_definition_file.xml").unwrap();
+        let node = zbus_xml::Node::from_reader(xml.as_bytes()).unwrap();
+
+        let tests =
+            generate_proxy_tests(&node.interfaces()[0], Path::new("/xml/node.xml")).unwrap();
+
+        assert!(tests.starts_with("#[cfg(test)]\nmod node_lockstep {"));
+        assert!(tests.contains("const XML_FILE: &str = \"/xml/node.xml\";"));
+        assert!(tests.contains("fn request_name_args() {"));
+        assert!(tests.contains(
+            "let _: fn(&super::NodeProxyBlocking<'static>, String, u32) -> ::zbus::Result<u32> = super::NodeProxyBlocking::request_name;"
+        ));
+        assert!(tests.contains("<(String, u32) as Type>::SIGNATURE"));
+        assert!(tests.contains("let _ = super::NodeProxyBlocking::receive_alert;"));
+        assert!(tests.contains("fn features_property() {"));
+        // `Ping` has neither arguments nor a reply.
+        assert!(!tests.contains("fn ping_"));
+    }
+
+    #[test]
+    fn test_generate_proxy_keyword_names() {
+        let node = zbus_xml::Node::from_reader(
+            r#"
+<node>
+  <interface name="org.example.Lamp">
+    <property name="Type" type="s" access="readwrite"/>
+  </interface>
+</node>"#
+                .as_bytes(),
+        )
+        .unwrap();
+        let interface = &node.interfaces()[0];
+
+        let proxy = generate_proxy(interface).unwrap();
+        assert!(proxy.contains("fn type_(&self) -> ::zbus::Result<String>;"));
+        assert!(proxy.contains("fn set_type_(&self, value: String) -> ::zbus::Result<()>;"));
+
+        let tests = generate_proxy_tests(interface, Path::new("/xml/lamp.xml")).unwrap();
+        assert!(tests.contains("fn type_property() {"));
+        assert!(tests.contains("= super::LampProxyBlocking::type_;"));
+    }
+
+    #[test]
+    fn test_generate_proxy_name_collisions() {
+        let proxy = |members: &str| {
+            let xml =
+                format!(r#"<node><interface name="org.example.Lamp">{members}</interface></node>"#);
+            let node = zbus_xml::Node::from_reader(xml.as_bytes()).unwrap();
+            generate_proxy(&node.interfaces()[0]).map_err(|e| e.to_string())
+        };
+
+        assert_eq!(
+            proxy(r#"<method name="SetLevel"/><property name="Level" type="u" access="readwrite"/>"#),
+            Err("Both method \"SetLevel\" and the setter of property \"Level\" would be the proxy method `set_level`".to_string())
+        );
+        assert_eq!(
+            proxy(r#"<method name="Level"/><property name="Level" type="u" access="read"/>"#),
+            Err(
+                "Both method \"Level\" and property \"Level\" would be the proxy method `level`"
+                    .to_string()
+            )
+        );
+        // Without a setter, there is nothing to collide with.
+        assert!(proxy(
+            r#"<method name="SetLevel"/><property name="Level" type="u" access="read"/>"#
+        )
+        .is_ok());
+    }
+}
@@ -0,0 +1,698 @@
+//! Compatibility of two versions of an interface description.
+//!
+//! Whether a change breaks anything depends on the side of the bus. Removing a method breaks
+//! clients calling it, but not servers still implementing it. Adding one breaks servers, which
+//! now miss it, but not clients. [`compare`] classifies each change for both sides.
+
+use std::fmt;
+
+use zbus_xml::{Annotation, Arg, ArgDirection, Interface, Node, PropertyAccess};
+
+use crate::{
+    access_str, arg_direction, args_signature, interfaces_with_paths, EmitsChangedSignal, MsgType,
+    EMITS_CHANGED_SIGNAL, NO_REPLY,
+};
+
+/// Whether a change breaks one side of the bus.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Impact {
+    Breaking,
+    NonBreaking,
+}
+
+impl Impact {
+    fn breaking(breaking: bool) -> Self {
+        if breaking {
+            Impact::Breaking
+        } else {
+            Impact::NonBreaking
+        }
+    }
+}
+
+impl fmt::Display for Impact {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Impact::Breaking => f.write_str("breaking"),
+            Impact::NonBreaking => f.write_str("non-breaking"),
+        }
+    }
+}
+
+/// What changed between two versions.
+#[non_exhaustive]
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ChangeKind {
+    InterfaceAdded,
+    InterfaceRemoved,
+    MemberAdded(MsgType),
+    MemberRemoved(MsgType),
+    /// The signature of a property, a signal's body, or a method's arguments in one direction.
+    SignatureChanged {
+        direction: Option<ArgDirection>,
+        old: String,
+        new: String,
+    },
+    /// An argument, by position, got a different name.
+    ArgRenamed {
+        index: usize,
+        old: Option<String>,
+        new: Option<String>,
+    },
+    /// An argument, by position, changed direction.
+    DirectionChanged {
+        index: usize,
+        old: Option<ArgDirection>,
+        new: Option<ArgDirection>,
+    },
+    AccessChanged {
+        old: PropertyAccess,
+        new: PropertyAccess,
+    },
+    AnnotationAdded {
+        name: String,
+        value: String,
+    },
+    AnnotationRemoved {
+        name: String,
+        value: String,
+    },
+    AnnotationChanged {
+        name: String,
+        old: String,
+        new: String,
+    },
+}
+
+impl fmt::Display for ChangeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChangeKind::InterfaceAdded => f.write_str("interface added"),
+            ChangeKind::InterfaceRemoved => f.write_str("interface removed"),
+            ChangeKind::MemberAdded(kind) => write!(f, "{kind} added"),
+            ChangeKind::MemberRemoved(kind) => write!(f, "{kind} removed"),
+            ChangeKind::SignatureChanged {
+                direction,
+                old,
+                new,
+            } => {
+                match direction {
+                    Some(ArgDirection::In) => f.write_str("input signature")?,
+                    Some(ArgDirection::Out) => f.write_str("output signature")?,
+                    None => f.write_str("signature")?,
+                }
+                write!(f, " changed from `{old}` to `{new}`")
+            }
+            ChangeKind::ArgRenamed { index, old, new } => write!(
+                f,
+                "argument {index} renamed from {} to {}",
+                arg_name(old.as_deref()),
+                arg_name(new.as_deref())
+            ),
+            ChangeKind::DirectionChanged { index, old, new } => write!(
+                f,
+                "argument {index} changed direction from `{}` to `{}`",
+                direction_str(*old),
+                direction_str(*new)
+            ),
+            ChangeKind::AccessChanged { old, new } => write!(
+                f,
+                "access changed from `{}` to `{}`",
+                access_str(*old),
+                access_str(*new)
+            ),
+            ChangeKind::AnnotationAdded { name, value } => {
+                write!(f, "annotation `{name}` added with value `{value}`")
+            }
+            ChangeKind::AnnotationRemoved { name, value } => {
+                write!(f, "annotation `{name}` removed, was `{value}`")
+            }
+            ChangeKind::AnnotationChanged { name, old, new } => {
+                write!(f, "annotation `{name}` changed from `{old}` to `{new}`")
+            }
+        }
+    }
+}
+
+fn arg_name(name: Option<&str>) -> String {
+    name.map_or_else(|| String::from("(unnamed)"), |name| format!("`{name}`"))
+}
+
+fn direction_str(direction: Option<ArgDirection>) -> &'static str {
+    match direction {
+        Some(ArgDirection::In) => "in",
+        Some(ArgDirection::Out) => "out",
+        None => "unspecified",
+    }
+}
+
+/// A change, and whom it breaks.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Change {
+    /// The interface, optionally followed by `.` and the member name.
+    pub item: String,
+    pub kind: ChangeKind,
+    /// The impact on code calling, or listening to, the interface.
+    pub clients: Impact,
+    /// The impact on code implementing the interface.
+    pub servers: Impact,
+}
+
+/// Every change between two versions of a description.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct CompatReport {
+    pub changes: Vec<Change>,
+}
+
+impl CompatReport {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    pub fn is_breaking_for_clients(&self) -> bool {
+        self.changes.iter().any(|c| c.clients == Impact::Breaking)
+    }
+
+    pub fn is_breaking_for_servers(&self) -> bool {
+        self.changes.iter().any(|c| c.servers == Impact::Breaking)
+    }
+}
+
+/// A Markdown summary and table of the changes.
+impl fmt::Display for CompatReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "No changes.");
+        }
+
+        let count = |impact: fn(&Change) -> Impact| {
+            self.changes
+                .iter()
+                .filter(|c| impact(c) == Impact::Breaking)
+                .count()
+        };
+        writeln!(
+            f,
+            "{} change(s), {} breaking for clients, {} breaking for servers.",
+            self.changes.len(),
+            count(|c| c.clients),
+            count(|c| c.servers)
+        )?;
+        writeln!(f)?;
+        writeln!(f, "| Item | Change | Clients | Servers |")?;
+        writeln!(f, "| --- | --- | --- | --- |")?;
+        for change in &self.changes {
+            writeln!(
+                f,
+                "| `{}` | {} | {} | {} |",
+                change.item, change.kind, change.clients, change.servers
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Compare two versions of a description, e.g. before and after an upstream update.
+///
+/// Interfaces are matched by name, anywhere in the trees of nodes. Members are matched by name,
+/// arguments by position.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::{compat::{compare, Impact}, Node};
+///
+/// let old = Node::from_reader(r#"
+/// <node>
+///   <interface name="org.a11y.atspi.Accessible">
+///     <method name="GetRole"><arg name="role" type="u" direction="out"/></method>
+///     <property name="Name" type="s" access="read"/>
+///   </interface>
+/// </node>"#.as_bytes()).unwrap();
+///
+/// let new = Node::from_reader(r#"
+/// <node>
+///   <interface name="org.a11y.atspi.Accessible">
+///     <method name="GetRole"><arg name="role" type="u" direction="out"/></method>
+///     <method name="GetRoleName"><arg name="name" type="s" direction="out"/></method>
+///     <property name="Name" type="s" access="readwrite"/>
+///   </interface>
+/// </node>"#.as_bytes()).unwrap();
+///
+/// let report = compare(&old, &new);
+///
+/// assert!(!report.is_breaking_for_clients());
+/// assert!(report.is_breaking_for_servers());
+/// assert_eq!(report.changes[0].item, "org.a11y.atspi.Accessible.GetRoleName");
+/// assert_eq!(report.changes[0].servers, Impact::Breaking);
+/// ```
+pub fn compare(old: &Node<'_>, new: &Node<'_>) -> CompatReport {
+    let old_interfaces = unique_interfaces(old);
+    let new_interfaces = unique_interfaces(new);
+    let mut report = Report::default();
+
+    for old_interface in &old_interfaces {
+        let name = old_interface.name().to_string();
+        match new_interfaces
+            .iter()
+            .find(|i| i.name() == old_interface.name())
+        {
+            Some(new_interface) => compare_interfaces(old_interface, new_interface, &mut report),
+            None => report.push(&name, ChangeKind::InterfaceRemoved, true, false),
+        }
+    }
+
+    for new_interface in &new_interfaces {
+        if !old_interfaces
+            .iter()
+            .any(|i| i.name() == new_interface.name())
+        {
+            let name = new_interface.name().to_string();
+            report.push(&name, ChangeKind::InterfaceAdded, false, true);
+        }
+    }
+
+    CompatReport {
+        changes: report.changes,
+    }
+}
+
+/// Interfaces of a tree of nodes, the first one of each name.
+fn unique_interfaces<'n, 'a>(node: &'n Node<'a>) -> Vec<&'n Interface<'a>> {
+    let mut interfaces: Vec<&Interface<'_>> = Vec::new();
+    for (_, interface) in interfaces_with_paths(node) {
+        if !interfaces.iter().any(|i| i.name() == interface.name()) {
+            interfaces.push(interface);
+        }
+    }
+    interfaces
+}
+
+#[derive(Default)]
+struct Report {
+    changes: Vec<Change>,
+}
+
+impl Report {
+    fn push(&mut self, item: &str, kind: ChangeKind, clients: bool, servers: bool) {
+        self.changes.push(Change {
+            item: item.to_string(),
+            kind,
+            clients: Impact::breaking(clients),
+            servers: Impact::breaking(servers),
+        });
+    }
+}
+
+fn compare_interfaces(old: &Interface<'_>, new: &Interface<'_>, report: &mut Report) {
+    let interface = old.name().to_string();
+    compare_annotations(&interface, old.annotations(), new.annotations(), report);
+
+    compare_members(
+        &interface,
+        MsgType::Method,
+        old.methods(),
+        new.methods(),
+        |m| m.name().to_string(),
+        |item, old, new, report| {
+            compare_args(item, old.args(), new.args(), true, report);
+            compare_annotations(item, old.annotations(), new.annotations(), report);
+        },
+        report,
+    );
+
+    compare_members(
+        &interface,
+        MsgType::Signal,
+        old.signals(),
+        new.signals(),
+        |s| s.name().to_string(),
+        |item, old, new, report| {
+            compare_args(item, old.args(), new.args(), false, report);
+            compare_annotations(item, old.annotations(), new.annotations(), report);
+        },
+        report,
+    );
+
+    compare_members(
+        &interface,
+        MsgType::Property,
+        old.properties(),
+        new.properties(),
+        |p| p.name().to_string(),
+        |item, old, new, report| {
+            if old.ty() != new.ty() {
+                let kind = ChangeKind::SignatureChanged {
+                    direction: None,
+                    old: old.ty().to_string(),
+                    new: new.ty().to_string(),
+                };
+                report.push(item, kind, true, true);
+            }
+            if old.access() != new.access() {
+                // Losing a capability breaks clients using it, gaining one breaks servers.
+                let lost = (old.access().read() && !new.access().read())
+                    || (old.access().write() && !new.access().write());
+                let gained = (new.access().read() && !old.access().read())
+                    || (new.access().write() && !old.access().write());
+                let kind = ChangeKind::AccessChanged {
+                    old: old.access(),
+                    new: new.access(),
+                };
+                report.push(item, kind, lost, gained);
+            }
+            compare_annotations(item, old.annotations(), new.annotations(), report);
+        },
+        report,
+    );
+}
+
+fn compare_members<T>(
+    interface: &str,
+    kind: MsgType,
+    old: &[T],
+    new: &[T],
+    name: impl Fn(&T) -> String,
+    compare: impl Fn(&str, &T, &T, &mut Report),
+    report: &mut Report,
+) {
+    for old_member in old {
+        let item = format!("{interface}.{}", name(old_member));
+        match new.iter().find(|m| name(m) == name(old_member)) {
+            Some(new_member) => compare(&item, old_member, new_member, report),
+            None => report.push(&item, ChangeKind::MemberRemoved(kind), true, false),
+        }
+    }
+
+    for new_member in new {
+        if !old.iter().any(|m| name(m) == name(new_member)) {
+            let item = format!("{interface}.{}", name(new_member));
+            report.push(&item, ChangeKind::MemberAdded(kind), false, true);
+        }
+    }
+}
+
+/// Compare arguments, of a method if `method`, else of a signal.
+fn compare_args(item: &str, old: &[Arg], new: &[Arg], method: bool, report: &mut Report) {
+    let same_count = old.len() == new.len();
+
+    let mut direction_changed = false;
+    if same_count && method {
+        for (index, (old_arg, new_arg)) in old.iter().zip(new).enumerate() {
+            let (old_dir, new_dir) = (Some(arg_direction(old_arg)), Some(arg_direction(new_arg)));
+            if old_dir != new_dir {
+                direction_changed = true;
+                let kind = ChangeKind::DirectionChanged {
+                    index,
+                    old: old_dir,
+                    new: new_dir,
+                };
+                report.push(item, kind, true, true);
+            }
+        }
+    }
+
+    let directions: &[Option<ArgDirection>] = if method {
+        &[Some(ArgDirection::In), Some(ArgDirection::Out)]
+    } else {
+        &[None]
+    };
+    for &direction in directions {
+        let (old_sig, new_sig) = (
+            args_signature(old, direction),
+            args_signature(new, direction),
+        );
+        // A signature that only changed as arguments changed direction is reported once, with
+        // the direction. Back in their old directions, the new arguments have the old signature.
+        let explained = direction_changed
+            && new
+                .iter()
+                .zip(old)
+                .filter(|(_, old_arg)| {
+                    direction.is_none_or(|direction| arg_direction(old_arg) == direction)
+                })
+                .map(|(arg, _)| arg.ty().to_string())
+                .collect::<String>()
+                == old_sig;
+        if old_sig != new_sig && !explained {
+            let kind = ChangeKind::SignatureChanged {
+                direction,
+                old: old_sig,
+                new: new_sig,
+            };
+            report.push(item, kind, true, true);
+        }
+    }
+
+    if same_count {
+        for (index, (old_arg, new_arg)) in old.iter().zip(new).enumerate() {
+            if old_arg.name() != new_arg.name() {
+                let kind = ChangeKind::ArgRenamed {
+                    index,
+                    old: old_arg.name().map(String::from),
+                    new: new_arg.name().map(String::from),
+                };
+                report.push(item, kind, false, false);
+            }
+        }
+    }
+}
+
+fn compare_annotations(item: &str, old: &[Annotation], new: &[Annotation], report: &mut Report) {
+    for old_annotation in old {
+        let name = old_annotation.name();
+        let new_value = new.iter().find(|a| a.name() == name).map(Annotation::value);
+        if new_value == Some(old_annotation.value()) {
+            continue;
+        }
+
+        let (clients, servers) = annotation_impact(name, Some(old_annotation.value()), new_value);
+        let kind = match new_value {
+            Some(new_value) => ChangeKind::AnnotationChanged {
+                name: name.to_string(),
+                old: old_annotation.value().to_string(),
+                new: new_value.to_string(),
+            },
+            None => ChangeKind::AnnotationRemoved {
+                name: name.to_string(),
+                value: old_annotation.value().to_string(),
+            },
+        };
+        report.push(item, kind, clients, servers);
+    }
+
+    for new_annotation in new {
+        let name = new_annotation.name();
+        if !old.iter().any(|a| a.name() == name) {
+            let (clients, servers) = annotation_impact(name, None, Some(new_annotation.value()));
+            let kind = ChangeKind::AnnotationAdded {
+                name: name.to_string(),
+                value: new_annotation.value().to_string(),
+            };
+            report.push(item, kind, clients, servers);
+        }
+    }
+}
+
+/// Whether an annotation change breaks clients and servers respectively.
+///
+/// Only annotations which change behavior on the bus can break anything.
+fn annotation_impact(name: &str, old: Option<&str>, new: Option<&str>) -> (bool, bool) {
+    match name {
+        // Clients waiting for a reply that never comes, or servers that now have to reply.
+        NO_REPLY => {
+            let (old, new) = (old == Some("true"), new == Some("true"));
+            (!old && new, old && !new)
+        }
+        // A weaker guarantee breaks clients, a stronger one breaks servers.
+        EMITS_CHANGED_SIGNAL => {
+            let strength = |value: Option<&str>| match value.map(str::parse) {
+                None => Some(2),
+                Some(Ok(EmitsChangedSignal::False)) => Some(0),
+                Some(Ok(EmitsChangedSignal::Invalidates)) => Some(1),
+                Some(Ok(EmitsChangedSignal::True)) => Some(2),
+                Some(Ok(EmitsChangedSignal::Const)) => Some(3),
+                Some(Err(_)) => None,
+            };
+            match (strength(old), strength(new)) {
+                (Some(old), Some(new)) => (new < old, new > old),
+                _ => (true, true),
+            }
+        }
+        _ => (false, false),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn node(interface: &str) -> Node<'static> {
+        Node::from_reader(format!("<node>{interface}</node>").as_bytes()).unwrap()
+    }
+
+    fn changes(old: &str, new: &str) -> Vec<(String, Impact, Impact)> {
+        compare(&node(old), &node(new))
+            .changes
+            .into_iter()
+            .map(|c| (c.kind.to_string(), c.clients, c.servers))
+            .collect()
+    }
+
+    use Impact::{Breaking, NonBreaking};
+
+    #[test]
+    fn test_interfaces_added_and_removed() {
+        let report = compare(
+            &node(r#"<interface name="org.example.Old"/>"#),
+            &node(r#"<interface name="org.example.New"/>"#),
+        );
+
+        assert_eq!(report.changes.len(), 2);
+        assert_eq!(report.changes[0].item, "org.example.Old");
+        assert_eq!(report.changes[0].kind, ChangeKind::InterfaceRemoved);
+        assert_eq!(report.changes[1].kind, ChangeKind::InterfaceAdded);
+    }
+
+    #[test]
+    fn test_method_changes() {
+        let old = r#"<interface name="org.example.Node">
+            <method name="Move">
+                <arg name="x" type="i" direction="in"/>
+                <arg name="ok" type="b" direction="out"/>
+            </method>
+            <method name="Reset"/>
+        </interface>"#;
+        let new = r#"<interface name="org.example.Node">
+            <method name="Move">
+                <arg name="dx" type="i" direction="in"/>
+                <arg name="ok" type="u" direction="out"/>
+            </method>
+            <method name="Reset">
+                <annotation name="org.freedesktop.DBus.Method.NoReply" value="true"/>
+            </method>
+        </interface>"#;
+
+        assert_eq!(
+            changes(old, new),
+            [
+                (
+                    "output signature changed from `b` to `u`".to_string(),
+                    Breaking,
+                    Breaking
+                ),
+                (
+                    "argument 0 renamed from `x` to `dx`".to_string(),
+                    NonBreaking,
+                    NonBreaking
+                ),
+                (
+                    "annotation `org.freedesktop.DBus.Method.NoReply` added with value `true`"
+                        .to_string(),
+                    Breaking,
+                    NonBreaking
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_direction_change_is_reported_once() {
+        let old = r#"<interface name="org.example.Node">
+            <method name="Swap"><arg type="s" direction="in"/></method>
+        </interface>"#;
+        let new = r#"<interface name="org.example.Node">
+            <method name="Swap"><arg type="s" direction="out"/></method>
+        </interface>"#;
+
+        assert_eq!(
+            changes(old, new),
+            [(
+                "argument 0 changed direction from `in` to `out`".to_string(),
+                Breaking,
+                Breaking
+            )]
+        );
+    }
+
+    #[test]
+    fn test_direction_change_keeps_other_signature_changes() {
+        let old = r#"<interface name="org.example.Node">
+            <method name="Resize">
+                <arg name="size" type="u" direction="in"/>
+                <arg name="scale" type="d" direction="in"/>
+            </method>
+        </interface>"#;
+        let new = r#"<interface name="org.example.Node">
+            <method name="Resize">
+                <arg name="size" type="t" direction="in"/>
+                <arg name="scale" type="d" direction="out"/>
+            </method>
+        </interface>"#;
+
+        assert_eq!(
+            changes(old, new),
+            [
+                (
+                    "argument 1 changed direction from `in` to `out`".to_string(),
+                    Breaking,
+                    Breaking
+                ),
+                (
+                    "input signature changed from `ud` to `t`".to_string(),
+                    Breaking,
+                    Breaking
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_property_changes() {
+        let old = r#"<interface name="org.example.Node">
+            <property name="Size" type="u" access="readwrite"/>
+            <property name="Name" type="s" access="read"/>
+        </interface>"#;
+        let new = r#"<interface name="org.example.Node">
+            <property name="Size" type="u" access="read"/>
+            <property name="Name" type="s" access="read">
+                <annotation name="org.freedesktop.DBus.Property.EmitsChangedSignal" value="const"/>
+            </property>
+        </interface>"#;
+
+        assert_eq!(
+            changes(old, new),
+            [
+                (
+                    "access changed from `readwrite` to `read`".to_string(),
+                    Breaking,
+                    NonBreaking
+                ),
+                (
+                    "annotation `org.freedesktop.DBus.Property.EmitsChangedSignal` added with value `const`"
+                        .to_string(),
+                    NonBreaking,
+                    Breaking
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_report_display() {
+        let report = compare(
+            &node(r#"<interface name="org.example.Node"><signal name="Gone"/></interface>"#),
+            &node(r#"<interface name="org.example.Node"/>"#),
+        );
+
+        assert_eq!(
+            report.to_string(),
+            "1 change(s), 1 breaking for clients, 0 breaking for servers.\n\
+             \n\
+             | Item | Change | Clients | Servers |\n\
+             | --- | --- | --- | --- |\n\
+             | `org.example.Node.Gone` | signal removed | breaking | non-breaking |\n"
+        );
+        assert_eq!(CompatReport::default().to_string(), "No changes.\n");
+    }
+}
@@ -0,0 +1,106 @@
+//! Transparent decompression of XML.
+//!
+//! Compressed data is recognized by its magic number, not by its file name. Each format is
+//! behind a cargo feature, `gzip` and `zstd`.
+
+use std::path::Path;
+
+use crate::Result;
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// File name extensions of XML files, compressed in a format this build can read.
+pub(crate) const XML_EXTENSIONS: &[&str] = &[
+    ".xml",
+    #[cfg(feature = "gzip")]
+    ".xml.gz",
+    #[cfg(feature = "zstd")]
+    ".xml.zst",
+];
+
+/// Whether `bytes` are compressed, in any format, readable or not.
+pub(crate) fn is_compressed(bytes: &[u8]) -> bool {
+    bytes.starts_with(&GZIP_MAGIC) || bytes.starts_with(&ZSTD_MAGIC)
+}
+
+/// Read the file at `path`, decompressed if compressed, into text.
+pub(crate) fn read_to_string(path: &Path) -> Result<String> {
+    decompress_to_string(std::fs::read(path)?)
+}
+
+/// Decompress `bytes`, if compressed, into text.
+pub(crate) fn decompress_to_string(bytes: Vec<u8>) -> Result<String> {
+    let bytes = if bytes.starts_with(&GZIP_MAGIC) {
+        gunzip(&bytes)?
+    } else if bytes.starts_with(&ZSTD_MAGIC) {
+        unzstd(&bytes)?
+    } else {
+        bytes
+    };
+
+    Ok(String::from_utf8(bytes)?)
+}
+
+#[cfg(feature = "gzip")]
+fn gunzip(bytes: &[u8]) -> Result<Vec<u8>> {
+    use std::io::Read;
+
+    let mut decompressed = Vec::new();
+    flate2::read::MultiGzDecoder::new(bytes).read_to_end(&mut decompressed)?;
+    Ok(decompressed)
+}
+
+#[cfg(not(feature = "gzip"))]
+fn gunzip(_bytes: &[u8]) -> Result<Vec<u8>> {
+    Err("XML is gzip compressed, enable the `gzip` feature to read it".into())
+}
+
+#[cfg(feature = "zstd")]
+fn unzstd(bytes: &[u8]) -> Result<Vec<u8>> {
+    Ok(zstd::decode_all(bytes)?)
+}
+
+#[cfg(not(feature = "zstd"))]
+fn unzstd(_bytes: &[u8]) -> Result<Vec<u8>> {
+    Err("XML is zstd compressed, enable the `zstd` feature to read it".into())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const XML: &str = r#"<node><interface name="org.example.Node"/></node>"#;
+
+    #[test]
+    fn test_plain_text_is_passed_through() {
+        assert_eq!(decompress_to_string(XML.into()).unwrap(), XML);
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn test_gzip() {
+        use std::io::Write;
+
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(XML.as_bytes()).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        assert_eq!(decompress_to_string(compressed).unwrap(), XML);
+    }
+
+    #[cfg(not(feature = "gzip"))]
+    #[test]
+    fn test_gzip_without_feature() {
+        let error = decompress_to_string(vec![0x1f, 0x8b, 0x08]).unwrap_err();
+        assert!(error.to_string().contains("`gzip` feature"));
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn test_zstd() {
+        let compressed = zstd::encode_all(XML.as_bytes(), 0).unwrap();
+
+        assert_eq!(decompress_to_string(compressed).unwrap(), XML);
+    }
+}
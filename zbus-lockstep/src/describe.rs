@@ -0,0 +1,451 @@
+//! Description of interfaces in Rust, to generate their XML from.
+//!
+//! Where the Rust code comes first, the XML can be generated from the types used in it, and
+//! then locked in with the validation tools like any other XML.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use zbus_lockstep::{
+//!     describe::{InterfaceDescription, MethodDescription, PropertyDescription},
+//!     PropertyAccess,
+//! };
+//!
+//! let interface = InterfaceDescription::new("org.example.Garden")
+//!     .method(
+//!         MethodDescription::new("Plant")
+//!             .arg::<String>("seed")
+//!             .reply::<bool>("planted"),
+//!     )
+//!     .property(PropertyDescription::new::<u32>("Plants", PropertyAccess::Read));
+//!
+//! let node = interface.to_node().unwrap();
+//! let property = &node.interfaces()[0].properties()[0];
+//!
+//! assert_eq!(property.ty().to_string(), "u");
+//! ```
+
+use std::fmt::Write;
+
+use zbus_xml::{ArgDirection, Node, PropertyAccess};
+use zvariant::Type;
+
+use crate::{annotations::NO_REPLY, dom::escape, lint::split_signature, Result};
+
+/// The document type of introspection data.
+const DOCTYPE: &str = "<!DOCTYPE node PUBLIC \"-//freedesktop//DTD D-BUS Object Introspection 1.0//EN\"\n \"http://www.freedesktop.org/standards/dbus/1.0/introspect.dtd\">\n";
+
+/// Arguments that share a type, named after the fields of a struct or after the whole type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Args {
+    names: Vec<String>,
+    signature: String,
+    direction: Option<ArgDirection>,
+}
+
+impl Args {
+    fn new<T: Type + ?Sized>(names: &[&str], direction: Option<ArgDirection>) -> Self {
+        Args {
+            names: names.iter().map(|name| name.to_string()).collect(),
+            signature: T::SIGNATURE.to_string(),
+            direction,
+        }
+    }
+
+    /// Write an `<arg>` per name, splitting the fields of a struct over several names.
+    fn write(&self, member: &str, xml: &mut String) -> Result<()> {
+        let types = match self.names.as_slice() {
+            [_] => vec![self.signature.as_str()],
+            names => {
+                let fields = self
+                    .signature
+                    .strip_prefix('(')
+                    .and_then(|fields| fields.strip_suffix(')'))
+                    .map(split_signature)
+                    .transpose()?
+                    .unwrap_or_default();
+                if fields.len() != names.len() {
+                    return Err(format!(
+                        "Member \"{member}\": {} argument names given for \"{}\".",
+                        names.len(),
+                        self.signature
+                    )
+                    .into());
+                }
+                fields
+            }
+        };
+
+        for (name, ty) in self.names.iter().zip(types) {
+            let _ = write!(
+                xml,
+                "      <arg name=\"{}\" type=\"{}\"",
+                escape(name, true),
+                escape(ty, true)
+            );
+            match self.direction {
+                Some(ArgDirection::In) => xml.push_str(" direction=\"in\""),
+                Some(ArgDirection::Out) => xml.push_str(" direction=\"out\""),
+                None => {}
+            }
+            xml.push_str("/>\n");
+        }
+        Ok(())
+    }
+}
+
+/// A method of an [`InterfaceDescription`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MethodDescription {
+    name: String,
+    args: Vec<Args>,
+    annotations: Vec<(String, String)>,
+}
+
+impl MethodDescription {
+    pub fn new(name: impl Into<String>) -> Self {
+        MethodDescription {
+            name: name.into(),
+            args: Vec::new(),
+            annotations: Vec::new(),
+        }
+    }
+
+    /// Add an input argument of type `T`.
+    #[must_use]
+    pub fn arg<T: Type + ?Sized>(self, name: &str) -> Self {
+        self.args::<T>(&[name])
+    }
+
+    /// Add an input argument per field of the struct `T`, or a single one for a single name.
+    #[must_use]
+    pub fn args<T: Type + ?Sized>(mut self, names: &[&str]) -> Self {
+        self.args
+            .push(Args::new::<T>(names, Some(ArgDirection::In)));
+        self
+    }
+
+    /// Add an output argument of type `T`.
+    #[must_use]
+    pub fn reply<T: Type + ?Sized>(self, name: &str) -> Self {
+        self.replies::<T>(&[name])
+    }
+
+    /// Add an output argument per field of the struct `T`, or a single one for a single name.
+    #[must_use]
+    pub fn replies<T: Type + ?Sized>(mut self, names: &[&str]) -> Self {
+        self.args
+            .push(Args::new::<T>(names, Some(ArgDirection::Out)));
+        self
+    }
+
+    /// Mark the method as not expecting a reply.
+    #[must_use]
+    pub fn no_reply(self) -> Self {
+        self.annotation(NO_REPLY, "true")
+    }
+
+    #[must_use]
+    pub fn annotation(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.annotations.push((name.into(), value.into()));
+        self
+    }
+}
+
+/// A signal of an [`InterfaceDescription`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignalDescription {
+    name: String,
+    args: Vec<Args>,
+    annotations: Vec<(String, String)>,
+}
+
+impl SignalDescription {
+    pub fn new(name: impl Into<String>) -> Self {
+        SignalDescription {
+            name: name.into(),
+            args: Vec::new(),
+            annotations: Vec::new(),
+        }
+    }
+
+    /// Add an argument of type `T`.
+    #[must_use]
+    pub fn arg<T: Type + ?Sized>(self, name: &str) -> Self {
+        self.args::<T>(&[name])
+    }
+
+    /// Add an argument per field of the struct `T`, or a single one for a single name.
+    #[must_use]
+    pub fn args<T: Type + ?Sized>(mut self, names: &[&str]) -> Self {
+        self.args.push(Args::new::<T>(names, None));
+        self
+    }
+
+    #[must_use]
+    pub fn annotation(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.annotations.push((name.into(), value.into()));
+        self
+    }
+}
+
+/// A property of an [`InterfaceDescription`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PropertyDescription {
+    name: String,
+    signature: String,
+    access: PropertyAccess,
+    annotations: Vec<(String, String)>,
+}
+
+impl PropertyDescription {
+    /// A property of type `T`.
+    pub fn new<T: Type + ?Sized>(name: impl Into<String>, access: PropertyAccess) -> Self {
+        PropertyDescription {
+            name: name.into(),
+            signature: T::SIGNATURE.to_string(),
+            access,
+            annotations: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn annotation(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.annotations.push((name.into(), value.into()));
+        self
+    }
+}
+
+/// An interface described in Rust.
+///
+/// Members are written to XML in the order they are added, methods first, then signals and
+/// properties.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InterfaceDescription {
+    name: String,
+    methods: Vec<MethodDescription>,
+    signals: Vec<SignalDescription>,
+    properties: Vec<PropertyDescription>,
+    annotations: Vec<(String, String)>,
+}
+
+impl InterfaceDescription {
+    pub fn new(name: impl Into<String>) -> Self {
+        InterfaceDescription {
+            name: name.into(),
+            methods: Vec::new(),
+            signals: Vec::new(),
+            properties: Vec::new(),
+            annotations: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn method(mut self, method: MethodDescription) -> Self {
+        self.methods.push(method);
+        self
+    }
+
+    #[must_use]
+    pub fn signal(mut self, signal: SignalDescription) -> Self {
+        self.signals.push(signal);
+        self
+    }
+
+    #[must_use]
+    pub fn property(mut self, property: PropertyDescription) -> Self {
+        self.properties.push(property);
+        self
+    }
+
+    #[must_use]
+    pub fn annotation(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.annotations.push((name.into(), value.into()));
+        self
+    }
+
+    /// The XML of a node holding only this interface.
+    pub fn to_xml(&self) -> Result<String> {
+        to_xml([self])
+    }
+
+    /// The `zbus_xml::Node` of a node holding only this interface.
+    pub fn to_node(&self) -> Result<Node<'static>> {
+        to_node([self])
+    }
+
+    fn write(&self, xml: &mut String) -> Result<()> {
+        let _ = writeln!(xml, "  <interface name=\"{}\">", escape(&self.name, true));
+
+        for method in &self.methods {
+            let _ = write!(xml, "    <method name=\"{}\"", escape(&method.name, true));
+            write_members(
+                &method.name,
+                &method.args,
+                &method.annotations,
+                "method",
+                xml,
+            )?;
+        }
+        for signal in &self.signals {
+            let _ = write!(xml, "    <signal name=\"{}\"", escape(&signal.name, true));
+            write_members(
+                &signal.name,
+                &signal.args,
+                &signal.annotations,
+                "signal",
+                xml,
+            )?;
+        }
+        for property in &self.properties {
+            split_signature(&property.signature)?;
+            let access = match property.access {
+                PropertyAccess::Read => "read",
+                PropertyAccess::Write => "write",
+                PropertyAccess::ReadWrite => "readwrite",
+            };
+            let _ = write!(
+                xml,
+                "    <property name=\"{}\" type=\"{}\" access=\"{access}\"",
+                escape(&property.name, true),
+                escape(&property.signature, true)
+            );
+            write_members(&property.name, &[], &property.annotations, "property", xml)?;
+        }
+
+        write_annotations(&self.annotations, "    ", xml);
+        xml.push_str("  </interface>\n");
+        Ok(())
+    }
+}
+
+/// Finish the start tag of a member, and write its arguments, annotations and end tag.
+fn write_members(
+    member: &str,
+    args: &[Args],
+    annotations: &[(String, String)],
+    element: &str,
+    xml: &mut String,
+) -> Result<()> {
+    if args.is_empty() && annotations.is_empty() {
+        xml.push_str("/>\n");
+        return Ok(());
+    }
+
+    xml.push_str(">\n");
+    for args in args {
+        args.write(member, xml)?;
+    }
+    write_annotations(annotations, "      ", xml);
+    let _ = writeln!(xml, "    </{element}>");
+    Ok(())
+}
+
+fn write_annotations(annotations: &[(String, String)], indent: &str, xml: &mut String) {
+    for (name, value) in annotations {
+        let _ = writeln!(
+            xml,
+            "{indent}<annotation name=\"{}\" value=\"{}\"/>",
+            escape(name, true),
+            escape(value, true)
+        );
+    }
+}
+
+/// The introspection XML of a node holding `interfaces`.
+pub fn to_xml<'i>(
+    interfaces: impl IntoIterator<Item = &'i InterfaceDescription>,
+) -> Result<String> {
+    let mut xml = String::from(DOCTYPE);
+    xml.push_str("<node>\n");
+    for interface in interfaces {
+        interface.write(&mut xml)?;
+    }
+    xml.push_str("</node>\n");
+    Ok(xml)
+}
+
+/// The `zbus_xml::Node` of a node holding `interfaces`.
+pub fn to_node<'i>(
+    interfaces: impl IntoIterator<Item = &'i InterfaceDescription>,
+) -> Result<Node<'static>> {
+    let xml = to_xml(interfaces)?;
+    Ok(Node::from_reader(xml.as_bytes())?)
+}
+
+#[cfg(test)]
+mod test {
+    use zvariant::OwnedObjectPath;
+
+    use super::*;
+    use crate::compat::compare;
+
+    #[derive(Type)]
+    #[allow(dead_code)]
+    struct Alert {
+        urgent: bool,
+        color: String,
+        volume: f64,
+    }
+
+    fn node_interface() -> InterfaceDescription {
+        InterfaceDescription::new("org.example.Node")
+            .method(
+                MethodDescription::new("RequestName")
+                    .arg::<str>("apple")
+                    .arg::<u32>("orange")
+                    .reply::<u32>("grape"),
+            )
+            .method(MethodDescription::new("Ping").no_reply())
+            .signal(SignalDescription::new("AddNode").arg::<(String, OwnedObjectPath)>("nodeAdded"))
+            .signal(SignalDescription::new("Alert").args::<Alert>(&["urgent", "color", "volume"]))
+            .signal(
+                SignalDescription::new("RemoveNode")
+                    .arg::<(String, OwnedObjectPath)>("nodeRemoved"),
+            )
+            .property(PropertyDescription::new::<Vec<String>>(
+                "Features",
+                PropertyAccess::Read,
+            ))
+    }
+
+    #[test]
+    fn test_described_interface_matches_xml() {
+        let xml = std::fs::read_to_string("../xml/test_definition_file.xml").unwrap();
+        let node = Node::from_reader(xml.as_bytes()).unwrap();
+
+        let described = node_interface().to_node().unwrap();
+
+        let report = compare(&node, &described);
+        assert!(report.is_empty(), "{report}");
+    }
+
+    #[test]
+    fn test_to_xml() {
+        let xml = InterfaceDescription::new("org.example.Bell")
+            .method(MethodDescription::new("Ring").arg::<u8>("times"))
+            .signal(SignalDescription::new("Rung"))
+            .to_xml()
+            .unwrap();
+
+        assert_eq!(
+            xml.strip_prefix(DOCTYPE).unwrap(),
+            "<node>\n  \
+               <interface name=\"org.example.Bell\">\n    \
+                 <method name=\"Ring\">\n      \
+                   <arg name=\"times\" type=\"y\" direction=\"in\"/>\n    \
+                 </method>\n    \
+                 <signal name=\"Rung\"/>\n  \
+               </interface>\n\
+             </node>\n"
+        );
+    }
+
+    #[test]
+    fn test_args_must_match_struct_fields() {
+        let interface = InterfaceDescription::new("org.example.Node")
+            .signal(SignalDescription::new("Alert").args::<Alert>(&["urgent", "color"]));
+
+        assert!(interface.to_xml().is_err());
+    }
+}
@@ -0,0 +1,310 @@
+//! Schemas of `a{sv}` dictionaries, declared with an annotation.
+//!
+//! Option dictionaries, such as the `hints` of `org.freedesktop.Notifications.Notify`, allow a
+//! fixed set of keys, each with a value of a fixed type, which specifications only describe in
+//! prose. The [`DICT_SCHEMA`] annotation on an argument or property lists them, so a struct
+//! with zvariant's `dict` signature can be kept in lockstep with the XML.
+
+use std::{fmt, io::Read, str::FromStr};
+
+use zvariant::Signature;
+
+use crate::{
+    annotations::ListAnnotation,
+    lint::split_signature,
+    LockstepError::{self, DictSchemaMismatch},
+    MsgType, Result,
+};
+
+/// Name of the annotation that lists the keys of an `a{sv}`, and the types of their values.
+///
+/// The value is a list of `key:signature` pairs, separated by whitespace or commas, e.g.
+/// `urgency:y category:s image-path:s`.
+pub const DICT_SCHEMA: &str = "org.zbus.lockstep.DictSchema";
+
+/// The keys of an `a{sv}` dictionary and the types of their values.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct DictSchema {
+    entries: Vec<(String, Signature)>,
+}
+
+impl DictSchema {
+    /// The keys and the types of their values, in the order of the annotation.
+    pub fn entries(&self) -> impl Iterator<Item = (&str, &Signature)> {
+        self.entries
+            .iter()
+            .map(|(key, signature)| (key.as_str(), signature))
+    }
+
+    /// The type of the value of `key`, if the schema has the key.
+    pub fn get(&self, key: &str) -> Option<&Signature> {
+        self.entries
+            .iter()
+            .find(|(entry, _)| entry == key)
+            .map(|(_, signature)| signature)
+    }
+
+    /// Check the fields of a struct, named `struct_name`, against the schema.
+    ///
+    /// Each field must hold a key of the schema, with the type of its value. A struct may hold
+    /// only some of the keys, as option dictionaries are usually sent with only some options.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use zbus_lockstep::{DictField, DictSchema};
+    /// use zvariant::Type;
+    ///
+    /// let schema: DictSchema = "urgency:y category:s".parse().unwrap();
+    /// let urgency = DictField {
+    ///     field: "urgency",
+    ///     key: "urgency",
+    ///     signature: u8::SIGNATURE,
+    /// };
+    ///
+    /// assert!(schema.validate("Hints", &[urgency.clone()]).is_ok());
+    /// assert!(schema
+    ///     .validate("Hints", &[DictField { key: "sound", ..urgency }])
+    ///     .is_err());
+    /// ```
+    pub fn validate(
+        &self,
+        struct_name: &str,
+        fields: &[DictField<'_>],
+    ) -> std::result::Result<(), LockstepError> {
+        let mut problems = Vec::new();
+        for (index, field) in fields.iter().enumerate() {
+            if fields[..index].iter().any(|other| other.key == field.key) {
+                problems.push(format!(
+                    "Field \"{}\" holds key \"{}\", which another field holds too.",
+                    field.field, field.key
+                ));
+            }
+
+            match self.get(field.key) {
+                None => problems.push(format!(
+                    "Field \"{}\" holds key \"{}\", which the schema does not have.",
+                    field.field, field.key
+                )),
+                Some(signature) if signature != field.signature => problems.push(format!(
+                    "Field \"{}\" has type \"{}\", but key \"{}\" has type \"{signature}\".",
+                    field.field, field.signature, field.key
+                )),
+                Some(_) => {}
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(DictSchemaMismatch(struct_name.to_owned(), problems))
+        }
+    }
+}
+
+impl ListAnnotation for DictSchema {
+    const NAME: &'static str = DICT_SCHEMA;
+
+    type Item = (String, Signature);
+
+    fn parse_item(entry: &str, _: &[Self::Item]) -> std::result::Result<Self::Item, String> {
+        // Signatures never hold a colon, keys might.
+        let (key, signature) = entry
+            .rsplit_once(':')
+            .ok_or(format!("No signature in \"{entry}\""))?;
+
+        // A variant holds a single complete type.
+        if !split_signature(signature).is_ok_and(|types| types.len() == 1) {
+            return Err(format!("\"{signature}\" is not a single complete type"));
+        }
+        let signature = Signature::from_str(signature).map_err(|e| e.to_string())?;
+        Ok((key.to_owned(), signature))
+    }
+
+    fn item_name((key, _): &Self::Item) -> &str {
+        key
+    }
+
+    fn fmt_item((key, signature): &Self::Item, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{key}:{signature}")
+    }
+
+    fn items(&self) -> &[Self::Item] {
+        &self.entries
+    }
+
+    fn from_items(entries: Vec<Self::Item>) -> std::result::Result<Self, String> {
+        Ok(DictSchema { entries })
+    }
+}
+
+impl FromStr for DictSchema {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Self::parse_list(s)
+    }
+}
+
+impl fmt::Display for DictSchema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_list(f)
+    }
+}
+
+/// A field of a struct holding an `a{sv}` dictionary.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct DictField<'a> {
+    /// The name of the field.
+    pub field: &'a str,
+    /// The key the field holds.
+    pub key: &'a str,
+    /// The signature of the field's type, or of `T` for an `Option<T>`.
+    pub signature: &'a Signature,
+}
+
+/// Retrieve the schema of an `a{sv}` argument or property, from its [`DICT_SCHEMA`] annotation.
+///
+/// For a method or signal, `arg_name` names the argument. Without it, the first argument with
+/// a schema is used. For a property, `arg_name` is ignored.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::{get_dict_schema, MsgType};
+/// use zvariant::Type;
+///
+/// let xml = r#"
+/// <node>
+/// <interface name="org.freedesktop.Notifications">
+///   <method name="Notify">
+///     <arg name="summary" type="s" direction="in"/>
+///     <arg name="hints" type="a{sv}" direction="in">
+///       <annotation name="org.zbus.lockstep.DictSchema" value="urgency:y category:s"/>
+///     </arg>
+///     <arg name="id" type="u" direction="out"/>
+///   </method>
+/// </interface>
+/// </node>
+/// "#;
+///
+/// let schema = get_dict_schema(
+///     xml.as_bytes(),
+///     "org.freedesktop.Notifications",
+///     "Notify",
+///     Some("hints"),
+///     MsgType::Method,
+/// )
+/// .unwrap();
+///
+/// assert_eq!(schema.get("urgency"), Some(u8::SIGNATURE));
+/// assert_eq!(schema.to_string(), "urgency:y category:s");
+/// ```
+pub fn get_dict_schema(
+    xml: impl Read,
+    interface_name: &str,
+    member_name: &str,
+    arg_name: Option<&str>,
+    msg_type: MsgType,
+) -> Result<DictSchema> {
+    DictSchema::read_annotated(
+        xml,
+        interface_name,
+        None,
+        member_name,
+        arg_name,
+        msg_type,
+        "a{sv}",
+    )
+}
+
+/// Like [`get_dict_schema`], from the interface at `object_path`.
+pub fn get_dict_schema_at(
+    xml: impl Read,
+    object_path: &str,
+    interface_name: &str,
+    member_name: &str,
+    arg_name: Option<&str>,
+    msg_type: MsgType,
+) -> Result<DictSchema> {
+    DictSchema::read_annotated(
+        xml,
+        interface_name,
+        Some(object_path),
+        member_name,
+        arg_name,
+        msg_type,
+        "a{sv}",
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use zvariant::{OwnedObjectPath, Type};
+
+    use super::*;
+
+    #[test]
+    fn test_parse_dict_schema() {
+        let schema: DictSchema = "urgency:y, x-vendor:key:s\n  image-data:(iiibiiay)"
+            .parse()
+            .unwrap();
+        let keys: Vec<_> = schema.entries().map(|(key, _)| key).collect();
+        assert_eq!(keys, ["urgency", "x-vendor:key", "image-data"]);
+        assert_eq!(schema.get("image-data").unwrap().to_string(), "(iiibiiay)");
+        assert_eq!(schema.get("category"), None);
+
+        assert_eq!("".parse::<DictSchema>().unwrap(), DictSchema::default());
+
+        for invalid in ["urgency", "urgency:yy", "urgency:a"] {
+            assert!(
+                invalid.parse::<DictSchema>().is_err(),
+                "\"{invalid}\" should not parse"
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_dict_schema() {
+        let schema: DictSchema = "urgency:y category:s".parse().unwrap();
+        let fields = [
+            DictField {
+                field: "urgency",
+                key: "urgency",
+                signature: u8::SIGNATURE,
+            },
+            DictField {
+                field: "category",
+                key: "category",
+                signature: OwnedObjectPath::SIGNATURE,
+            },
+            DictField {
+                field: "kind",
+                key: "category",
+                signature: String::SIGNATURE,
+            },
+            DictField {
+                field: "sound",
+                key: "sound-file",
+                signature: String::SIGNATURE,
+            },
+        ];
+
+        assert!(schema.validate("Hints", &fields[..1]).is_ok());
+
+        match schema.validate("Hints", &fields) {
+            Err(DictSchemaMismatch(name, problems)) => {
+                assert_eq!(name, "Hints");
+                assert_eq!(
+                    problems,
+                    [
+                        "Field \"category\" has type \"o\", but key \"category\" has type \"s\".",
+                        "Field \"kind\" holds key \"category\", which another field holds too.",
+                        "Field \"sound\" holds key \"sound-file\", which the schema does not have.",
+                    ]
+                );
+            }
+            result => panic!("Unexpected result: {result:?}"),
+        }
+    }
+}
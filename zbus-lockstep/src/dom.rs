@@ -0,0 +1,362 @@
+//! A minimal XML tree which, unlike `zbus_xml::Node`, keeps source locations, comments and
+//! elements or attributes `zbus_xml` does not know about.
+//!
+//! Used where the XML itself is inspected rather than the interfaces it describes.
+
+use std::fmt;
+
+use quick_xml::{
+    events::{BytesStart, Event},
+    Reader,
+};
+
+/// A position in an XML document, both line and column start at 1.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Attribute {
+    pub(crate) name: String,
+    pub(crate) value: String,
+    pub(crate) location: Location,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Element {
+    pub(crate) name: String,
+    pub(crate) attributes: Vec<Attribute>,
+    pub(crate) children: Vec<Content>,
+    pub(crate) location: Location,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Content {
+    Element(Element),
+    /// Text and where it starts, at its first character that is not whitespace if it has one.
+    Text(String, Location),
+    /// The inside of `<![CDATA[ ... ]]>` and where the section starts.
+    CData(String, Location),
+    Comment(String),
+    /// The inside of `<?xml ... ?>`.
+    Declaration(String),
+    /// The inside of `<?target ... ?>`.
+    Instruction(String),
+    /// The inside of `<!DOCTYPE ... >`.
+    DocType(String),
+}
+
+impl Element {
+    pub(crate) fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|attribute| attribute.name == name)
+            .map(|attribute| attribute.value.as_str())
+    }
+
+    pub(crate) fn elements(&self) -> impl Iterator<Item = &Element> {
+        self.children.iter().filter_map(|child| match child {
+            Content::Element(element) => Some(element),
+            _ => None,
+        })
+    }
+}
+
+/// An XML syntax error.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct SyntaxError {
+    pub(crate) message: String,
+    pub(crate) location: Location,
+}
+
+/// Maps byte offsets to line and column.
+struct Lines(Vec<usize>);
+
+impl Lines {
+    fn new(text: &str) -> Self {
+        let starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        Lines(starts)
+    }
+
+    fn locate(&self, text: &str, offset: usize) -> Location {
+        let offset = offset.min(text.len());
+        let line = self.0.partition_point(|&start| start <= offset);
+        let start = self.0[line - 1];
+        let column = text[start..offset].chars().count() + 1;
+        Location { line, column }
+    }
+}
+
+/// Parse `text` into its top-level content, which may hold more than one element.
+pub(crate) fn parse(text: &str) -> Result<Vec<Content>, SyntaxError> {
+    let lines = Lines::new(text);
+    let mut reader = Reader::from_str(text);
+
+    // Stack of open elements, the bottom entry collects the top-level content.
+    let mut top_level = Vec::new();
+    let mut open: Vec<Element> = Vec::new();
+
+    loop {
+        let offset = reader.buffer_position() as usize;
+        let event = reader.read_event().map_err(|e| SyntaxError {
+            message: e.to_string(),
+            location: lines.locate(text, reader.error_position() as usize),
+        })?;
+        let location = lines.locate(text, offset);
+
+        let content = match event {
+            Event::Start(start) => {
+                open.push(element(&start, offset, &lines, text)?);
+                continue;
+            }
+            Event::Empty(start) => Content::Element(element(&start, offset, &lines, text)?),
+            Event::End(_) => {
+                let element = open.pop().expect("end names are checked by the reader");
+                Content::Element(element)
+            }
+            Event::Text(raw) => {
+                let leading_whitespace = raw
+                    .iter()
+                    .take_while(|byte| byte.is_ascii_whitespace())
+                    .count();
+                let start = if leading_whitespace < raw.len() {
+                    lines.locate(text, offset + leading_whitespace)
+                } else {
+                    location
+                };
+                let text = raw.unescape().map_err(|e| SyntaxError {
+                    message: e.to_string(),
+                    location,
+                })?;
+                Content::Text(text.into_owned(), start)
+            }
+            Event::CData(data) => {
+                Content::CData(String::from_utf8_lossy(&data).into_owned(), location)
+            }
+            Event::Comment(comment) => {
+                Content::Comment(String::from_utf8_lossy(&comment).into_owned())
+            }
+            Event::Decl(declaration) => {
+                Content::Declaration(String::from_utf8_lossy(&declaration).into_owned())
+            }
+            Event::PI(instruction) => {
+                Content::Instruction(String::from_utf8_lossy(&instruction).into_owned())
+            }
+            Event::DocType(doctype) => {
+                Content::DocType(String::from_utf8_lossy(&doctype).into_owned())
+            }
+            Event::Eof => break,
+        };
+
+        match open.last_mut() {
+            Some(parent) => parent.children.push(content),
+            None => top_level.push(content),
+        }
+    }
+
+    if let Some(element) = open.last() {
+        return Err(SyntaxError {
+            message: format!("Unclosed element <{}>", element.name),
+            location: element.location,
+        });
+    }
+
+    Ok(top_level)
+}
+
+/// Build an element, without children, from its start tag found at `offset`.
+fn element(
+    start: &BytesStart<'_>,
+    offset: usize,
+    lines: &Lines,
+    text: &str,
+) -> Result<Element, SyntaxError> {
+    let location = lines.locate(text, offset);
+    let name = String::from_utf8_lossy(start.name().as_ref()).into_owned();
+    let mut attributes = Vec::new();
+
+    for attribute in start.attributes() {
+        let attribute = attribute.map_err(|e| SyntaxError {
+            message: e.to_string(),
+            location,
+        })?;
+        let key = attribute.key.as_ref();
+        // The iterator borrows keys from the tag, which starts after the `<` at `offset`.
+        let key_offset = offset + 1 + (key.as_ptr() as usize - start.as_ptr() as usize);
+        let value = attribute.unescape_value().map_err(|e| SyntaxError {
+            message: e.to_string(),
+            location,
+        })?;
+
+        attributes.push(Attribute {
+            name: String::from_utf8_lossy(key).into_owned(),
+            value: value.into_owned(),
+            location: lines.locate(text, key_offset),
+        });
+    }
+
+    Ok(Element {
+        name,
+        attributes,
+        children: Vec::new(),
+        location,
+    })
+}
+
+/// The top-level elements of parsed content.
+pub(crate) fn root_elements(content: &[Content]) -> impl Iterator<Item = &Element> {
+    content.iter().filter_map(|content| match content {
+        Content::Element(element) => Some(element),
+        _ => None,
+    })
+}
+
+/// Write content back to XML, as is.
+pub(crate) fn write(content: &[Content], out: &mut String) {
+    for content in content {
+        match content {
+            Content::Element(element) => {
+                out.push('<');
+                out.push_str(&element.name);
+                for attribute in &element.attributes {
+                    out.push(' ');
+                    out.push_str(&attribute.name);
+                    out.push_str("=\"");
+                    out.push_str(&escape(&attribute.value, true));
+                    out.push('"');
+                }
+                if element.children.is_empty() {
+                    out.push_str("/>");
+                } else {
+                    out.push('>');
+                    write(&element.children, out);
+                    out.push_str("</");
+                    out.push_str(&element.name);
+                    out.push('>');
+                }
+            }
+            Content::Text(text, _) => out.push_str(&escape(text, false)),
+            Content::CData(data, _) => {
+                out.push_str("<![CDATA[");
+                out.push_str(data);
+                out.push_str("]]>");
+            }
+            Content::Comment(comment) => {
+                out.push_str("<!--");
+                out.push_str(comment);
+                out.push_str("-->");
+            }
+            Content::Declaration(inner) | Content::Instruction(inner) => {
+                out.push_str("<?");
+                out.push_str(inner);
+                out.push_str("?>");
+            }
+            Content::DocType(inner) => {
+                out.push_str("<!DOCTYPE ");
+                out.push_str(inner);
+                out.push('>');
+            }
+        }
+    }
+}
+
+/// Escape text, or an attribute value, for XML.
+pub(crate) fn escape(text: &str, attribute: bool) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' if attribute => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_keeps_locations_and_comments() {
+        let xml = "<node>\n  <!-- doc -->\n  <interface name=\"org.example.Node\"/>\n</node>";
+        let content = parse(xml).unwrap();
+        let node = root_elements(&content).next().unwrap();
+
+        assert_eq!(node.location, Location { line: 1, column: 1 });
+        assert!(node
+            .children
+            .iter()
+            .any(|child| *child == Content::Comment(" doc ".to_string())));
+
+        let interface = node.elements().next().unwrap();
+        assert_eq!(interface.location, Location { line: 3, column: 3 });
+        assert_eq!(interface.attribute("name"), Some("org.example.Node"));
+        assert_eq!(
+            interface.attributes[0].location,
+            Location {
+                line: 3,
+                column: 14
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_locates_attributes_after_values_holding_their_name() {
+        let content = parse("<arg type=\"name\" name=\"type\"/>").unwrap();
+        let arg = root_elements(&content).next().unwrap();
+
+        let columns: Vec<_> = arg
+            .attributes
+            .iter()
+            .map(|attribute| attribute.location.column)
+            .collect();
+        assert_eq!(columns, [6, 18]);
+    }
+
+    #[test]
+    fn test_parse_locates_text() {
+        let xml = "<node>\n  <doc>\n    Some text\n  </doc><![CDATA[data]]>\n</node>";
+        let content = parse(xml).unwrap();
+        let node = root_elements(&content).next().unwrap();
+
+        let doc = node.elements().next().unwrap();
+        assert_eq!(
+            doc.children,
+            [Content::Text(
+                "\n    Some text\n  ".to_string(),
+                Location { line: 3, column: 5 }
+            )]
+        );
+        assert!(node.children.contains(&Content::CData(
+            "data".to_string(),
+            Location { line: 4, column: 9 }
+        )));
+    }
+
+    #[test]
+    fn test_write_round_trip() {
+        let xml = "<?xml version=\"1.0\"?>\n<node><!-- a &amp; b -->\n  <interface name=\"a&amp;b\"><annotation name=\"x.y\" value=\"&lt;\"/></interface>\n</node>";
+        let mut written = String::new();
+        write(&parse(xml).unwrap(), &mut written);
+        assert_eq!(written, xml);
+    }
+
+    #[test]
+    fn test_parse_reports_unclosed_element() {
+        let error = parse("<node>\n  <interface name=\"a.b\">\n</node>").unwrap_err();
+        assert_eq!(error.location.line, 3);
+    }
+}
@@ -0,0 +1,351 @@
+//! Validation against the structure described by the `DBus` introspection DTD.
+//!
+//! `zbus_xml` is lenient and skips misplaced elements and unknown attributes. Other consumers,
+//! such as `gdbus-codegen` or `qdbusxml2cpp`, may not be. This checks the document against
+//! `introspect.dtd`:
+//!
+//! ```text
+//! <!ELEMENT node (node|interface)*>
+//! <!ELEMENT interface (method|signal|property|annotation)*>
+//! <!ELEMENT method (arg|annotation)*>
+//! <!ELEMENT signal (arg|annotation)*>
+//! <!ELEMENT arg (annotation*)>
+//! <!ELEMENT property (annotation*)>
+//! <!ELEMENT annotation EMPTY>
+//! ```
+//!
+//! Elements in the `doc:` namespace are documentation and may appear anywhere, their content is
+//! not validated.
+
+use std::path::Path;
+
+use crate::{
+    compression,
+    dom::{self, Content, Element},
+    lint::Diagnostic,
+};
+
+/// The documentation namespace used by `gdbus-codegen` and friends.
+const DOC_NAMESPACE: &str = "http://www.freedesktop.org/dbus/1.0/doc.dtd";
+
+/// Allowed children, required and optional attributes of an element.
+struct Declaration {
+    children: &'static [&'static str],
+    required: &'static [&'static str],
+    optional: &'static [&'static str],
+    /// An attribute restricted to a set of values.
+    enumerated: Option<(&'static str, &'static [&'static str])>,
+}
+
+fn declaration(element: &str) -> Option<Declaration> {
+    let declaration = match element {
+        "node" => Declaration {
+            children: &["node", "interface"],
+            required: &[],
+            optional: &["name"],
+            enumerated: None,
+        },
+        "interface" => Declaration {
+            children: &["method", "signal", "property", "annotation"],
+            required: &["name"],
+            optional: &[],
+            enumerated: None,
+        },
+        "method" | "signal" => Declaration {
+            children: &["arg", "annotation"],
+            required: &["name"],
+            optional: &[],
+            enumerated: None,
+        },
+        "arg" => Declaration {
+            children: &["annotation"],
+            required: &["type"],
+            optional: &["name", "direction"],
+            enumerated: Some(("direction", &["in", "out"])),
+        },
+        "property" => Declaration {
+            children: &["annotation"],
+            required: &["name", "type", "access"],
+            optional: &[],
+            enumerated: Some(("access", &["read", "write", "readwrite"])),
+        },
+        "annotation" => Declaration {
+            children: &[],
+            required: &["name", "value"],
+            optional: &[],
+            enumerated: None,
+        },
+        _ => return None,
+    };
+    Some(declaration)
+}
+
+/// Validate a `DBus` XML file against the introspection DTD.
+///
+/// Returns every violation, in document order. An empty list means the document is valid.
+/// A compressed file is read like [`load_xml`](crate::load_xml) reads it.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::validate_dtd;
+///
+/// assert!(validate_dtd("../xml/test_definition_file.xml").is_empty());
+/// ```
+pub fn validate_dtd(path: impl AsRef<Path>) -> Vec<Diagnostic> {
+    let path = path.as_ref();
+
+    let mut diagnostics = match compression::read_to_string(path) {
+        Ok(text) => validate_dtd_str(&text),
+        Err(e) => vec![Diagnostic::without_location(format!(
+            "Failed to read file: {e}"
+        ))],
+    };
+
+    for diagnostic in &mut diagnostics {
+        diagnostic.path = Some(path.to_path_buf());
+    }
+
+    diagnostics
+}
+
+/// Validate `DBus` XML held in a string against the introspection DTD.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::validate_dtd_str;
+///
+/// let xml = r#"
+/// <node>
+///   <interface name="org.example.Node">
+///     <arg name="misplaced" type="s"/>
+///     <property name="Size" type="u" acces="read"/>
+///   </interface>
+/// </node>
+/// "#;
+///
+/// let violations = validate_dtd_str(xml);
+/// let lines: Vec<_> = violations.iter().map(|v| v.location.unwrap().line).collect();
+/// assert_eq!(lines, [4, 5, 5]);
+/// ```
+pub fn validate_dtd_str(xml: &str) -> Vec<Diagnostic> {
+    let content = match dom::parse(xml) {
+        Ok(content) => content,
+        Err(e) => return vec![Diagnostic::error(e.location, e.message)],
+    };
+
+    let mut diagnostics = Vec::new();
+    let mut roots = dom::root_elements(&content);
+
+    match roots.next() {
+        Some(root) if root.name == "node" => validate_element(root, &[], &mut diagnostics),
+        Some(root) => diagnostics.push(Diagnostic::error(
+            root.location,
+            format!("Root element must be <node>, found <{}>", root.name),
+        )),
+        None => diagnostics.push(Diagnostic::without_location("Document has no root <node>")),
+    }
+
+    for extra in roots {
+        diagnostics.push(Diagnostic::error(
+            extra.location,
+            format!("Unexpected second root element <{}>", extra.name),
+        ));
+    }
+
+    diagnostics
+}
+
+/// Validate an element which is known to the DTD.
+///
+/// `prefixes` holds the namespace prefixes declared by the element's ancestors, with the URI
+/// each is bound to.
+fn validate_element(
+    element: &Element,
+    prefixes: &[(&str, &str)],
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let declaration = declaration(&element.name).expect("element is declared by the DTD");
+
+    let mut prefixes = prefixes.to_vec();
+    for attribute in &element.attributes {
+        if let Some(prefix) = attribute.name.strip_prefix("xmlns:") {
+            prefixes.push((prefix, &attribute.value));
+        }
+    }
+
+    for attribute in &element.attributes {
+        let name = attribute.name.as_str();
+        if name == "xmlns" || name.starts_with("xmlns:") {
+            continue;
+        }
+        if let Some((prefix, _)) = name.split_once(':') {
+            check_prefix(prefix, &prefixes, attribute.location, diagnostics);
+            continue;
+        }
+        if !declaration.required.contains(&name) && !declaration.optional.contains(&name) {
+            diagnostics.push(Diagnostic::error(
+                attribute.location,
+                format!("Unknown attribute \"{name}\" on <{}>", element.name),
+            ));
+        }
+    }
+
+    for required in declaration.required {
+        if element.attribute(required).is_none() {
+            diagnostics.push(Diagnostic::error(
+                element.location,
+                format!(
+                    "<{}> is missing the required \"{required}\" attribute",
+                    element.name
+                ),
+            ));
+        }
+    }
+
+    if let Some((attribute, values)) = declaration.enumerated {
+        if let Some(value) = element.attribute(attribute) {
+            if !values.contains(&value) {
+                diagnostics.push(Diagnostic::error(
+                    element.location,
+                    format!(
+                        "Value \"{value}\" of \"{attribute}\" is not one of: {}",
+                        values.join(", ")
+                    ),
+                ));
+            }
+        }
+    }
+
+    for child in &element.children {
+        match child {
+            Content::Element(child) => {
+                if let Some((prefix, _)) = child.name.split_once(':') {
+                    let uri = check_prefix(prefix, &prefixes, child.location, diagnostics);
+                    if uri.is_some_and(|uri| uri != DOC_NAMESPACE) {
+                        diagnostics.push(Diagnostic::error(
+                            child.location,
+                            format!("Element <{}> from an unknown namespace", child.name),
+                        ));
+                    }
+                } else if !declaration.children.contains(&child.name.as_str()) {
+                    diagnostics.push(Diagnostic::error(
+                        child.location,
+                        format!("<{}> is not allowed in <{}>", child.name, element.name),
+                    ));
+                } else {
+                    validate_element(child, &prefixes, diagnostics);
+                }
+            }
+            Content::Text(text, location) | Content::CData(text, location)
+                if !text.trim().is_empty() =>
+            {
+                diagnostics.push(Diagnostic::error(
+                    *location,
+                    format!("Text is not allowed in <{}>", element.name),
+                ));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Check that a namespace prefix is declared, returning the URI it is bound to.
+fn check_prefix<'a>(
+    prefix: &str,
+    prefixes: &[(&str, &'a str)],
+    location: dom::Location,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Option<&'a str> {
+    let uri = prefixes
+        .iter()
+        .rev()
+        .find(|(declared, _)| *declared == prefix)
+        .map(|(_, uri)| *uri);
+
+    if uri.is_none() && prefix != "xml" {
+        diagnostics.push(Diagnostic::error(
+            location,
+            format!("Namespace prefix \"{prefix}\" is not declared"),
+        ));
+    }
+    uri
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_doc_namespace_is_allowed() {
+        let xml = r#"<node xmlns:doc="http://www.freedesktop.org/dbus/1.0/doc.dtd">
+  <interface name="org.example.Node">
+    <doc:doc><doc:description><doc:para>A node.</doc:para></doc:description></doc:doc>
+    <method name="Ping">
+      <doc:doc><doc:summary>Ping the node.</doc:summary></doc:doc>
+    </method>
+  </interface>
+</node>"#;
+
+        assert!(validate_dtd_str(xml).is_empty());
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn test_compressed_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("node.xml.zst");
+        let xml = std::fs::read("../xml/test_definition_file.xml").unwrap();
+        std::fs::write(&path, zstd::encode_all(&xml[..], 0).unwrap()).unwrap();
+
+        assert!(validate_dtd(&path).is_empty());
+    }
+
+    #[test]
+    fn test_violations() {
+        let xml = r#"<node>
+  <doc:doc/>
+  <interface name="org.example.Node" version="2">
+    <method name="Ping">
+      <property name="Size" type="u" access="read"/>
+      Pong
+    </method>
+    <signal>
+      <arg type="s" direction="both"/>
+      <annotation name="org.example.Annotation"/>
+    </signal>
+  </interface>
+</node>"#;
+
+        let violations: Vec<_> = validate_dtd_str(xml)
+            .into_iter()
+            .map(|v| (v.location.unwrap().line, v.message))
+            .collect();
+
+        assert_eq!(
+            violations,
+            [
+                (2, "Namespace prefix \"doc\" is not declared".to_string()),
+                (
+                    3,
+                    "Unknown attribute \"version\" on <interface>".to_string()
+                ),
+                (5, "<property> is not allowed in <method>".to_string()),
+                (6, "Text is not allowed in <method>".to_string()),
+                (
+                    8,
+                    "<signal> is missing the required \"name\" attribute".to_string()
+                ),
+                (
+                    9,
+                    "Value \"both\" of \"direction\" is not one of: in, out".to_string()
+                ),
+                (
+                    10,
+                    "<annotation> is missing the required \"value\" attribute".to_string()
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,257 @@
+//! Enumerations declared in XML, to keep `#[repr(u32)]` enums and the like in lockstep.
+//!
+//! Specifications document the values of enumerations, such as AT-SPI's `Role`, in prose. An
+//! annotation on an interface, named [`ENUM_PREFIX`] followed by the name of the enumeration,
+//! lists the variants and their values.
+
+use std::{fmt, io::Read, str::FromStr};
+
+use crate::{
+    annotations::ListAnnotation,
+    LockstepError::{self, EnumMismatch},
+    Result,
+};
+
+/// Prefix of the name of the annotations that declare enumerations.
+///
+/// The annotation `org.zbus.lockstep.Enum.Role` declares the enumeration `Role`. Its value
+/// lists the variants, separated by whitespace or commas, each with its value, `Name=value`,
+/// or without, in which case the value follows the one before, starting at zero:
+///
+/// ```xml
+/// <interface name="org.a11y.atspi.Accessible">
+///   <annotation name="org.zbus.lockstep.Enum.Role" value="Invalid AcceleratorLabel Alert"/>
+///   <annotation name="org.zbus.lockstep.Enum.Layer" value="Invalid=0 Widget=3 Mdi=4"/>
+/// </interface>
+/// ```
+pub const ENUM_PREFIX: &str = "org.zbus.lockstep.Enum.";
+
+/// An enumeration declared in XML: its variants and their values.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct Enumeration {
+    variants: Vec<(String, i64)>,
+}
+
+impl Enumeration {
+    /// The variants and their values, in the order of the annotation.
+    pub fn variants(&self) -> impl Iterator<Item = (&str, i64)> {
+        self.variants
+            .iter()
+            .map(|(name, value)| (name.as_str(), *value))
+    }
+
+    /// The value of the variant named `name`, if any.
+    pub fn get(&self, name: &str) -> Option<i64> {
+        self.variants
+            .iter()
+            .find(|(variant, _)| variant == name)
+            .map(|(_, value)| *value)
+    }
+
+    /// Check the variants of an enum, named `enum_name`, against the enumeration.
+    ///
+    /// `variants` holds the name and the discriminant of each variant. Variants missing from
+    /// the enum, variants not in the enumeration and variants with another value are reported.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use zbus_lockstep::Enumeration;
+    ///
+    /// let enumeration: Enumeration = "Invalid TitleBar MenuBar".parse().unwrap();
+    ///
+    /// assert!(enumeration
+    ///     .validate("Role", &[("Invalid", 0), ("TitleBar", 1), ("MenuBar", 2)])
+    ///     .is_ok());
+    /// assert!(enumeration
+    ///     .validate("Role", &[("Invalid", 0), ("MenuBar", 1)])
+    ///     .is_err());
+    /// ```
+    pub fn validate(
+        &self,
+        enum_name: &str,
+        variants: &[(&str, i64)],
+    ) -> std::result::Result<(), LockstepError> {
+        let mut problems = Vec::new();
+        for (name, value) in self.variants() {
+            match variants.iter().find(|(variant, _)| *variant == name) {
+                None => problems.push(format!("Variant \"{name}\" = {value} is missing.")),
+                Some((_, used)) if *used != value => problems.push(format!(
+                    "Variant \"{name}\" is {value} in XML, but {used} in the implementation."
+                )),
+                Some(_) => {}
+            }
+        }
+        for (name, value) in variants {
+            if self.get(name).is_none() {
+                problems.push(format!(
+                    "Variant \"{name}\" = {value} is not in the enumeration."
+                ));
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(EnumMismatch(enum_name.to_owned(), problems))
+        }
+    }
+}
+
+impl ListAnnotation for Enumeration {
+    const NAME: &'static str = ENUM_PREFIX;
+
+    type Item = (String, i64);
+
+    fn parse_item(
+        variant: &str,
+        previous: &[Self::Item],
+    ) -> std::result::Result<Self::Item, String> {
+        let (name, value) = match variant.split_once('=') {
+            Some((name, value)) => (name, parse_value(value)?),
+            None => {
+                let next = previous
+                    .last()
+                    .map_or(Some(0), |(_, value)| value.checked_add(1));
+                (variant, next.ok_or("Value out of range")?)
+            }
+        };
+        Ok((name.to_owned(), value))
+    }
+
+    fn item_name((name, _): &Self::Item) -> &str {
+        name
+    }
+
+    fn fmt_item((name, value): &Self::Item, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{name}={value}")
+    }
+
+    fn items(&self) -> &[Self::Item] {
+        &self.variants
+    }
+
+    fn from_items(variants: Vec<Self::Item>) -> std::result::Result<Self, String> {
+        Ok(Enumeration { variants })
+    }
+}
+
+impl FromStr for Enumeration {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Self::parse_list(s)
+    }
+}
+
+impl fmt::Display for Enumeration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_list(f)
+    }
+}
+
+/// A decimal or, prefixed with `0x`, hexadecimal value.
+pub(crate) fn parse_value(value: &str) -> std::result::Result<i64, String> {
+    let (digits, negative) = match value.strip_prefix('-') {
+        Some(digits) => (digits, true),
+        None => (value, false),
+    };
+    let parsed = match digits.strip_prefix("0x") {
+        Some(hex) => i64::from_str_radix(hex, 16),
+        None => digits.parse::<i64>(),
+    }
+    .map_err(|e| format!("Invalid value \"{value}\": {e}"))?;
+
+    Ok(if negative { -parsed } else { parsed })
+}
+
+/// Retrieve an enumeration declared on an interface with an [`ENUM_PREFIX`] annotation.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::get_enumeration;
+///
+/// let xml = r#"
+/// <node>
+/// <interface name="org.a11y.atspi.Accessible">
+///   <annotation name="org.zbus.lockstep.Enum.Role" value="Invalid AcceleratorLabel Alert"/>
+/// </interface>
+/// </node>
+/// "#;
+///
+/// let role = get_enumeration(xml.as_bytes(), "org.a11y.atspi.Accessible", "Role").unwrap();
+/// assert_eq!(role.get("Alert"), Some(2));
+/// ```
+pub fn get_enumeration(
+    xml: impl Read,
+    interface_name: &str,
+    enum_name: &str,
+) -> Result<Enumeration> {
+    Enumeration::read_declared(xml, interface_name, None, enum_name, "enumeration")
+}
+
+/// Like [`get_enumeration`], from the interface at `object_path`.
+pub fn get_enumeration_at(
+    xml: impl Read,
+    object_path: &str,
+    interface_name: &str,
+    enum_name: &str,
+) -> Result<Enumeration> {
+    Enumeration::read_declared(
+        xml,
+        interface_name,
+        Some(object_path),
+        enum_name,
+        "enumeration",
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_enumeration() {
+        let enumeration: Enumeration = "Invalid, Widget=3 Mdi\n  Window=0x10 Below=-1 Above"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            enumeration.to_string(),
+            "Invalid=0 Widget=3 Mdi=4 Window=16 Below=-1 Above=0"
+        );
+
+        for invalid in ["Widget=three", "Max=9223372036854775807 Over"] {
+            assert!(
+                invalid.parse::<Enumeration>().is_err(),
+                "\"{invalid}\" should not parse"
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_enumeration() {
+        let enumeration: Enumeration = "Invalid TitleBar MenuBar ScrollBar".parse().unwrap();
+        let variants = [
+            ("Invalid", 0),
+            ("MenuBar", 2),
+            ("ScrollBar", 4),
+            ("StatusBar", 5),
+        ];
+
+        match enumeration.validate("Role", &variants) {
+            Err(EnumMismatch(name, problems)) => {
+                assert_eq!(name, "Role");
+                assert_eq!(
+                    problems,
+                    [
+                        "Variant \"TitleBar\" = 1 is missing.",
+                        "Variant \"ScrollBar\" is 3 in XML, but 4 in the implementation.",
+                        "Variant \"StatusBar\" = 5 is not in the enumeration.",
+                    ]
+                );
+            }
+            result => panic!("Unexpected result: {result:?}"),
+        }
+    }
+}
@@ -1,3 +1,11 @@
+use zbus_xml::PropertyAccess;
+
+use crate::{access_str, EmitsChangedSignal};
+
+/// An error of the lookups and checks of this crate.
+///
+/// The variants that hold a list of differences, such as [`LockstepError::EnumMismatch`], hold
+/// every difference found rather than only the first.
 #[non_exhaustive]
 #[derive(Debug)]
 pub enum LockstepError {
@@ -5,6 +13,33 @@ pub enum LockstepError {
     InterfaceNotFound(String),
     MemberNotFound(String),
     PropertyNotFound(String),
+    ObjectPathNotFound(String),
+    /// The property name, the access mode declared in XML and the access mode provided, if any.
+    PropertyAccessMismatch(String, PropertyAccess, Option<PropertyAccess>),
+    /// The annotation name and the value that could not be understood.
+    InvalidAnnotationValue(String, String),
+    /// The property name, the value in XML and the value used by the implementation.
+    EmitsChangedSignalMismatch(String, EmitsChangedSignal, EmitsChangedSignal),
+    /// The method name and whether XML declares it `NoReply`.
+    NoReplyMismatch(String, bool),
+    /// The file that ends up including itself.
+    IncludeCycle(String),
+    /// The lockfile path and the changes it does not record.
+    LockfileOutdated(String, Vec<String>),
+    /// The path of a file that is not in canonical form, and the first line that differs.
+    NotCanonical(String, usize),
+    /// The interface name and the differences between a struct and its properties.
+    PropertyBundleMismatch(String, Vec<String>),
+    /// The struct name and the differences between its fields and a dictionary schema.
+    DictSchemaMismatch(String, Vec<String>),
+    /// The enum name and the differences between its strings and the allowed values.
+    AllowedValuesMismatch(String, Vec<String>),
+    /// The enum name and the differences between its variants and the enumeration in XML.
+    EnumMismatch(String, Vec<String>),
+    /// The type name and the differences between its flags and the flag set in XML.
+    FlagsMismatch(String, Vec<String>),
+    /// The argument or property name, the variant type in XML and the type used.
+    VariantTypeMismatch(String, String, String),
 }
 
 impl std::error::Error for LockstepError {}
@@ -24,6 +59,109 @@ impl std::fmt::Display for LockstepError {
             LockstepError::PropertyNotFound(name) => {
                 write!(f, "Property \"{name}\" not found.")
             }
+            LockstepError::ObjectPathNotFound(path) => {
+                write!(f, "Object path \"{path}\" not found.")
+            }
+            LockstepError::PropertyAccessMismatch(name, declared, Some(provided)) => {
+                write!(
+                    f,
+                    "Property \"{name}\" is declared \"{}\", but the implementation provides \"{}\".",
+                    access_str(*declared),
+                    access_str(*provided)
+                )
+            }
+            LockstepError::PropertyAccessMismatch(name, declared, None) => {
+                write!(
+                    f,
+                    "Property \"{name}\" is declared \"{}\", but the implementation provides neither getter nor setter.",
+                    access_str(*declared)
+                )
+            }
+            LockstepError::InvalidAnnotationValue(name, value) => {
+                write!(f, "Invalid value \"{value}\" for annotation \"{name}\".")
+            }
+            LockstepError::EmitsChangedSignalMismatch(name, declared, used) => {
+                write!(
+                    f,
+                    "Property \"{name}\" emits changed signal \"{declared}\" in XML, but \"{used}\" in the implementation."
+                )
+            }
+            LockstepError::NoReplyMismatch(name, true) => {
+                write!(
+                    f,
+                    "Method \"{name}\" is annotated `NoReply` in XML, but the implementation waits for a reply."
+                )
+            }
+            LockstepError::NoReplyMismatch(name, false) => {
+                write!(
+                    f,
+                    "Method \"{name}\" expects a reply in XML, but the implementation is declared `no_reply`."
+                )
+            }
+            LockstepError::IncludeCycle(path) => {
+                write!(f, "File \"{path}\" includes itself.")
+            }
+            LockstepError::LockfileOutdated(path, changes) => {
+                writeln!(f, "Lockfile \"{path}\" is outdated:")?;
+                for change in changes {
+                    writeln!(f, "  {change}")?;
+                }
+                write!(
+                    f,
+                    "Review the XML changes, then update the lockfile by running the tests with {}=1.",
+                    crate::UPDATE_LOCKFILE_ENV
+                )
+            }
+            LockstepError::NotCanonical(path, line) => {
+                write!(
+                    f,
+                    "File \"{path}\" is not in canonical form from line {line} on. Format it with `zbus_lockstep::fmt::Formatter::format_file`."
+                )
+            }
+            LockstepError::PropertyBundleMismatch(name, problems) => {
+                write!(f, "Struct does not match the properties of \"{name}\":")?;
+                for problem in problems {
+                    write!(f, "\n  {problem}")?;
+                }
+                Ok(())
+            }
+            LockstepError::VariantTypeMismatch(name, declared, used) => {
+                write!(
+                    f,
+                    "Variant \"{name}\" holds \"{declared}\" in XML, but \"{used}\" in the implementation."
+                )
+            }
+            LockstepError::EnumMismatch(name, problems) => {
+                write!(f, "Enum \"{name}\" does not match the enumeration in XML:")?;
+                for problem in problems {
+                    write!(f, "\n  {problem}")?;
+                }
+                Ok(())
+            }
+            LockstepError::FlagsMismatch(name, problems) => {
+                write!(f, "Flags \"{name}\" do not match the flag set in XML:")?;
+                for problem in problems {
+                    write!(f, "\n  {problem}")?;
+                }
+                Ok(())
+            }
+            LockstepError::AllowedValuesMismatch(name, problems) => {
+                write!(
+                    f,
+                    "Enum \"{name}\" does not match the allowed values in XML:"
+                )?;
+                for problem in problems {
+                    write!(f, "\n  {problem}")?;
+                }
+                Ok(())
+            }
+            LockstepError::DictSchemaMismatch(name, problems) => {
+                write!(f, "Struct \"{name}\" does not match the dictionary schema:")?;
+                for problem in problems {
+                    write!(f, "\n  {problem}")?;
+                }
+                Ok(())
+            }
         }
     }
 }
@@ -0,0 +1,249 @@
+//! Plain language descriptions of signatures.
+
+use std::fmt::Write;
+
+use zvariant::Signature;
+
+use crate::{codegen::rust_type, lint::split_signature};
+
+/// A complete type, or a message body of several, with its parts.
+struct TypeNode {
+    signature: String,
+    summary: String,
+    rust: String,
+    children: Vec<(&'static str, TypeNode)>,
+}
+
+impl TypeNode {
+    fn new(signature: &str) -> Self {
+        let bytes = signature.as_bytes();
+        let (summary, children) = match bytes.first() {
+            Some(b'a') if bytes.get(1) == Some(&b'{') => {
+                let entry = &signature[2..signature.len() - 1];
+                let types = split_signature(entry).unwrap_or_default();
+                let (key, value) = (TypeNode::new(types[0]), TypeNode::new(types[1]));
+                let summary = format!("dict: {} → {}", key.summary, value.summary);
+                (summary, vec![("key", key), ("value", value)])
+            }
+            Some(b'a') => {
+                let element = TypeNode::new(&signature[1..]);
+                let summary = format!("array of {}", element.brief());
+                (summary, vec![("element", element)])
+            }
+            Some(b'(') => {
+                let fields = split_signature(&signature[1..signature.len() - 1])
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|field| ("field", TypeNode::new(field)))
+                    .collect::<Vec<_>>();
+                let summary = format!("struct ({})", briefs(&fields));
+                (summary, fields)
+            }
+            Some(&byte) => (basic_name(byte).to_string(), Vec::new()),
+            None => ("nothing".to_string(), Vec::new()),
+        };
+
+        TypeNode {
+            signature: signature.to_string(),
+            summary,
+            rust: rust_type(signature).map_or_else(|_| "()".to_string(), |ty| idiomatic(&ty)),
+            children,
+        }
+    }
+
+    /// A message body of several complete types.
+    fn body(types: Vec<&str>) -> Self {
+        let args: Vec<_> = types
+            .into_iter()
+            .map(|ty| ("argument", TypeNode::new(ty)))
+            .collect();
+        let rust = args
+            .iter()
+            .map(|(_, arg)| arg.rust.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        TypeNode {
+            signature: args.iter().map(|(_, arg)| arg.signature.as_str()).collect(),
+            summary: format!("{} arguments ({})", args.len(), briefs(&args)),
+            rust: format!("({rust})"),
+            children: args,
+        }
+    }
+
+    /// A short description, used within that of a struct or array.
+    fn brief(&self) -> String {
+        let nested = self
+            .children
+            .iter()
+            .any(|(_, child)| !child.children.is_empty());
+        if self.summary.starts_with("dict") {
+            "dict".to_string()
+        } else if nested && self.summary.starts_with("struct") {
+            "struct".to_string()
+        } else {
+            self.summary.clone()
+        }
+    }
+
+    fn write(&self, role: &str, depth: usize, out: &mut String) {
+        let indent = "  ".repeat(depth);
+        let role = if role.is_empty() {
+            String::new()
+        } else {
+            format!("{role} ")
+        };
+        let _ = writeln!(
+            out,
+            "{indent}{role}`{}`: {}, `{}`",
+            self.signature, self.summary, self.rust
+        );
+        // Basic elements of arrays are already described by the array.
+        if self
+            .children
+            .iter()
+            .all(|(_, child)| child.children.is_empty())
+            && self.summary.starts_with("array")
+        {
+            return;
+        }
+        for (role, child) in &self.children {
+            child.write(role, depth + 1, out);
+        }
+    }
+}
+
+fn briefs(children: &[(&str, TypeNode)]) -> String {
+    children
+        .iter()
+        .map(|(_, child)| child.brief())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn basic_name(code: u8) -> &'static str {
+    match code {
+        b'y' => "byte",
+        b'b' => "boolean",
+        b'n' => "int16",
+        b'q' => "uint16",
+        b'i' => "int32",
+        b'u' => "uint32",
+        b'x' => "int64",
+        b't' => "uint64",
+        b'd' => "double",
+        b's' => "string",
+        b'o' => "object path",
+        b'g' => "signature",
+        b'v' => "variant",
+        b'h' => "file descriptor",
+        _ => "unknown",
+    }
+}
+
+/// A Rust type as it would be written with `zvariant` and `HashMap` imported.
+fn idiomatic(ty: &str) -> String {
+    ty.replace("::std::collections::", "")
+        .replace("::zvariant::", "")
+}
+
+/// Describe a signature in plain language, along with the idiomatic Rust type of each part.
+///
+/// The first line describes the whole signature. Each part of a container type follows on its
+/// own line, indented below the container.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::str::FromStr;
+///
+/// use zbus_lockstep::explain;
+/// use zvariant::Signature;
+///
+/// let signature = Signature::from_str("a{oa{sv}}").unwrap();
+///
+/// assert_eq!(
+///     explain(&signature),
+///     "`a{oa{sv}}`: dict: object path → dict: string → variant, \
+///      `HashMap<OwnedObjectPath, HashMap<String, OwnedValue>>`
+///   key `o`: object path, `OwnedObjectPath`
+///   value `a{sv}`: dict: string → variant, `HashMap<String, OwnedValue>`
+///     key `s`: string, `String`
+///     value `v`: variant, `OwnedValue`
+/// "
+/// );
+/// ```
+pub fn explain(signature: &Signature) -> String {
+    explain_str(&signature.to_string())
+}
+
+/// Like [`explain`], for a signature in a string, which may hold several complete types.
+pub(crate) fn explain_str(signature: &str) -> String {
+    let node = match split_signature(signature) {
+        Ok(types) if types.len() > 1 => TypeNode::body(types),
+        Ok(_) => TypeNode::new(signature),
+        Err(e) => return format!("`{signature}`: invalid signature, {e}\n"),
+    };
+
+    let mut out = String::new();
+    node.write("", 0, &mut out);
+    out
+}
+
+/// The message of a failed check of a type against XML, explaining both signatures.
+///
+/// Used by the tests the `validate` attribute and the code generators emit.
+pub fn explain_mismatch(xml: &Signature, ty: &Signature) -> String {
+    format!(
+        "The signature of the type does not match the XML.\n\nIn XML:\n{}\nOf the type:\n{}",
+        explain(xml),
+        explain(ty)
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_explain_struct() {
+        assert_eq!(
+            explain_str("((so)as)"),
+            "`((so)as)`: struct (struct (string, object path), array of string), `((String, OwnedObjectPath), Vec<String>)`
+  field `(so)`: struct (string, object path), `(String, OwnedObjectPath)`
+    field `s`: string, `String`
+    field `o`: object path, `OwnedObjectPath`
+  field `as`: array of string, `Vec<String>`
+"
+        );
+    }
+
+    #[test]
+    fn test_explain_body() {
+        assert_eq!(
+            explain_str("bsd"),
+            "`bsd`: 3 arguments (boolean, string, double), `(bool, String, f64)`
+  argument `b`: boolean, `bool`
+  argument `s`: string, `String`
+  argument `d`: double, `f64`
+"
+        );
+        assert_eq!(explain_str(""), "``: nothing, `()`\n");
+        assert!(explain_str("a{vs}").contains("invalid signature"));
+    }
+
+    #[test]
+    fn test_explain_array_of_structs() {
+        let signature: Signature = "a(ii)".parse().unwrap();
+        assert_eq!(explain(&signature), explain_str("a(ii)"));
+
+        assert_eq!(
+            explain_str("a(ii)"),
+            "`a(ii)`: array of struct (int32, int32), `Vec<(i32, i32)>`
+  element `(ii)`: struct (int32, int32), `(i32, i32)`
+    field `i`: int32, `i32`
+    field `i`: int32, `i32`
+"
+        );
+    }
+}
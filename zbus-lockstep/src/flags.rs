@@ -0,0 +1,272 @@
+//! Flag sets declared in XML, to keep `bitflags!` and `enumflags2` types in lockstep.
+//!
+//! Arguments of type `u` or `t`, or the words of an `au`, often hold a bitmask, such as
+//! AT-SPI's `StateSet`. An annotation on an interface, named [`FLAGS_PREFIX`] followed by the
+//! name of the flag set, lists the flags and their bit positions.
+
+use std::{fmt, io::Read, str::FromStr};
+
+use crate::{
+    annotations::ListAnnotation,
+    enumeration::parse_value,
+    LockstepError::{self, FlagsMismatch},
+    Result,
+};
+
+/// Prefix of the name of the annotations that declare flag sets.
+///
+/// The annotation `org.zbus.lockstep.Flags.State` declares the flag set `State`. Its value
+/// lists the flags, separated by whitespace or commas, each with its bit position,
+/// `Name=bit`, or without, in which case the flag takes the next bit, starting at bit zero:
+///
+/// ```xml
+/// <interface name="org.a11y.atspi.Accessible">
+///   <annotation name="org.zbus.lockstep.Flags.State" value="Invalid Active Armed Busy"/>
+///   <annotation name="org.zbus.lockstep.Flags.Urgency" value="Low=0 Critical=4"/>
+/// </interface>
+/// ```
+pub const FLAGS_PREFIX: &str = "org.zbus.lockstep.Flags.";
+
+/// A flag set declared in XML: its flags and their bit positions.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct FlagSet {
+    flags: Vec<(String, u32)>,
+}
+
+impl FlagSet {
+    /// The flags and their bit positions, in the order of the annotation.
+    pub fn flags(&self) -> impl Iterator<Item = (&str, u32)> {
+        self.flags.iter().map(|(name, bit)| (name.as_str(), *bit))
+    }
+
+    /// The bit position of the flag named `name`, if any.
+    pub fn bit(&self, name: &str) -> Option<u32> {
+        self.flags
+            .iter()
+            .find(|(flag, _)| flag == name)
+            .map(|(_, bit)| *bit)
+    }
+
+    /// The value of the flag named `name`, its bit set, if any.
+    pub fn value(&self, name: &str) -> Option<u64> {
+        self.bit(name).map(|bit| 1 << bit)
+    }
+
+    /// Check the flags of a type, named `type_name`, against the flag set.
+    ///
+    /// `flags` holds the name and the value of each flag. Flags missing from the type, flags
+    /// not in the flag set, flags with another value, values that are not a single bit and
+    /// flags overlapping each other are reported. Composite flags, such as an `ALL` that is
+    /// the union of other flags, are skipped unless the flag set names them.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use zbus_lockstep::FlagSet;
+    ///
+    /// let flag_set: FlagSet = "Active Armed Busy".parse().unwrap();
+    ///
+    /// assert!(flag_set
+    ///     .validate("State", &[("Active", 1), ("Armed", 2), ("Busy", 4)])
+    ///     .is_ok());
+    /// assert!(flag_set
+    ///     .validate("State", &[("Active", 1), ("Armed", 2), ("Busy", 3)])
+    ///     .is_err());
+    /// ```
+    pub fn validate(
+        &self,
+        type_name: &str,
+        flags: &[(&str, u64)],
+    ) -> std::result::Result<(), LockstepError> {
+        let mut problems = Vec::new();
+        for (name, bit) in self.flags() {
+            match flags.iter().find(|(flag, _)| *flag == name) {
+                None => problems.push(format!("Flag \"{name}\" = 1 << {bit} is missing.")),
+                Some((_, used)) if *used != 1 << bit => problems.push(format!(
+                    "Flag \"{name}\" is 1 << {bit} in XML, but {used:#x} in the implementation."
+                )),
+                Some(_) => {}
+            }
+        }
+        let flags: Vec<_> = flags
+            .iter()
+            .filter(|(name, value)| self.bit(name).is_some() || !is_union(*value, flags))
+            .collect();
+        for (index, (name, value)) in flags.iter().enumerate() {
+            if value.count_ones() != 1 {
+                problems.push(format!("Flag \"{name}\" = {value:#x} is not a single bit."));
+            }
+            for (other, _) in flags[..index]
+                .iter()
+                .filter(|(_, other)| other & value != 0)
+            {
+                problems.push(format!("Flag \"{name}\" overlaps flag \"{other}\"."));
+            }
+            if self.bit(name).is_none() {
+                problems.push(format!(
+                    "Flag \"{name}\" = {value:#x} is not in the flag set."
+                ));
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(FlagsMismatch(type_name.to_owned(), problems))
+        }
+    }
+}
+
+/// Whether `value` spans several bits, each set by one of the other `flags` within it.
+fn is_union(value: u64, flags: &[(&str, u64)]) -> bool {
+    let covered = flags
+        .iter()
+        .map(|(_, other)| *other)
+        .filter(|other| *other != value && other & !value == 0)
+        .fold(0, |covered, other| covered | other);
+
+    value.count_ones() > 1 && covered == value
+}
+
+impl ListAnnotation for FlagSet {
+    const NAME: &'static str = FLAGS_PREFIX;
+
+    type Item = (String, u32);
+
+    fn parse_item(flag: &str, previous: &[Self::Item]) -> std::result::Result<Self::Item, String> {
+        let (name, bit) = match flag.split_once('=') {
+            Some((name, bit)) => (name, parse_value(bit)?),
+            None => (
+                flag,
+                previous.last().map_or(0, |(_, bit)| i64::from(*bit) + 1),
+            ),
+        };
+
+        let bit = u32::try_from(bit)
+            .ok()
+            .filter(|bit| *bit < u64::BITS)
+            .ok_or(format!("Bit {bit} of flag \"{name}\" out of range"))?;
+        if let Some((other, _)) = previous.iter().find(|(_, other)| *other == bit) {
+            return Err(format!("Flags \"{other}\" and \"{name}\" share bit {bit}"));
+        }
+        Ok((name.to_owned(), bit))
+    }
+
+    fn item_name((name, _): &Self::Item) -> &str {
+        name
+    }
+
+    fn fmt_item((name, bit): &Self::Item, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{name}={bit}")
+    }
+
+    fn items(&self) -> &[Self::Item] {
+        &self.flags
+    }
+
+    fn from_items(flags: Vec<Self::Item>) -> std::result::Result<Self, String> {
+        Ok(FlagSet { flags })
+    }
+}
+
+impl FromStr for FlagSet {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Self::parse_list(s)
+    }
+}
+
+impl fmt::Display for FlagSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_list(f)
+    }
+}
+
+/// Retrieve a flag set declared on an interface with a [`FLAGS_PREFIX`] annotation.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::get_flag_set;
+///
+/// let xml = r#"
+/// <node>
+/// <interface name="org.a11y.atspi.Accessible">
+///   <annotation name="org.zbus.lockstep.Flags.State" value="Invalid Active Armed Busy"/>
+/// </interface>
+/// </node>
+/// "#;
+///
+/// let state = get_flag_set(xml.as_bytes(), "org.a11y.atspi.Accessible", "State").unwrap();
+/// assert_eq!(state.value("Busy"), Some(0b1000));
+/// ```
+pub fn get_flag_set(xml: impl Read, interface_name: &str, name: &str) -> Result<FlagSet> {
+    FlagSet::read_declared(xml, interface_name, None, name, "flag set")
+}
+
+/// Like [`get_flag_set`], from the interface at `object_path`.
+pub fn get_flag_set_at(
+    xml: impl Read,
+    object_path: &str,
+    interface_name: &str,
+    name: &str,
+) -> Result<FlagSet> {
+    FlagSet::read_declared(xml, interface_name, Some(object_path), name, "flag set")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_flag_set() {
+        let flag_set: FlagSet = "Invalid, Active Armed\n  Busy=0x10 Checked Low=32"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            flag_set.to_string(),
+            "Invalid=0 Active=1 Armed=2 Busy=16 Checked=17 Low=32"
+        );
+        assert_eq!(flag_set.value("Low"), Some(1 << 32));
+
+        for invalid in ["Busy=one", "Busy=2 Armed=2", "Busy=64", "Busy=-1"] {
+            assert!(
+                invalid.parse::<FlagSet>().is_err(),
+                "\"{invalid}\" should not parse"
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_flag_set() {
+        let flag_set: FlagSet = "Active Armed Busy Checked".parse().unwrap();
+        let flags = [
+            ("Active", 0b1),
+            ("Busy", 0b110),
+            ("Checked", 0b1000),
+            ("Focused", 0b1_0000),
+            ("Selected", 0b1_1000),
+            ("Pressed", 0b10_0001),
+        ];
+
+        match flag_set.validate("State", &flags) {
+            Err(FlagsMismatch(name, problems)) => {
+                assert_eq!(name, "State");
+                assert_eq!(
+                    problems,
+                    [
+                        "Flag \"Armed\" = 1 << 1 is missing.",
+                        "Flag \"Busy\" is 1 << 2 in XML, but 0x6 in the implementation.",
+                        "Flag \"Busy\" = 0x6 is not a single bit.",
+                        "Flag \"Focused\" = 0x10 is not in the flag set.",
+                        "Flag \"Pressed\" = 0x21 is not a single bit.",
+                        "Flag \"Pressed\" overlaps flag \"Active\".",
+                        "Flag \"Pressed\" = 0x21 is not in the flag set.",
+                    ]
+                );
+            }
+            result => panic!("Unexpected result: {result:?}"),
+        }
+    }
+}
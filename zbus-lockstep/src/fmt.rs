@@ -0,0 +1,389 @@
+//! Canonical formatting of introspection XML.
+//!
+//! In canonical form:
+//!
+//! - Every element and comment is on its own line, indented by its depth.
+//! - Known attributes come in a fixed order, `name`, `type`, `direction`, `access` and `value`,
+//!   followed by any others sorted by name.
+//! - Elements without children are self-closing.
+//! - Elements holding text, like `doc:` elements, are written on a single line as they are.
+//!
+//! Comments, annotations and elements `zbus_xml` does not know about are kept. Optionally, the
+//! members of each interface are sorted: methods, then signals, then properties, each by name.
+//! A comment right before a member moves along with it.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use zbus_lockstep::fmt::Formatter;
+//!
+//! let xml = r#"<node><interface name="org.example.Node">
+//!     <signal name="Removed"><arg type="o" name="path"/></signal>
+//!     <method name="Add"/>
+//! </interface></node>"#;
+//!
+//! let formatted = Formatter::new().sort_members(true).format_str(xml).unwrap();
+//!
+//! assert_eq!(
+//!     formatted,
+//!     r#"<node>
+//!   <interface name="org.example.Node">
+//!     <method name="Add"/>
+//!     <signal name="Removed">
+//!       <arg name="path" type="o"/>
+//!     </signal>
+//!   </interface>
+//! </node>
+//! "#
+//! );
+//! ```
+
+use std::path::Path;
+
+use crate::{
+    compression,
+    dom::{self, escape, Attribute, Content, Element},
+    LockstepError, Result,
+};
+
+/// Attributes written first, in this order.
+const ATTRIBUTE_ORDER: [&str; 5] = ["name", "type", "direction", "access", "value"];
+
+/// Writes introspection XML in canonical form.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Formatter {
+    indent: usize,
+    sort_members: bool,
+}
+
+impl Default for Formatter {
+    fn default() -> Self {
+        Formatter {
+            indent: 2,
+            sort_members: false,
+        }
+    }
+}
+
+impl Formatter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Spaces to indent by per level, 2 by default.
+    #[must_use]
+    pub fn indent(mut self, indent: usize) -> Self {
+        self.indent = indent;
+        self
+    }
+
+    /// Whether to sort the members of interfaces, `false` by default.
+    #[must_use]
+    pub fn sort_members(mut self, sort: bool) -> Self {
+        self.sort_members = sort;
+        self
+    }
+
+    /// The canonical form of `xml`.
+    pub fn format_str(&self, xml: &str) -> Result<String> {
+        let content = dom::parse(xml).map_err(|e| format!("{}: {}", e.location, e.message))?;
+
+        let mut out = String::new();
+        for content in &content {
+            self.write(content, 0, &mut out);
+        }
+        Ok(out)
+    }
+
+    /// Whether `xml` is in canonical form.
+    pub fn is_canonical(&self, xml: &str) -> Result<bool> {
+        Ok(self.format_str(xml)? == xml)
+    }
+
+    /// Rewrite the file at `path` in canonical form, returning whether it changed.
+    ///
+    /// Compressed files are rejected, rather than written back uncompressed.
+    pub fn format_file(&self, path: impl AsRef<Path>) -> Result<bool> {
+        let path = path.as_ref();
+        let xml = read_uncompressed(path)?;
+        let formatted = self
+            .format_str(&xml)
+            .map_err(|e| format!("{}:{e}", path.display()))?;
+
+        if formatted == xml {
+            return Ok(false);
+        }
+        std::fs::write(path, formatted)?;
+        Ok(true)
+    }
+
+    /// Check that the file at `path` is in canonical form.
+    ///
+    /// # Errors
+    ///
+    /// [`LockstepError::NotCanonical`] with the first line that differs, if it is not. Like
+    /// [`Formatter::format_file`], this rejects compressed files.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use zbus_lockstep::fmt::Formatter;
+    ///
+    /// let dir = tempfile::tempdir().unwrap();
+    /// let path = dir.path().join("org.example.Node.xml");
+    /// std::fs::write(&path, "<node>\n  <interface name=\"org.example.Node\"/>\n</node>\n").unwrap();
+    ///
+    /// Formatter::new().check_file(&path).unwrap();
+    /// ```
+    pub fn check_file(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let xml = read_uncompressed(path)?;
+        let formatted = self
+            .format_str(&xml)
+            .map_err(|e| format!("{}:{e}", path.display()))?;
+
+        if formatted == xml {
+            return Ok(());
+        }
+
+        let mut formatted_lines = formatted.lines();
+        let line = xml
+            .lines()
+            .position(|line| formatted_lines.next() != Some(line))
+            .unwrap_or_else(|| xml.lines().count())
+            + 1;
+        Err(LockstepError::NotCanonical(path.to_string_lossy().into_owned(), line).into())
+    }
+
+    fn write(&self, content: &Content, depth: usize, out: &mut String) {
+        let indent = " ".repeat(self.indent * depth);
+        match content {
+            Content::Element(element) => self.write_element(element, depth, out),
+            // Whitespace between elements is replaced by the indentation.
+            Content::Text(text, _) if text.trim().is_empty() => {}
+            Content::Declaration(inner) => {
+                out.push_str(&format!("{indent}<?{}?>\n", inner.trim()));
+            }
+            content => {
+                out.push_str(&indent);
+                dom::write(std::slice::from_ref(content), out);
+                out.push('\n');
+            }
+        }
+    }
+
+    fn write_element(&self, element: &Element, depth: usize, out: &mut String) {
+        out.push_str(&" ".repeat(self.indent * depth));
+        out.push('<');
+        out.push_str(&element.name);
+        for attribute in sorted_attributes(&element.attributes) {
+            out.push_str(&format!(
+                " {}=\"{}\"",
+                attribute.name,
+                escape(&attribute.value, true)
+            ));
+        }
+
+        let has_text = element.children.iter().any(|child| match child {
+            Content::Text(text, _) => !text.trim().is_empty(),
+            Content::CData(..) => true,
+            _ => false,
+        });
+        let is_empty = element
+            .children
+            .iter()
+            .all(|child| matches!(child, Content::Text(text, _) if text.trim().is_empty()));
+
+        if is_empty {
+            out.push_str("/>\n");
+            return;
+        }
+
+        out.push('>');
+        if has_text {
+            // Text is significant, write the content as it is.
+            dom::write(&element.children, out);
+        } else {
+            out.push('\n');
+            let children = if self.sort_members && element.name == "interface" {
+                sorted_members(&element.children)
+            } else {
+                element.children.iter().collect()
+            };
+            for child in children {
+                self.write(child, depth + 1, out);
+            }
+            out.push_str(&" ".repeat(self.indent * depth));
+        }
+        out.push_str(&format!("</{}>\n", element.name));
+    }
+}
+
+/// Attributes in canonical order.
+fn sorted_attributes(attributes: &[Attribute]) -> Vec<&Attribute> {
+    let mut sorted: Vec<&Attribute> = attributes.iter().collect();
+    sorted.sort_by_key(|attribute| {
+        let rank = ATTRIBUTE_ORDER
+            .iter()
+            .position(|name| *name == attribute.name)
+            .unwrap_or(ATTRIBUTE_ORDER.len());
+        (rank, attribute.name.as_str())
+    });
+    sorted
+}
+
+/// The children of an interface, with the members sorted after everything else.
+fn sorted_members(children: &[Content]) -> Vec<&Content> {
+    // Each element along with the comments right before it.
+    let mut chunks: Vec<Vec<&Content>> = Vec::new();
+    let mut pending = Vec::new();
+    for child in children {
+        match child {
+            Content::Text(text, _) if text.trim().is_empty() => {}
+            Content::Element(_) => {
+                pending.push(child);
+                chunks.push(std::mem::take(&mut pending));
+            }
+            _ => pending.push(child),
+        }
+    }
+
+    let member_rank = |chunk: &[&Content]| match chunk.last() {
+        Some(Content::Element(element)) => match element.name.as_str() {
+            "method" => Some((0, element.attribute("name").unwrap_or_default().to_string())),
+            "signal" => Some((1, element.attribute("name").unwrap_or_default().to_string())),
+            "property" => Some((2, element.attribute("name").unwrap_or_default().to_string())),
+            _ => None,
+        },
+        _ => None,
+    };
+
+    let (mut members, others): (Vec<_>, Vec<_>) = chunks
+        .into_iter()
+        .partition(|chunk| member_rank(chunk).is_some());
+    members.sort_by_cached_key(|chunk| member_rank(chunk));
+
+    others
+        .into_iter()
+        .chain(members)
+        .flatten()
+        .chain(pending)
+        .collect()
+}
+
+/// Read the file at `path`, which must not be compressed.
+fn read_uncompressed(path: &Path) -> Result<String> {
+    let bytes = std::fs::read(path)?;
+    if compression::is_compressed(&bytes) {
+        return Err(format!(
+            "{}: Compressed files cannot be formatted, decompress them first",
+            path.display()
+        )
+        .into());
+    }
+    Ok(String::from_utf8(bytes)?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_format_keeps_comments_and_docs() {
+        let xml = r#"<?xml version="1.0"?>
+<!DOCTYPE node PUBLIC "-//freedesktop//DTD D-BUS Object Introspection 1.0//EN"
+ "http://www.freedesktop.org/standards/dbus/1.0/introspect.dtd">
+<node xmlns:doc="http://www.freedesktop.org/dbus/1.0/doc.dtd">
+    <interface name="org.example.Node">
+        <!-- Ping the node. -->
+        <method name="Ping">
+            <doc:doc><doc:summary>Ping &amp; wait</doc:summary></doc:doc>
+            <annotation value="true" name="org.freedesktop.DBus.Method.NoReply"/>
+        </method>
+    </interface>
+</node>"#;
+
+        assert_eq!(
+            Formatter::new().format_str(xml).unwrap(),
+            r#"<?xml version="1.0"?>
+<!DOCTYPE node PUBLIC "-//freedesktop//DTD D-BUS Object Introspection 1.0//EN"
+ "http://www.freedesktop.org/standards/dbus/1.0/introspect.dtd">
+<node xmlns:doc="http://www.freedesktop.org/dbus/1.0/doc.dtd">
+  <interface name="org.example.Node">
+    <!-- Ping the node. -->
+    <method name="Ping">
+      <doc:doc>
+        <doc:summary>Ping &amp; wait</doc:summary>
+      </doc:doc>
+      <annotation name="org.freedesktop.DBus.Method.NoReply" value="true"/>
+    </method>
+  </interface>
+</node>
+"#
+        );
+    }
+
+    #[test]
+    fn test_sort_members_moves_comments_along() {
+        let xml = r#"<node>
+  <interface name="org.example.Node">
+    <property name="B" type="s" access="read"/>
+    <!-- About A. -->
+    <property name="A" type="s" access="read"/>
+    <signal name="Changed"/>
+    <annotation name="org.freedesktop.DBus.Deprecated" value="true"/>
+  </interface>
+</node>
+"#;
+
+        assert_eq!(
+            Formatter::new().sort_members(true).format_str(xml).unwrap(),
+            r#"<node>
+  <interface name="org.example.Node">
+    <annotation name="org.freedesktop.DBus.Deprecated" value="true"/>
+    <signal name="Changed"/>
+    <!-- About A. -->
+    <property name="A" type="s" access="read"/>
+    <property name="B" type="s" access="read"/>
+  </interface>
+</node>
+"#
+        );
+        assert!(Formatter::new().is_canonical(xml).unwrap());
+    }
+
+    #[test]
+    fn test_check_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("node.xml");
+        std::fs::write(
+            &path,
+            "<node>\n  <interface  name='org.example.Node'/>\n</node>\n",
+        )
+        .unwrap();
+
+        let error = Formatter::new().check_file(&path).unwrap_err();
+        assert!(error.to_string().contains("from line 2 on"));
+
+        assert!(Formatter::new().format_file(&path).unwrap());
+        assert!(!Formatter::new().format_file(&path).unwrap());
+        Formatter::new().check_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_compressed_file_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("node.xml.gz");
+        std::fs::write(&path, [0x1f, 0x8b, 0x08, 0x00]).unwrap();
+
+        for error in [
+            Formatter::new().check_file(&path).unwrap_err(),
+            Formatter::new().format_file(&path).unwrap_err(),
+        ] {
+            assert!(error
+                .to_string()
+                .contains("Compressed files cannot be formatted"));
+        }
+        assert_eq!(std::fs::read(&path).unwrap(), [0x1f, 0x8b, 0x08, 0x00]);
+    }
+}
@@ -6,8 +6,12 @@
 //! It offers functions that retrieve the signature of a method's argument type, of a method's
 //! return type, pf a signal's body type or of a property's type from `DBus` XML.  
 //!
-//! These functions require that you provide the file path to the XML file, the interface name,
-//! and the interface member wherein the signature resides.
+//! These functions require that you provide the XML file, the interface name, and the interface
+//! member wherein the signature resides. Compressed files are recognized and decompressed, given
+//! the `gzip` or `zstd` feature. Interfaces implemented by child
+//! `<node>`s are found too, the first node implementing the interface is used. Each function has
+//! an `_at` variant, e.g. [`get_signal_body_type_at`], which takes an object path and uses only
+//! the interface at that path, see [`find_interface`].
 //!
 //! Corresponding to each of these functions, macros are provided which do not
 //! require you to exactly point out where the signature is found. These will just search
@@ -17,23 +21,108 @@
 //!
 //! - `xml` or `XML`, the default path for `DBus` XML files - or is set by the
 //! - `LOCKSTEP_XML_PATH`, the env variable that overrides the default.
+//!
+//! XML files may pull in other files with `xi:include`, or hold several concatenated documents,
+//! see [`load_xml`]. With the `gzip` or `zstd` feature, they may be compressed, too.
+//!
+//! [`codegen`] generates Rust types for the members of an interface, and `zbus` proxy traits.
+//!
+//! [`describe`] goes the other way, from interfaces described in Rust to their XML.
+//!
+//! [`explain`] describes a signature in plain language. Failed validations explain both
+//! signatures, see [`explain_mismatch`], and suggest a type that matches the XML, see
+//! [`codegen::suggest_type`]. [`assert_signature_eq!`] does the same for a type checked in a
+//! test.
+//!
+//! [`fmt::Formatter`] rewrites XML files in canonical form, or checks that they are.
+//!
+//! [`validate_properties`] checks a struct holding the properties of an interface, as
+//! returned by `GetAll`.
+//!
+//! [`get_variant_type`] reads the concrete type of a `v` from a [`VARIANT_TYPE`] annotation.
+//!
+//! [`get_enumeration`] reads the variants of an enumeration declared with an [`ENUM_PREFIX`]
+//! annotation, to check the discriminants of an enum, see [`Enumeration::validate`].
+//!
+//! [`get_flag_set`] reads the bit positions of flags declared with a [`FLAGS_PREFIX`]
+//! annotation, to check a `bitflags!` or `enumflags2` type, see [`FlagSet::validate`].
+//!
+//! [`get_allowed_values`] reads the strings an `s` may hold from an [`ALLOWED_VALUES`]
+//! annotation, to check an enum serialized as a string, see [`AllowedValues::validate`].
+//!
+//! [`get_dict_schema`] reads the keys and value types of an `a{sv}` from a
+//! [`DICT_SCHEMA`] annotation, to check a struct holding the dictionary, see
+//! [`DictSchema::validate`].
+//!
+//! [`registry::Registry`] finds every member with a signature, and the signatures used the
+//! most, to share Rust types between them.
+//!
+//! [`compat::compare`] tells which changes between two versions of an interface break clients
+//! or servers.
+//!
+//! [`check_lockfile`] keeps track of changes to the XML definitions of validated members, in a
+//! `lockstep.lock` file.
+//!
+//! [`lint`] checks the XML files themselves against the rules of the `DBus` specification,
+//! [`validate_dtd`] checks their structure against the introspection DTD.
 #![doc(html_root_url = "https://docs.rs/zbus-lockstep/0.5.0")]
 #![allow(clippy::missing_errors_doc)]
 
+mod allowed_values;
+mod annotations;
+pub mod codegen;
+pub mod compat;
+mod compression;
+pub mod describe;
+mod dict_schema;
+mod dom;
+mod dtd;
+mod enumeration;
 mod error;
+mod explain;
+mod flags;
+pub mod fmt;
+mod lint;
+mod load;
+mod lockfile;
 mod macros;
+mod properties;
+pub mod registry;
+mod scan;
+mod tree;
 
 use std::{io::Read, str::FromStr};
 
+pub use allowed_values::{
+    get_allowed_values, get_allowed_values_at, AllowedValues, ALLOWED_VALUES,
+};
+pub use annotations::{EmitsChangedSignal, EMITS_CHANGED_SIGNAL, NO_REPLY, VARIANT_TYPE};
+pub use dict_schema::{get_dict_schema, get_dict_schema_at, DictField, DictSchema, DICT_SCHEMA};
+pub use dom::Location;
+pub use dtd::{validate_dtd, validate_dtd_str};
+pub use enumeration::{get_enumeration, get_enumeration_at, Enumeration, ENUM_PREFIX};
 pub use error::LockstepError;
+pub use explain::{explain, explain_mismatch};
+pub use flags::{get_flag_set, get_flag_set_at, FlagSet, FLAGS_PREFIX};
+pub use lint::{lint, lint_str, Diagnostic, Severity};
+pub use load::{is_xml_file, load_nodes, load_xml};
+pub use lockfile::{
+    assert_lockfile, check_lockfile, LockedMember, Lockfile, LOCKFILE, UPDATE_LOCKFILE_ENV,
+};
 pub use macros::resolve_xml_path;
+pub use properties::{validate_properties, PropertyField, RenameRule};
+pub use scan::{Scan, Scanner, SkipReason, Skipped, IGNORE_FILE};
+pub use tree::{find_interface, interfaces_with_paths};
 pub use zbus_xml::{
     self,
     ArgDirection::{In, Out},
-    Node,
+    Node, PropertyAccess,
 };
 use zvariant::Signature;
-use LockstepError::{ArgumentNotFound, InterfaceNotFound, MemberNotFound, PropertyNotFound};
+use LockstepError::{
+    ArgumentNotFound, EmitsChangedSignalMismatch, MemberNotFound, NoReplyMismatch,
+    PropertyAccessMismatch, PropertyNotFound, VariantTypeMismatch,
+};
 
 type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
 
@@ -44,6 +133,51 @@ pub enum MsgType {
     Property,
 }
 
+impl std::fmt::Display for MsgType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MsgType::Method => f.write_str("method"),
+            MsgType::Signal => f.write_str("signal"),
+            MsgType::Property => f.write_str("property"),
+        }
+    }
+}
+
+/// The direction of a method argument.
+///
+/// Arguments without a `direction` are input arguments, as the D-Bus specification has it.
+pub fn arg_direction(arg: &zbus_xml::Arg) -> zbus_xml::ArgDirection {
+    arg.direction().unwrap_or(In)
+}
+
+/// The signature of the arguments going in `direction`, or of all arguments if `None`.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::{args_signature, Node, Out};
+///
+/// let xml = r#"<node>
+///   <interface name="org.example.Node">
+///     <method name="Lookup">
+///       <arg name="key" type="s"/>
+///       <arg name="value" type="v" direction="out"/>
+///     </method>
+///   </interface>
+/// </node>"#;
+/// let node = Node::from_reader(xml.as_bytes()).unwrap();
+/// let method = &node.interfaces()[0].methods()[0];
+///
+/// assert_eq!(args_signature(method.args(), Some(Out)), "v");
+/// assert_eq!(args_signature(method.args(), None), "sv");
+/// ```
+pub fn args_signature(args: &[zbus_xml::Arg], direction: Option<zbus_xml::ArgDirection>) -> String {
+    args.iter()
+        .filter(|arg| direction.is_none_or(|direction| arg_direction(arg) == direction))
+        .map(|arg| arg.ty().to_string())
+        .collect()
+}
+
 /// Retrieve a signal's body type signature from `DBus` XML.
 ///
 /// If you provide an argument name, then the signature of that argument is returned.
@@ -86,18 +220,35 @@ pub enum MsgType {
 /// assert_eq!(&signature, DeviceEvent::SIGNATURE);
 /// ```
 pub fn get_signal_body_type(
+    xml: impl Read,
+    interface_name: &str,
+    member_name: &str,
+    arg: Option<&str>,
+) -> Result<Signature> {
+    signal_body_type_in(xml, interface_name, None, member_name, arg)
+}
+
+/// Like [`get_signal_body_type`], from the interface at `object_path`.
+pub fn get_signal_body_type_at(
+    xml: impl Read,
+    object_path: &str,
+    interface_name: &str,
+    member_name: &str,
+    arg: Option<&str>,
+) -> Result<Signature> {
+    signal_body_type_in(xml, interface_name, Some(object_path), member_name, arg)
+}
+
+fn signal_body_type_in(
     mut xml: impl Read,
     interface_name: &str,
+    object_path: Option<&str>,
     member_name: &str,
     arg: Option<&str>,
 ) -> Result<Signature> {
-    let node = Node::from_reader(&mut xml)?;
+    let nodes = load::read_nodes(&mut xml)?;
 
-    let interfaces = node.interfaces();
-    let interface = interfaces
-        .iter()
-        .find(|iface| iface.name() == interface_name)
-        .ok_or(InterfaceNotFound(interface_name.to_owned()))?;
+    let (_, interface) = tree::find_interface_in(&nodes, interface_name, object_path)?;
 
     let signals = interface.signals();
     let signal = signals
@@ -157,17 +308,32 @@ pub fn get_signal_body_type(
 /// assert_eq!(signature, *InUse::SIGNATURE);
 /// ```
 pub fn get_property_type(
+    xml: impl Read,
+    interface_name: &str,
+    property_name: &str,
+) -> Result<Signature> {
+    property_type_in(xml, interface_name, None, property_name)
+}
+
+/// Like [`get_property_type`], from the interface at `object_path`.
+pub fn get_property_type_at(
+    xml: impl Read,
+    object_path: &str,
+    interface_name: &str,
+    property_name: &str,
+) -> Result<Signature> {
+    property_type_in(xml, interface_name, Some(object_path), property_name)
+}
+
+fn property_type_in(
     mut xml: impl Read,
     interface_name: &str,
+    object_path: Option<&str>,
     property_name: &str,
 ) -> Result<Signature> {
-    let node = Node::from_reader(&mut xml)?;
+    let nodes = load::read_nodes(&mut xml)?;
 
-    let interfaces = node.interfaces();
-    let interface = interfaces
-        .iter()
-        .find(|iface| iface.name() == interface_name)
-        .ok_or(InterfaceNotFound(interface_name.to_string()))?;
+    let (_, interface) = tree::find_interface_in(&nodes, interface_name, object_path)?;
 
     let properties = interface.properties();
     let property = properties
@@ -179,6 +345,237 @@ pub fn get_property_type(
     Ok(Signature::from_str(&signature).map_err(|_| "Invalid signature")?)
 }
 
+/// Retrieve the access mode of a property from XML.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::fs::File;
+/// use std::io::{Seek, SeekFrom, Write};
+/// use tempfile::tempfile;
+/// use zbus_lockstep::{get_property_access, PropertyAccess};
+///
+/// let xml = String::from(r#"
+/// <node>
+/// <interface name="org.freedesktop.GeoClue2.Client">
+///   <property type="u" name="DistanceThreshold" access="readwrite"/>
+/// </interface>
+/// </node>
+/// "#);
+///
+/// let mut xml_file: File = tempfile().unwrap();
+/// xml_file.write_all(xml.as_bytes()).unwrap();
+/// xml_file.seek(SeekFrom::Start(0)).unwrap();
+///
+/// let interface_name = "org.freedesktop.GeoClue2.Client";
+/// let property_name = "DistanceThreshold";
+///
+/// let access = get_property_access(xml_file, interface_name, property_name).unwrap();
+/// assert_eq!(access, PropertyAccess::ReadWrite);
+/// ```
+pub fn get_property_access(
+    xml: impl Read,
+    interface_name: &str,
+    property_name: &str,
+) -> Result<PropertyAccess> {
+    property_access_in(xml, interface_name, None, property_name)
+}
+
+/// Like [`get_property_access`], from the interface at `object_path`.
+pub fn get_property_access_at(
+    xml: impl Read,
+    object_path: &str,
+    interface_name: &str,
+    property_name: &str,
+) -> Result<PropertyAccess> {
+    property_access_in(xml, interface_name, Some(object_path), property_name)
+}
+
+fn property_access_in(
+    mut xml: impl Read,
+    interface_name: &str,
+    object_path: Option<&str>,
+    property_name: &str,
+) -> Result<PropertyAccess> {
+    let nodes = load::read_nodes(&mut xml)?;
+
+    let (_, interface) = tree::find_interface_in(&nodes, interface_name, object_path)?;
+
+    let properties = interface.properties();
+    let property = properties
+        .iter()
+        .find(|property| property.name() == property_name)
+        .ok_or(PropertyNotFound(property_name.to_owned()))?;
+
+    Ok(property.access())
+}
+
+/// The access mode as written in `DBus` XML, e.g. `"readwrite"`.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::{access_str, PropertyAccess};
+///
+/// assert_eq!(access_str(PropertyAccess::ReadWrite), "readwrite");
+/// ```
+pub fn access_str(access: PropertyAccess) -> &'static str {
+    match access {
+        PropertyAccess::Read => "read",
+        PropertyAccess::Write => "write",
+        PropertyAccess::ReadWrite => "readwrite",
+    }
+}
+
+/// Check a property's declared access mode against the accessors an implementation provides.
+///
+/// `has_getter` and `has_setter` describe whether the implementation, e.g. a zbus `#[interface]`,
+/// offers a getter and / or a setter for the property. The check fails if the implementation
+/// provides a setter for a read-only property, omits the getter of a readable property, etc.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::{validate_property_access, PropertyAccess};
+///
+/// assert!(validate_property_access("InUse", PropertyAccess::Read, true, false).is_ok());
+/// assert!(validate_property_access("InUse", PropertyAccess::Read, true, true).is_err());
+/// ```
+pub fn validate_property_access(
+    property_name: &str,
+    access: PropertyAccess,
+    has_getter: bool,
+    has_setter: bool,
+) -> std::result::Result<(), LockstepError> {
+    let provided = match (has_getter, has_setter) {
+        (true, false) => Some(PropertyAccess::Read),
+        (false, true) => Some(PropertyAccess::Write),
+        (true, true) => Some(PropertyAccess::ReadWrite),
+        (false, false) => None,
+    };
+
+    if provided == Some(access) {
+        Ok(())
+    } else {
+        Err(PropertyAccessMismatch(
+            property_name.to_owned(),
+            access,
+            provided,
+        ))
+    }
+}
+
+/// Retrieve the effective `org.freedesktop.DBus.Property.EmitsChangedSignal` value of a property.
+///
+/// The annotation may be set on the property itself or on its interface, in which case it
+/// applies to all properties of that interface. Absent any annotation, the value is `true`.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::fs::File;
+/// use std::io::{Seek, SeekFrom, Write};
+/// use tempfile::tempfile;
+/// use zbus_lockstep::{get_property_emits_changed_signal, EmitsChangedSignal};
+///
+/// let xml = String::from(r#"
+/// <node>
+/// <interface name="org.freedesktop.login1.Seat">
+///   <annotation name="org.freedesktop.DBus.Property.EmitsChangedSignal" value="false"/>
+///   <property type="s" name="Id" access="read">
+///     <annotation name="org.freedesktop.DBus.Property.EmitsChangedSignal" value="const"/>
+///   </property>
+///   <property type="b" name="CanGraphical" access="read"/>
+/// </interface>
+/// </node>
+/// "#);
+///
+/// let mut xml_file: File = tempfile().unwrap();
+/// xml_file.write_all(xml.as_bytes()).unwrap();
+/// xml_file.seek(SeekFrom::Start(0)).unwrap();
+///
+/// let interface_name = "org.freedesktop.login1.Seat";
+///
+/// let emits = get_property_emits_changed_signal(&mut xml_file, interface_name, "Id").unwrap();
+/// assert_eq!(emits, EmitsChangedSignal::Const);
+///
+/// xml_file.seek(SeekFrom::Start(0)).unwrap();
+/// let emits =
+///     get_property_emits_changed_signal(&mut xml_file, interface_name, "CanGraphical").unwrap();
+/// assert_eq!(emits, EmitsChangedSignal::False);
+/// ```
+pub fn get_property_emits_changed_signal(
+    xml: impl Read,
+    interface_name: &str,
+    property_name: &str,
+) -> Result<EmitsChangedSignal> {
+    property_emits_changed_signal_in(xml, interface_name, None, property_name)
+}
+
+/// Like [`get_property_emits_changed_signal`], from the interface at `object_path`.
+pub fn get_property_emits_changed_signal_at(
+    xml: impl Read,
+    object_path: &str,
+    interface_name: &str,
+    property_name: &str,
+) -> Result<EmitsChangedSignal> {
+    property_emits_changed_signal_in(xml, interface_name, Some(object_path), property_name)
+}
+
+fn property_emits_changed_signal_in(
+    mut xml: impl Read,
+    interface_name: &str,
+    object_path: Option<&str>,
+    property_name: &str,
+) -> Result<EmitsChangedSignal> {
+    let nodes = load::read_nodes(&mut xml)?;
+
+    let (_, interface) = tree::find_interface_in(&nodes, interface_name, object_path)?;
+
+    let properties = interface.properties();
+    let property = properties
+        .iter()
+        .find(|property| property.name() == property_name)
+        .ok_or(PropertyNotFound(property_name.to_owned()))?;
+
+    Ok(EmitsChangedSignal::from_annotations(
+        property.annotations(),
+        interface.annotations(),
+    )?)
+}
+
+/// Check the `EmitsChangedSignal` value from XML against the one used by an implementation.
+///
+/// `setting` is the value of `emits_changed_signal` in a zbus `#[interface]` property
+/// attribute, e.g. `#[zbus(property(emits_changed_signal = "const"))]`. Properties without
+/// the setting emit `"true"`.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::{validate_emits_changed_signal, EmitsChangedSignal};
+///
+/// assert!(validate_emits_changed_signal("Id", EmitsChangedSignal::Const, "const").is_ok());
+/// assert!(validate_emits_changed_signal("Id", EmitsChangedSignal::Const, "true").is_err());
+/// ```
+pub fn validate_emits_changed_signal(
+    property_name: &str,
+    emits_changed_signal: EmitsChangedSignal,
+    setting: &str,
+) -> std::result::Result<(), LockstepError> {
+    let used: EmitsChangedSignal = setting.parse()?;
+
+    if used == emits_changed_signal {
+        Ok(())
+    } else {
+        Err(EmitsChangedSignalMismatch(
+            property_name.to_owned(),
+            emits_changed_signal,
+            used,
+        ))
+    }
+}
+
 /// Retrieve the signature of a method's return type from XML.
 ///
 /// If you provide an argument name, then the signature of that argument is returned.
@@ -224,18 +621,41 @@ pub fn get_property_type(
 /// assert_eq!(signature, *Role::SIGNATURE);
 /// ```
 pub fn get_method_return_type(
+    xml: impl Read,
+    interface_name: &str,
+    member_name: &str,
+    arg_name: Option<&str>,
+) -> Result<Signature> {
+    method_return_type_in(xml, interface_name, None, member_name, arg_name)
+}
+
+/// Like [`get_method_return_type`], from the interface at `object_path`.
+pub fn get_method_return_type_at(
+    xml: impl Read,
+    object_path: &str,
+    interface_name: &str,
+    member_name: &str,
+    arg_name: Option<&str>,
+) -> Result<Signature> {
+    method_return_type_in(
+        xml,
+        interface_name,
+        Some(object_path),
+        member_name,
+        arg_name,
+    )
+}
+
+fn method_return_type_in(
     mut xml: impl Read,
     interface_name: &str,
+    object_path: Option<&str>,
     member_name: &str,
     arg_name: Option<&str>,
 ) -> Result<Signature> {
-    let node = Node::from_reader(&mut xml)?;
+    let nodes = load::read_nodes(&mut xml)?;
 
-    let interfaces = node.interfaces();
-    let interface = interfaces
-        .iter()
-        .find(|iface| iface.name() == interface_name)
-        .ok_or(InterfaceNotFound(interface_name.to_string()))?;
+    let (_, interface) = tree::find_interface_in(&nodes, interface_name, object_path)?;
 
     let methods = interface.methods();
     let method = methods
@@ -246,25 +666,237 @@ pub fn get_method_return_type(
     let args = method.args();
 
     let signature = {
-        if arg_name.is_some() {
+        if let Some(arg_name) = arg_name {
             args.iter()
-                .find(|arg| arg.name() == arg_name)
-                .ok_or(ArgumentNotFound(
-                    arg_name.expect("arg_name guarded by 'is_some'").to_string(),
-                ))?
+                .find(|arg| arg.name() == Some(arg_name))
+                .ok_or(ArgumentNotFound(arg_name.to_string()))?
                 .ty()
                 .to_string()
         } else {
-            args.iter()
-                .filter(|arg| arg.direction() == Some(Out))
-                .map(|arg| arg.ty().to_string())
-                .collect::<String>()
+            args_signature(args, Some(Out))
         }
     };
 
     Ok(Signature::from_str(&signature).map_err(|_| "Invalid signature")?)
 }
 
+/// Retrieve whether a method is annotated with `org.freedesktop.DBus.Method.NoReply`.
+///
+/// Callers of such a method must not wait for a reply.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::fs::File;
+/// use std::io::{Seek, SeekFrom, Write};
+/// use tempfile::tempfile;
+/// use zbus_lockstep::get_method_no_reply;
+///
+/// let xml = String::from(r#"
+/// <node>
+/// <interface name="org.a11y.atspi.DeviceEventListener">
+///   <method name="NotifyEvent">
+///     <arg name="event" type="(uiuuisb)" direction="in"/>
+///     <annotation name="org.freedesktop.DBus.Method.NoReply" value="true"/>
+///   </method>
+/// </interface>
+/// </node>
+/// "#);
+///
+/// let mut xml_file: File = tempfile().unwrap();
+/// xml_file.write_all(xml.as_bytes()).unwrap();
+/// xml_file.seek(SeekFrom::Start(0)).unwrap();
+///
+/// let interface_name = "org.a11y.atspi.DeviceEventListener";
+/// let member_name = "NotifyEvent";
+///
+/// let no_reply = get_method_no_reply(xml_file, interface_name, member_name).unwrap();
+/// assert!(no_reply);
+/// ```
+pub fn get_method_no_reply(
+    xml: impl Read,
+    interface_name: &str,
+    member_name: &str,
+) -> Result<bool> {
+    method_no_reply_in(xml, interface_name, None, member_name)
+}
+
+/// Like [`get_method_no_reply`], from the interface at `object_path`.
+pub fn get_method_no_reply_at(
+    xml: impl Read,
+    object_path: &str,
+    interface_name: &str,
+    member_name: &str,
+) -> Result<bool> {
+    method_no_reply_in(xml, interface_name, Some(object_path), member_name)
+}
+
+fn method_no_reply_in(
+    mut xml: impl Read,
+    interface_name: &str,
+    object_path: Option<&str>,
+    member_name: &str,
+) -> Result<bool> {
+    let nodes = load::read_nodes(&mut xml)?;
+
+    let (_, interface) = tree::find_interface_in(&nodes, interface_name, object_path)?;
+
+    let methods = interface.methods();
+    let method = methods
+        .iter()
+        .find(|method| method.name() == member_name)
+        .ok_or(MemberNotFound(member_name.to_string()))?;
+
+    Ok(annotations::no_reply(method.annotations())?)
+}
+
+/// Check the `NoReply` annotation from XML against a method's declaration.
+///
+/// `declared` tells whether the method is declared `#[zbus(no_reply)]` in the proxy.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::validate_no_reply;
+///
+/// assert!(validate_no_reply("NotifyEvent", true, true).is_ok());
+/// assert!(validate_no_reply("NotifyEvent", true, false).is_err());
+/// ```
+pub fn validate_no_reply(
+    member_name: &str,
+    no_reply: bool,
+    declared: bool,
+) -> std::result::Result<(), LockstepError> {
+    if no_reply == declared {
+        Ok(())
+    } else {
+        Err(NoReplyMismatch(member_name.to_owned(), no_reply))
+    }
+}
+
+/// Retrieve the concrete type of a variant argument or property, from its [`VARIANT_TYPE`]
+/// annotation.
+///
+/// For a method or signal, `arg_name` names the argument. Without it, the first argument with
+/// the annotation is used. For a property, `arg_name` is ignored.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::{get_variant_type, MsgType};
+/// use zvariant::Type;
+///
+/// let xml = r#"
+/// <node>
+/// <interface name="org.a11y.atspi.Event.Object">
+///   <signal name="BoundsChanged">
+///     <arg name="detail" type="s"/>
+///     <arg name="bounds" type="v">
+///       <annotation name="org.zbus.lockstep.VariantType" value="(iiii)"/>
+///     </arg>
+///   </signal>
+/// </interface>
+/// </node>
+/// "#;
+///
+/// let signature = get_variant_type(
+///     xml.as_bytes(),
+///     "org.a11y.atspi.Event.Object",
+///     "BoundsChanged",
+///     Some("bounds"),
+///     MsgType::Signal,
+/// )
+/// .unwrap();
+/// assert_eq!(&signature, <(i32, i32, i32, i32)>::SIGNATURE);
+/// ```
+pub fn get_variant_type(
+    xml: impl Read,
+    interface_name: &str,
+    member_name: &str,
+    arg_name: Option<&str>,
+    msg_type: MsgType,
+) -> Result<Signature> {
+    variant_type_in(xml, interface_name, None, member_name, arg_name, msg_type)
+}
+
+/// Like [`get_variant_type`], from the interface at `object_path`.
+pub fn get_variant_type_at(
+    xml: impl Read,
+    object_path: &str,
+    interface_name: &str,
+    member_name: &str,
+    arg_name: Option<&str>,
+    msg_type: MsgType,
+) -> Result<Signature> {
+    variant_type_in(
+        xml,
+        interface_name,
+        Some(object_path),
+        member_name,
+        arg_name,
+        msg_type,
+    )
+}
+
+fn variant_type_in(
+    mut xml: impl Read,
+    interface_name: &str,
+    object_path: Option<&str>,
+    member_name: &str,
+    arg_name: Option<&str>,
+    msg_type: MsgType,
+) -> Result<Signature> {
+    let nodes = load::read_nodes(&mut xml)?;
+
+    let (_, interface) = tree::find_interface_in(&nodes, interface_name, object_path)?;
+
+    let (ty, annotations) =
+        annotations::find_annotated(interface, member_name, arg_name, msg_type, VARIANT_TYPE)?;
+    let signature = annotations::variant_type(annotations)?.ok_or(format!(
+        "\"{member_name}\" has no {VARIANT_TYPE} annotation."
+    ))?;
+    if ty != "v" {
+        return Err(format!(
+            "\"{member_name}\" has a {VARIANT_TYPE} annotation, but type \"{ty}\" instead of \"v\"."
+        )
+        .into());
+    }
+
+    Ok(signature)
+}
+
+/// Check the concrete type of a variant, as declared in XML, against the type an
+/// implementation puts in it.
+///
+/// `name` is the name of the argument or property, for the error.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::validate_variant_type;
+/// use zvariant::{Signature, Type};
+///
+/// let declared: Signature = "(iiii)".parse().unwrap();
+///
+/// assert!(validate_variant_type("bounds", &declared, <(i32, i32, i32, i32)>::SIGNATURE).is_ok());
+/// assert!(validate_variant_type("bounds", &declared, <(u32, u32)>::SIGNATURE).is_err());
+/// ```
+pub fn validate_variant_type(
+    name: &str,
+    variant_type: &Signature,
+    used: &Signature,
+) -> std::result::Result<(), LockstepError> {
+    if variant_type == used {
+        Ok(())
+    } else {
+        Err(VariantTypeMismatch(
+            name.to_owned(),
+            variant_type.to_string(),
+            used.to_string(),
+        ))
+    }
+}
+
 /// Retrieve the signature of a method's argument type from XML.
 ///
 /// Useful when one or more arguments, used to call a method, outline a useful type.
@@ -273,6 +905,9 @@ pub fn get_method_return_type(
 /// If you do not provide an argument name, then the signature of all arguments to the call is
 /// returned.
 ///
+/// Arguments without a `direction` are arguments to the call, as the D-Bus specification has
+/// it. Up to version 0.5, only arguments with `direction="in"` were taken.
+///
 /// # Examples
 ///
 /// ```rust
@@ -324,18 +959,41 @@ pub fn get_method_return_type(
 /// assert_eq!(&signature, Notification::SIGNATURE);
 /// ```
 pub fn get_method_args_type(
+    xml: impl Read,
+    interface_name: &str,
+    member_name: &str,
+    arg_name: Option<&str>,
+) -> Result<Signature> {
+    method_args_type_in(xml, interface_name, None, member_name, arg_name)
+}
+
+/// Like [`get_method_args_type`], from the interface at `object_path`.
+pub fn get_method_args_type_at(
+    xml: impl Read,
+    object_path: &str,
+    interface_name: &str,
+    member_name: &str,
+    arg_name: Option<&str>,
+) -> Result<Signature> {
+    method_args_type_in(
+        xml,
+        interface_name,
+        Some(object_path),
+        member_name,
+        arg_name,
+    )
+}
+
+fn method_args_type_in(
     mut xml: impl Read,
     interface_name: &str,
+    object_path: Option<&str>,
     member_name: &str,
     arg_name: Option<&str>,
 ) -> Result<Signature> {
-    let node = Node::from_reader(&mut xml)?;
+    let nodes = load::read_nodes(&mut xml)?;
 
-    let interfaces = node.interfaces();
-    let interface = interfaces
-        .iter()
-        .find(|iface| iface.name() == interface_name)
-        .ok_or(InterfaceNotFound(interface_name.to_owned()))?;
+    let (_, interface) = tree::find_interface_in(&nodes, interface_name, object_path)?;
 
     let methods = interface.methods();
     let method = methods
@@ -345,19 +1003,14 @@ pub fn get_method_args_type(
 
     let args = method.args();
 
-    let signature = if arg_name.is_some() {
+    let signature = if let Some(arg_name) = arg_name {
         args.iter()
-            .find(|arg| arg.name() == arg_name)
-            .ok_or(ArgumentNotFound(
-                arg_name.expect("arg_name guarded by is_some").to_string(),
-            ))?
+            .find(|arg| arg.name() == Some(arg_name))
+            .ok_or(ArgumentNotFound(arg_name.to_string()))?
             .ty()
             .to_string()
     } else {
-        args.iter()
-            .filter(|arg| arg.direction() == Some(In))
-            .map(|arg| arg.ty().to_string())
-            .collect::<String>()
+        args_signature(args, Some(In))
     };
 
     Ok(Signature::from_str(&signature).map_err(|_| "Invalid signature")?)
@@ -370,7 +1023,11 @@ mod test {
     use tempfile::tempfile;
     use zvariant::{OwnedObjectPath, Type};
 
-    use crate::get_signal_body_type;
+    use crate::{
+        get_method_args_type, get_method_return_type, get_property_emits_changed_signal,
+        get_property_type, get_property_type_at, get_signal_body_type, get_variant_type,
+        EmitsChangedSignal, MsgType,
+    };
 
     #[test]
     fn test_get_signature_of_cache_add_accessible() {
@@ -415,4 +1072,165 @@ mod test {
         let signature = get_signal_body_type(xml_file, interface_name, member_name, None).unwrap();
         assert_eq!(signature, *CacheItem::SIGNATURE);
     }
+
+    #[test]
+    fn test_emits_changed_signal_is_inherited_from_interface() {
+        let xml = r#"
+            <node>
+                <interface name="org.example.Cached">
+                    <annotation name="org.freedesktop.DBus.Property.EmitsChangedSignal" value="invalidates"/>
+                    <property name="Inherited" type="s" access="read"/>
+                    <property name="Overridden" type="s" access="read">
+                        <annotation name="org.freedesktop.DBus.Property.EmitsChangedSignal" value="false"/>
+                    </property>
+                </interface>
+                <interface name="org.example.Plain">
+                    <property name="Default" type="s" access="read"/>
+                </interface>
+            </node>
+        "#;
+
+        let emits =
+            |iface, prop| get_property_emits_changed_signal(xml.as_bytes(), iface, prop).unwrap();
+
+        assert_eq!(
+            emits("org.example.Cached", "Inherited"),
+            EmitsChangedSignal::Invalidates
+        );
+        assert_eq!(
+            emits("org.example.Cached", "Overridden"),
+            EmitsChangedSignal::False
+        );
+        assert_eq!(
+            emits("org.example.Plain", "Default"),
+            EmitsChangedSignal::True
+        );
+    }
+
+    #[test]
+    fn test_emits_changed_signal_with_invalid_value() {
+        let xml = r#"
+            <node>
+                <interface name="org.example.Cached">
+                    <property name="Broken" type="s" access="read">
+                        <annotation name="org.freedesktop.DBus.Property.EmitsChangedSignal" value="sometimes"/>
+                    </property>
+                </interface>
+            </node>
+        "#;
+
+        let result =
+            get_property_emits_changed_signal(xml.as_bytes(), "org.example.Cached", "Broken");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_get_property_type_of_child_node() {
+        let xml = r#"
+            <node name="/org/example">
+                <node name="child">
+                    <interface name="org.example.Child">
+                        <property name="Age" type="u" access="read"/>
+                    </interface>
+                </node>
+            </node>
+        "#;
+
+        let signature = get_property_type(xml.as_bytes(), "org.example.Child", "Age").unwrap();
+        assert_eq!(signature, *u32::SIGNATURE);
+    }
+
+    #[test]
+    fn test_get_method_args_type_without_direction() {
+        let xml = std::fs::read_to_string("../xml/directions.xml").unwrap();
+
+        let args = get_method_args_type(xml.as_bytes(), "org.example.Dial", "Turn", None);
+        assert_eq!(args.unwrap(), *<(i32, bool)>::SIGNATURE);
+
+        let reply = get_method_return_type(xml.as_bytes(), "org.example.Dial", "Turn", None);
+        assert_eq!(reply.unwrap(), *u32::SIGNATURE);
+    }
+
+    #[test]
+    fn test_get_method_args_type_counts_args_without_direction() {
+        let xml = r#"<node>
+            <interface name="org.example.Dial">
+                <method name="Press">
+                    <arg name="force" type="d"/>
+                    <arg name="repeat" type="u" direction="in"/>
+                </method>
+            </interface>
+        </node>"#;
+
+        // Up to version 0.5, `force` was left out and this was `u`.
+        let args = get_method_args_type(xml.as_bytes(), "org.example.Dial", "Press", None);
+        assert_eq!(args.unwrap(), *<(f64, u32)>::SIGNATURE);
+    }
+
+    #[test]
+    fn test_get_property_type_at_object_path() {
+        let xml = std::fs::read_to_string("../xml/object_paths.xml").unwrap();
+        let property_type =
+            |path| get_property_type_at(xml.as_bytes(), path, "org.example.Lamp", "Glow");
+
+        // Without an object path, the first node implementing the interface is used.
+        assert_eq!(
+            get_property_type(xml.as_bytes(), "org.example.Lamp", "Glow").unwrap(),
+            *u32::SIGNATURE
+        );
+        assert_eq!(
+            property_type("/org/example/lamp/ceiling").unwrap(),
+            *u32::SIGNATURE
+        );
+        assert_eq!(
+            property_type("/org/example/lamp/desk/").unwrap(),
+            *f64::SIGNATURE
+        );
+
+        assert!(property_type("/org/example/lamp")
+            .unwrap_err()
+            .to_string()
+            .contains("Interface \"org.example.Lamp\" not found"));
+        assert!(property_type("/org/example/lamp/floor")
+            .unwrap_err()
+            .to_string()
+            .contains("Object path \"/org/example/lamp/floor\" not found"));
+    }
+
+    #[test]
+    fn test_get_variant_type_of_property() {
+        let xml = r#"
+            <node>
+                <interface name="org.example.Window">
+                    <property name="Geometry" type="v" access="read">
+                        <annotation name="org.zbus.lockstep.VariantType" value="(iiii)"/>
+                    </property>
+                    <property name="Size" type="(ii)" access="read">
+                        <annotation name="org.zbus.lockstep.VariantType" value="(ii)"/>
+                    </property>
+                    <property name="Title" type="v" access="read">
+                        <annotation name="org.zbus.lockstep.VariantType" value="ss"/>
+                    </property>
+                    <property name="Icon" type="v" access="read"/>
+                </interface>
+            </node>
+        "#;
+        let variant_type = |property| {
+            get_variant_type(
+                xml.as_bytes(),
+                "org.example.Window",
+                property,
+                None,
+                MsgType::Property,
+            )
+        };
+
+        assert_eq!(
+            variant_type("Geometry").unwrap(),
+            *<(i32, i32, i32, i32)>::SIGNATURE
+        );
+        assert!(variant_type("Size").is_err());
+        assert!(variant_type("Title").is_err());
+        assert!(variant_type("Icon").is_err());
+    }
 }
@@ -0,0 +1,631 @@
+//! Checks `DBus` introspection XML against the rules of the `DBus` specification.
+//!
+//! `zbus_xml` either rejects a malformed document as a whole or accepts things the
+//! specification forbids, such as duplicate members. Linting reports each problem with its
+//! location, so broken XML fails fast and precisely.
+
+use std::{collections::HashMap, fmt, path::Path};
+
+use crate::{
+    compression,
+    dom::{self, Element, Location},
+};
+
+/// Maximum length of a signature, and of a name, in bytes.
+const MAX_LENGTH: usize = 255;
+
+/// Maximum nesting depth of arrays, and of structs, in a signature.
+const MAX_DEPTH: usize = 32;
+
+/// How serious a [`Diagnostic`] is.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+pub enum Severity {
+    /// The document does not follow the rules of the `DBus` specification.
+    Error,
+    /// The document is valid, but likely not what was intended.
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => f.write_str("error"),
+            Severity::Warning => f.write_str("warning"),
+        }
+    }
+}
+
+/// A problem found in an XML document.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    /// The file the problem was found in, if linting a file.
+    pub path: Option<std::path::PathBuf>,
+    /// Where in the document the problem was found, if known.
+    pub location: Option<Location>,
+}
+
+impl Diagnostic {
+    pub(crate) fn error(location: Location, message: impl Into<String>) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            message: message.into(),
+            path: None,
+            location: Some(location),
+        }
+    }
+
+    pub(crate) fn warning(location: Location, message: impl Into<String>) -> Self {
+        Diagnostic {
+            severity: Severity::Warning,
+            message: message.into(),
+            path: None,
+            location: Some(location),
+        }
+    }
+
+    pub(crate) fn without_location(message: impl Into<String>) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            message: message.into(),
+            path: None,
+            location: None,
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(path) = &self.path {
+            write!(f, "{}:", path.display())?;
+        }
+        if let Some(location) = &self.location {
+            write!(f, "{location}:")?;
+        }
+        if self.path.is_some() || self.location.is_some() {
+            f.write_str(" ")?;
+        }
+        write!(f, "{}: {}", self.severity, self.message)
+    }
+}
+
+/// Lint a `DBus` XML file.
+///
+/// Returns all problems found, in document order. An empty list means the file passed.
+/// Compressed files are decompressed first, given the `gzip` or `zstd` feature.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::{lint, Severity};
+///
+/// let diagnostics = lint("../xml/test_definition_file.xml");
+/// assert!(diagnostics.iter().all(|d| d.severity != Severity::Error));
+/// ```
+pub fn lint(path: impl AsRef<Path>) -> Vec<Diagnostic> {
+    let path = path.as_ref();
+
+    let mut diagnostics = match compression::read_to_string(path) {
+        Ok(text) => lint_str(&text),
+        Err(e) => vec![Diagnostic::without_location(format!(
+            "Failed to read file: {e}"
+        ))],
+    };
+
+    for diagnostic in &mut diagnostics {
+        diagnostic.path = Some(path.to_path_buf());
+    }
+
+    diagnostics
+}
+
+/// Lint `DBus` XML held in a string.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::{lint_str, Severity};
+///
+/// let xml = r#"
+/// <node>
+///   <interface name="org.example.Node">
+///     <signal name="Removed">
+///       <arg name="node" type="(so"/>
+///     </signal>
+///   </interface>
+/// </node>
+/// "#;
+///
+/// let diagnostics = lint_str(xml);
+/// assert_eq!(diagnostics.len(), 1);
+/// assert_eq!(diagnostics[0].severity, Severity::Error);
+/// assert_eq!(diagnostics[0].location.unwrap().line, 5);
+/// ```
+pub fn lint_str(xml: &str) -> Vec<Diagnostic> {
+    let content = match dom::parse(xml) {
+        Ok(content) => content,
+        Err(e) => return vec![Diagnostic::error(e.location, e.message)],
+    };
+
+    let mut diagnostics = Vec::new();
+    let mut roots = dom::root_elements(&content).peekable();
+
+    if roots.peek().is_none() {
+        diagnostics.push(Diagnostic::without_location("Document has no root <node>"));
+    }
+
+    for root in roots {
+        if root.name == "node" {
+            lint_node(root, &mut diagnostics);
+        } else {
+            diagnostics.push(Diagnostic::error(
+                root.location,
+                format!("Root element must be <node>, found <{}>", root.name),
+            ));
+        }
+    }
+
+    diagnostics
+}
+
+fn lint_node(node: &Element, diagnostics: &mut Vec<Diagnostic>) {
+    let mut interfaces: HashMap<&str, Location> = HashMap::new();
+    let mut nodes: HashMap<&str, Location> = HashMap::new();
+
+    for child in node.elements() {
+        match child.name.as_str() {
+            "interface" => {
+                if let Some(name) = child.attribute("name") {
+                    if let Some(first) = interfaces.insert(name, child.location) {
+                        diagnostics.push(Diagnostic::error(
+                            child.location,
+                            format!("Duplicate interface \"{name}\", first defined at {first}"),
+                        ));
+                    }
+                }
+                lint_interface(child, diagnostics);
+            }
+            "node" => {
+                match child.attribute("name") {
+                    Some(name) => {
+                        if let Some(first) = nodes.insert(name, child.location) {
+                            diagnostics.push(Diagnostic::error(
+                                child.location,
+                                format!("Duplicate node \"{name}\", first defined at {first}"),
+                            ));
+                        }
+                    }
+                    None => diagnostics.push(Diagnostic::error(
+                        child.location,
+                        "Child <node> without \"name\" attribute",
+                    )),
+                }
+                lint_node(child, diagnostics);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn lint_interface(interface: &Element, diagnostics: &mut Vec<Diagnostic>) {
+    let name = required_attribute(interface, "name", diagnostics);
+    if let Some(name) = name {
+        if let Err(reason) = check_interface_name(name) {
+            diagnostics.push(Diagnostic::error(
+                interface.location,
+                format!("Invalid interface name \"{name}\": {reason}"),
+            ));
+        }
+    }
+
+    // Members by kind and name.
+    let mut members: HashMap<(&str, &str), Location> = HashMap::new();
+    let mut has_members = false;
+
+    for child in interface.elements() {
+        let (kind, other) = match child.name.as_str() {
+            "method" => ("method", Some("signal")),
+            "signal" => ("signal", Some("method")),
+            "property" => ("property", None),
+            "annotation" => {
+                lint_annotation(child, diagnostics);
+                continue;
+            }
+            _ => continue,
+        };
+        has_members = true;
+
+        let Some(member) = required_attribute(child, "name", diagnostics) else {
+            lint_member(child, diagnostics);
+            continue;
+        };
+
+        if let Err(reason) = check_member_name(member) {
+            diagnostics.push(Diagnostic::error(
+                child.location,
+                format!("Invalid {kind} name \"{member}\": {reason}"),
+            ));
+        }
+
+        if let Some(first) = members.insert((kind, member), child.location) {
+            diagnostics.push(Diagnostic::error(
+                child.location,
+                format!("Duplicate {kind} \"{member}\", first defined at {first}"),
+            ));
+        } else if let Some(first) = other.and_then(|other| members.get(&(other, member))) {
+            diagnostics.push(Diagnostic::warning(
+                child.location,
+                format!("{kind} \"{member}\" has the same name as the member defined at {first}"),
+            ));
+        }
+
+        lint_member(child, diagnostics);
+    }
+
+    if !has_members {
+        diagnostics.push(Diagnostic::warning(
+            interface.location,
+            format!("Interface \"{}\" has no members", name.unwrap_or("")),
+        ));
+    }
+}
+
+fn lint_member(member: &Element, diagnostics: &mut Vec<Diagnostic>) {
+    if member.name == "property" {
+        if let Some(ty) = required_attribute(member, "type", diagnostics) {
+            lint_single_type(member, ty, diagnostics);
+        }
+        match required_attribute(member, "access", diagnostics) {
+            None | Some("read" | "write" | "readwrite") => {}
+            Some(access) => diagnostics.push(Diagnostic::error(
+                member.location,
+                format!(
+                    "Unknown access \"{access}\", expected \"read\", \"write\" or \"readwrite\""
+                ),
+            )),
+        }
+    }
+
+    let mut args: HashMap<&str, Location> = HashMap::new();
+
+    for child in member.elements() {
+        match child.name.as_str() {
+            "arg" if member.name != "property" => {
+                if let Some(name) = child.attribute("name") {
+                    if let Some(first) = args.insert(name, child.location) {
+                        diagnostics.push(Diagnostic::error(
+                            child.location,
+                            format!("Duplicate argument \"{name}\", first defined at {first}"),
+                        ));
+                    }
+                }
+                if let Some(ty) = required_attribute(child, "type", diagnostics) {
+                    lint_single_type(child, ty, diagnostics);
+                }
+                match (member.name.as_str(), child.attribute("direction")) {
+                    (_, None) | ("method", Some("in" | "out")) | ("signal", Some("out")) => {}
+                    ("signal", Some("in")) => diagnostics.push(Diagnostic::warning(
+                        child.location,
+                        "Signal arguments can only be \"out\"",
+                    )),
+                    (_, Some(direction)) => diagnostics.push(Diagnostic::error(
+                        child.location,
+                        format!("Unknown direction \"{direction}\", expected \"in\" or \"out\""),
+                    )),
+                }
+            }
+            "annotation" => lint_annotation(child, diagnostics),
+            _ => {}
+        }
+    }
+}
+
+fn lint_annotation(annotation: &Element, diagnostics: &mut Vec<Diagnostic>) {
+    if let Some(name) = required_attribute(annotation, "name", diagnostics) {
+        if let Err(reason) = check_interface_name(name) {
+            diagnostics.push(Diagnostic::warning(
+                annotation.location,
+                format!("Invalid annotation name \"{name}\": {reason}"),
+            ));
+        }
+    }
+    required_attribute(annotation, "value", diagnostics);
+}
+
+/// Lint a signature that must hold exactly one complete type.
+fn lint_single_type(element: &Element, ty: &str, diagnostics: &mut Vec<Diagnostic>) {
+    match check_signature(ty) {
+        Ok(1) => {}
+        Ok(count) => diagnostics.push(Diagnostic::error(
+            element.location,
+            format!("Type \"{ty}\" must be a single complete type, found {count}"),
+        )),
+        Err(reason) => diagnostics.push(Diagnostic::error(
+            element.location,
+            format!("Invalid signature \"{ty}\": {reason}"),
+        )),
+    }
+}
+
+fn required_attribute<'a>(
+    element: &'a Element,
+    name: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Option<&'a str> {
+    let value = element.attribute(name);
+    if value.is_none() {
+        diagnostics.push(Diagnostic::error(
+            element.location,
+            format!("<{}> is missing the \"{name}\" attribute", element.name),
+        ));
+    }
+    value
+}
+
+/// Check an interface name, which is also the grammar of annotation names.
+fn check_interface_name(name: &str) -> Result<(), String> {
+    if name.len() > MAX_LENGTH {
+        return Err(format!("longer than {MAX_LENGTH} bytes"));
+    }
+    let elements: Vec<&str> = name.split('.').collect();
+    if elements.len() < 2 {
+        return Err("must have at least two elements separated by '.'".to_string());
+    }
+    elements.into_iter().try_for_each(check_name_element)
+}
+
+/// Check a method, signal or property name.
+fn check_member_name(name: &str) -> Result<(), String> {
+    if name.len() > MAX_LENGTH {
+        return Err(format!("longer than {MAX_LENGTH} bytes"));
+    }
+    check_name_element(name)
+}
+
+fn check_name_element(element: &str) -> Result<(), String> {
+    let mut chars = element.chars();
+    match chars.next() {
+        None => return Err("empty element".to_string()),
+        Some(c) if c.is_ascii_digit() => {
+            return Err(format!("element \"{element}\" starts with a digit"))
+        }
+        _ => {}
+    }
+    match element
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || *c == '_'))
+    {
+        Some(c) => Err(format!("invalid character '{c}'")),
+        None => Ok(()),
+    }
+}
+
+/// Check a signature, returning the number of complete types it holds.
+pub(crate) fn check_signature(signature: &str) -> Result<usize, String> {
+    if signature.len() > MAX_LENGTH {
+        return Err(format!(
+            "{} bytes long, the maximum is {MAX_LENGTH}",
+            signature.len()
+        ));
+    }
+
+    let mut parser = SignatureChecker {
+        bytes: signature.as_bytes(),
+        position: 0,
+    };
+    let mut count = 0;
+    while parser.position < parser.bytes.len() {
+        parser.complete_type(0, 0)?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// Split a valid signature into its complete types.
+pub(crate) fn split_signature(signature: &str) -> Result<Vec<&str>, String> {
+    check_signature(signature)?;
+
+    let mut parser = SignatureChecker {
+        bytes: signature.as_bytes(),
+        position: 0,
+    };
+    let mut types = Vec::new();
+    while parser.position < parser.bytes.len() {
+        let start = parser.position;
+        parser.complete_type(0, 0)?;
+        types.push(&signature[start..parser.position]);
+    }
+    Ok(types)
+}
+
+struct SignatureChecker<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl SignatureChecker<'_> {
+    fn next(&mut self) -> Result<u8, String> {
+        let byte = self
+            .bytes
+            .get(self.position)
+            .copied()
+            .ok_or_else(|| "unexpected end of signature".to_string())?;
+        self.position += 1;
+        Ok(byte)
+    }
+
+    fn complete_type(&mut self, arrays: usize, structs: usize) -> Result<(), String> {
+        let at = self.position;
+        match self.next()? {
+            b'y' | b'b' | b'n' | b'q' | b'i' | b'u' | b'x' | b't' | b'd' | b'h' | b's' | b'o'
+            | b'g' | b'v' => Ok(()),
+            b'a' => {
+                if arrays == MAX_DEPTH {
+                    return Err(format!("arrays nested deeper than {MAX_DEPTH}"));
+                }
+                if self.bytes.get(self.position) == Some(&b'{') {
+                    self.position += 1;
+                    self.dict_entry(arrays + 1, structs + 1)
+                } else {
+                    self.complete_type(arrays + 1, structs)
+                }
+            }
+            b'(' => {
+                if structs == MAX_DEPTH {
+                    return Err(format!("structs nested deeper than {MAX_DEPTH}"));
+                }
+                if self.bytes.get(self.position) == Some(&b')') {
+                    return Err(format!("empty struct at offset {at}"));
+                }
+                while self.bytes.get(self.position) != Some(&b')') {
+                    self.complete_type(arrays, structs + 1)?;
+                }
+                self.position += 1;
+                Ok(())
+            }
+            b'{' => Err(format!("dict entry outside an array at offset {at}")),
+            byte => Err(format!(
+                "unexpected '{}' at offset {at}",
+                char::from(byte).escape_default()
+            )),
+        }
+    }
+
+    fn dict_entry(&mut self, arrays: usize, structs: usize) -> Result<(), String> {
+        if structs > MAX_DEPTH {
+            return Err(format!("structs nested deeper than {MAX_DEPTH}"));
+        }
+
+        let at = self.position;
+        match self.next()? {
+            b'y' | b'b' | b'n' | b'q' | b'i' | b'u' | b'x' | b't' | b'd' | b'h' | b's' | b'o'
+            | b'g' => {}
+            _ => return Err(format!("dict key at offset {at} is not a basic type")),
+        }
+        self.complete_type(arrays, structs)?;
+
+        let at = self.position;
+        match self.next()? {
+            b'}' => Ok(()),
+            _ => Err(format!(
+                "dict entry at offset {at} must hold exactly two types"
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn messages(xml: &str) -> Vec<(Severity, usize, String)> {
+        lint_str(xml)
+            .into_iter()
+            .map(|d| (d.severity, d.location.unwrap().line, d.message))
+            .collect()
+    }
+
+    #[test]
+    fn test_lint_valid_definition_file() {
+        assert!(lint("../xml/test_definition_file.xml").is_empty());
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn test_lint_compressed_file() {
+        use std::io::Write;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("node.xml.gz");
+        let file = std::fs::File::create(&path).unwrap();
+        let mut encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+        let xml = std::fs::read("../xml/test_definition_file.xml").unwrap();
+        encoder.write_all(&xml).unwrap();
+        encoder.finish().unwrap();
+
+        assert!(lint(&path).is_empty());
+    }
+
+    #[test]
+    fn test_lint_reports_spec_violations() {
+        let xml = r#"<node>
+  <interface name="org.example">
+    <method name="Get">
+      <arg name="key" type="s" direction="in"/>
+      <arg name="key" type="v" direction="inout"/>
+    </method>
+    <method name="Get"/>
+    <property name="Size" type="uu" access="readonly"/>
+  </interface>
+  <interface name="example"/>
+</node>"#;
+
+        let found = messages(xml);
+        let expected = [
+            (Severity::Error, 5, "Duplicate argument \"key\", first defined at 4:7"),
+            (
+                Severity::Error,
+                5,
+                "Unknown direction \"inout\", expected \"in\" or \"out\"",
+            ),
+            (Severity::Error, 7, "Duplicate method \"Get\", first defined at 3:5"),
+            (
+                Severity::Error,
+                8,
+                "Type \"uu\" must be a single complete type, found 2",
+            ),
+            (
+                Severity::Error,
+                8,
+                "Unknown access \"readonly\", expected \"read\", \"write\" or \"readwrite\"",
+            ),
+            (
+                Severity::Error,
+                10,
+                "Invalid interface name \"example\": must have at least two elements separated by '.'",
+            ),
+            (Severity::Warning, 10, "Interface \"example\" has no members"),
+        ];
+
+        assert_eq!(found.len(), expected.len(), "{found:#?}");
+        for ((severity, line, message), (e_severity, e_line, e_message)) in
+            found.iter().zip(expected)
+        {
+            assert_eq!(
+                (*severity, *line, message.as_str()),
+                (e_severity, e_line, e_message)
+            );
+        }
+    }
+
+    #[test]
+    fn test_check_signature() {
+        assert_eq!(check_signature("a{sv}"), Ok(1));
+        assert_eq!(check_signature("((so)(so)(so)iiassusau)"), Ok(1));
+        assert_eq!(check_signature("su"), Ok(2));
+        assert_eq!(check_signature(""), Ok(0));
+        assert!(check_signature("{sv}").is_err());
+        assert!(check_signature("a{vs}").is_err());
+        assert!(check_signature("a{sss}").is_err());
+        assert!(check_signature("()").is_err());
+        assert!(check_signature("(s").is_err());
+
+        let deep_arrays = "a".repeat(33) + "y";
+        assert!(check_signature(&deep_arrays).is_err());
+        let deep_structs = "(".repeat(33) + "y" + &")".repeat(33);
+        assert!(check_signature(&deep_structs).is_err());
+        let long = "y".repeat(256);
+        assert!(check_signature(&long).is_err());
+    }
+
+    #[test]
+    fn test_split_signature() {
+        assert_eq!(
+            split_signature("(so)a{sv}u"),
+            Ok(vec!["(so)", "a{sv}", "u"])
+        );
+        assert_eq!(split_signature(""), Ok(vec![]));
+        assert!(split_signature("(so").is_err());
+    }
+}
@@ -0,0 +1,294 @@
+//! Loading of XML files which use `xi:include`, or hold more than one `<node>`.
+//!
+//! Upstream specifications sometimes split interfaces across files, or ship a file which
+//! concatenates several documents. Both are accepted as they are, compressed or not.
+
+use std::{
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use zbus_xml::Node;
+
+use crate::{
+    compression::{self, XML_EXTENSIONS},
+    dom::{self, Content, Element},
+    LockstepError::IncludeCycle,
+    Result,
+};
+
+/// The XInclude namespace.
+const XINCLUDE_NAMESPACE: &str = "http://www.w3.org/2001/XInclude";
+
+/// Read an XML file, with all `xi:include` elements replaced by the content they refer to.
+///
+/// Included files are looked up relative to the including file. Including a file that is
+/// already being included fails with [`LockstepError::IncludeCycle`](crate::LockstepError).
+///
+/// An included document's unnamed root `<node>` is merged into the `<node>` that includes it,
+/// so a shared root can pull in one file per interface. A named root `<node>` becomes a child
+/// node. If an include fails, the content of its `xi:fallback`, if any, is used.
+///
+/// Files compressed with gzip or zstd are decompressed, given the `gzip` or `zstd` feature.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::fs;
+/// use zbus_lockstep::load_xml;
+///
+/// let dir = tempfile::tempdir().unwrap();
+/// fs::write(
+///     dir.path().join("Node.xml"),
+///     r#"<node><interface name="org.example.Node"><method name="Ping"/></interface></node>"#,
+/// )
+/// .unwrap();
+/// fs::write(
+///     dir.path().join("all.xml"),
+///     r#"<node xmlns:xi="http://www.w3.org/2001/XInclude"><xi:include href="Node.xml"/></node>"#,
+/// )
+/// .unwrap();
+///
+/// let xml = load_xml(dir.path().join("all.xml")).unwrap();
+/// assert!(xml.contains(r#"<interface name="org.example.Node">"#));
+/// assert!(!xml.contains("xi:include"));
+/// ```
+pub fn load_xml(path: impl AsRef<Path>) -> Result<String> {
+    let mut stack = Vec::new();
+    let content = load_content(path.as_ref(), &mut stack)?;
+
+    let mut xml = String::new();
+    dom::write(&content, &mut xml);
+    Ok(xml)
+}
+
+/// Load the nodes of an XML file, with includes resolved.
+///
+/// A file may hold several concatenated documents, each with its own root `<node>`.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::load_nodes;
+///
+/// let nodes = load_nodes("../xml/test_definition_file.xml").unwrap();
+/// assert_eq!(nodes[0].interfaces()[0].name(), "org.example.Node");
+/// ```
+pub fn load_nodes(path: impl AsRef<Path>) -> Result<Vec<Node<'static>>> {
+    nodes_from_str(&load_xml(path)?)
+}
+
+/// Whether `path` names an XML file this build can read, see [`load_xml`].
+///
+/// That is a file ending in `.xml`, or in `.xml.gz` or `.xml.zst` given the `gzip` or `zstd`
+/// feature.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::is_xml_file;
+///
+/// assert!(is_xml_file("xml/Cache.xml"));
+/// assert!(!is_xml_file("xml/README"));
+/// assert_eq!(is_xml_file("xml/Cache.xml.gz"), cfg!(feature = "gzip"));
+/// ```
+pub fn is_xml_file(path: impl AsRef<Path>) -> bool {
+    path.as_ref()
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| XML_EXTENSIONS.iter().any(|ext| name.ends_with(ext)))
+}
+
+/// Read the nodes of XML which may hold several concatenated documents, compressed or not.
+///
+/// Without a file path to resolve them against, `xi:include`s are left as they are.
+pub(crate) fn read_nodes(mut xml: impl Read) -> Result<Vec<Node<'static>>> {
+    let mut bytes = Vec::new();
+    xml.read_to_end(&mut bytes)?;
+    nodes_from_str(&compression::decompress_to_string(bytes)?)
+}
+
+/// Parse XML which may hold several concatenated documents.
+pub(crate) fn nodes_from_str(xml: &str) -> Result<Vec<Node<'static>>> {
+    let content = dom::parse(xml).map_err(|e| format!("{}: {}", e.location, e.message))?;
+    let roots: Vec<&Element> = dom::root_elements(&content).collect();
+
+    if roots.len() == 1 {
+        return Ok(vec![Node::from_reader(xml.as_bytes())?]);
+    }
+
+    roots
+        .into_iter()
+        .map(|root| {
+            let mut document = String::new();
+            dom::write(&[Content::Element(root.clone())], &mut document);
+            Ok(Node::from_reader(document.as_bytes())?)
+        })
+        .collect()
+}
+
+fn load_content(path: &Path, stack: &mut Vec<PathBuf>) -> Result<Vec<Content>> {
+    let canonical = path
+        .canonicalize()
+        .map_err(|e| format!("Failed to read \"{}\": {e}", path.display()))?;
+
+    if stack.contains(&canonical) {
+        return Err(IncludeCycle(canonical.display().to_string()).into());
+    }
+
+    let text = std::fs::read(&canonical)
+        .map_err(Into::into)
+        .and_then(compression::decompress_to_string)
+        .map_err(|e| format!("Failed to read \"{}\": {e}", canonical.display()))?;
+    let mut content = dom::parse(&text).map_err(|e| {
+        format!(
+            "Failed to parse \"{}\" at {}: {}",
+            canonical.display(),
+            e.location,
+            e.message
+        )
+    })?;
+
+    stack.push(canonical.clone());
+    let base = canonical.parent().unwrap_or(Path::new("/")).to_path_buf();
+    let result = resolve_includes(&mut content, &base, &[], stack);
+    stack.pop();
+
+    result.map(|()| content)
+}
+
+/// Replace the includes in `content` in place.
+///
+/// `prefixes` holds the namespace prefixes bound to the XInclude namespace by ancestors.
+fn resolve_includes(
+    content: &mut Vec<Content>,
+    base: &Path,
+    prefixes: &[String],
+    stack: &mut Vec<PathBuf>,
+) -> Result<()> {
+    let mut resolved = Vec::with_capacity(content.len());
+
+    for item in content.drain(..) {
+        let Content::Element(mut element) = item else {
+            resolved.push(item);
+            continue;
+        };
+
+        let mut prefixes = prefixes.to_vec();
+        prefixes.extend(element.attributes.iter().filter_map(|attribute| {
+            let prefix = attribute.name.strip_prefix("xmlns:")?;
+            (attribute.value == XINCLUDE_NAMESPACE).then(|| prefix.to_string())
+        }));
+
+        if !is_xinclude(&element, "include", &prefixes) {
+            resolve_includes(&mut element.children, base, &prefixes, stack)?;
+            resolved.push(Content::Element(element));
+            continue;
+        }
+
+        match include(&element, base, stack) {
+            Ok(included) => resolved.extend(included),
+            Err(e) => {
+                let Some(fallback) = element
+                    .elements()
+                    .find(|child| is_xinclude(child, "fallback", &prefixes))
+                else {
+                    return Err(e);
+                };
+                let mut children = fallback.children.clone();
+                resolve_includes(&mut children, base, &prefixes, stack)?;
+                resolved.extend(children);
+            }
+        }
+    }
+
+    *content = resolved;
+    Ok(())
+}
+
+fn is_xinclude(element: &Element, local_name: &str, prefixes: &[String]) -> bool {
+    element
+        .name
+        .split_once(':')
+        .is_some_and(|(prefix, name)| name == local_name && prefixes.iter().any(|p| p == prefix))
+}
+
+/// The content an `xi:include` element refers to.
+fn include(element: &Element, base: &Path, stack: &mut Vec<PathBuf>) -> Result<Vec<Content>> {
+    let href = element
+        .attribute("href")
+        .ok_or("xi:include without \"href\" attribute")?;
+
+    if element
+        .attribute("parse")
+        .is_some_and(|parse| parse != "xml")
+    {
+        return Err(format!("Unsupported xi:include parse mode for \"{href}\"").into());
+    }
+
+    let included = load_content(&base.join(href), stack)?;
+
+    let mut content = Vec::new();
+    for item in included {
+        match item {
+            // Merge unnamed root nodes into the including node.
+            Content::Element(root) if root.name == "node" && root.attribute("name").is_none() => {
+                content.extend(root.children);
+            }
+            Content::Element(root) => content.push(Content::Element(root)),
+            // The prolog of the included document is dropped.
+            _ => {}
+        }
+    }
+    Ok(content)
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn test_include_cycle_is_detected() {
+        let dir = tempfile::tempdir().unwrap();
+        let including = r#"<node xmlns:xi="http://www.w3.org/2001/XInclude">
+            <xi:include href="b.xml"/>
+        </node>"#;
+        fs::write(dir.path().join("a.xml"), including).unwrap();
+        fs::write(
+            dir.path().join("b.xml"),
+            including.replace("b.xml", "a.xml"),
+        )
+        .unwrap();
+
+        let error = load_xml(dir.path().join("a.xml")).unwrap_err();
+        assert!(error.to_string().contains("a.xml"), "{error}");
+    }
+
+    #[test]
+    fn test_include_fallback() {
+        let dir = tempfile::tempdir().unwrap();
+        let xml = r#"<node xmlns:xi="http://www.w3.org/2001/XInclude">
+            <xi:include href="missing.xml">
+                <xi:fallback><interface name="org.example.Fallback"/></xi:fallback>
+            </xi:include>
+        </node>"#;
+        fs::write(dir.path().join("a.xml"), xml).unwrap();
+
+        let nodes = load_nodes(dir.path().join("a.xml")).unwrap();
+        assert_eq!(nodes[0].interfaces()[0].name(), "org.example.Fallback");
+    }
+
+    #[test]
+    fn test_nodes_from_concatenated_documents() {
+        let xml = r#"<?xml version="1.0"?>
+            <node><interface name="org.example.First"/></node>
+            <node><interface name="org.example.Second"/></node>
+        "#;
+
+        let nodes = nodes_from_str(xml).unwrap();
+        assert_eq!(nodes.len(), 2);
+        assert_eq!(nodes[1].interfaces()[0].name(), "org.example.Second");
+    }
+}
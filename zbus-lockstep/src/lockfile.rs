@@ -0,0 +1,536 @@
+//! A lockfile recording the XML definitions of the members a crate validates against.
+//!
+//! Like `Cargo.lock` surfaces dependency updates, `lockstep.lock` surfaces changes to vendored
+//! XML: a test checking the lockfile fails until it is updated, so the change shows up in the
+//! same commit that updates the XML.
+
+use std::{fmt, path::Path, str::FromStr};
+
+use zbus_xml::{Annotation, Arg, ArgDirection, Interface};
+
+use crate::{
+    access_str, args_signature, interfaces_with_paths, load_nodes, LockstepError, MsgType, Result,
+    Scanner,
+};
+
+/// The conventional name of the lockfile.
+pub const LOCKFILE: &str = "lockstep.lock";
+
+/// Set this environment variable to `1` or `true` to have [`check_lockfile`] update the lockfile.
+pub const UPDATE_LOCKFILE_ENV: &str = "LOCKSTEP_UPDATE_LOCK";
+
+/// A member, as recorded in the lockfile.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct LockedMember {
+    pub kind: MsgType,
+    pub interface: String,
+    pub name: String,
+    /// The property type, or signal body, signature. For methods, the input and output
+    /// signatures separated by `->`.
+    pub signature: String,
+    /// Hash of the member's definition: arguments with names and directions, access and
+    /// annotations. Formatting and comments do not count.
+    pub hash: String,
+}
+
+impl LockedMember {
+    /// Record a member of a tree of XML files.
+    fn from_xml(
+        interfaces: &[&Interface<'_>],
+        kind: MsgType,
+        interface_name: &str,
+        name: &str,
+    ) -> Result<Self> {
+        let interface = interfaces
+            .iter()
+            .find(|interface| interface.name() == interface_name)
+            .ok_or(LockstepError::InterfaceNotFound(interface_name.to_owned()))?;
+
+        let mut definition = format!("{kind} {name}\n");
+        let signature = match kind {
+            MsgType::Method => {
+                let method = interface
+                    .methods()
+                    .iter()
+                    .find(|method| method.name() == name)
+                    .ok_or(LockstepError::MemberNotFound(name.to_owned()))?;
+                write_args(&mut definition, method.args());
+                write_annotations(&mut definition, method.annotations());
+
+                format!(
+                    "{}->{}",
+                    args_signature(method.args(), Some(ArgDirection::In)),
+                    args_signature(method.args(), Some(ArgDirection::Out))
+                )
+            }
+            MsgType::Signal => {
+                let signal = interface
+                    .signals()
+                    .iter()
+                    .find(|signal| signal.name() == name)
+                    .ok_or(LockstepError::MemberNotFound(name.to_owned()))?;
+                write_args(&mut definition, signal.args());
+                write_annotations(&mut definition, signal.annotations());
+
+                signal
+                    .args()
+                    .iter()
+                    .map(|arg| arg.ty().to_string())
+                    .collect()
+            }
+            MsgType::Property => {
+                let property = interface
+                    .properties()
+                    .iter()
+                    .find(|property| property.name() == name)
+                    .ok_or(LockstepError::PropertyNotFound(name.to_owned()))?;
+                definition.push_str(&format!("access {}\n", access_str(property.access())));
+                write_annotations(&mut definition, property.annotations());
+
+                property.ty().to_string()
+            }
+        };
+
+        // Interface annotations, e.g. `EmitsChangedSignal`, apply to the member as well.
+        write_annotations(&mut definition, interface.annotations());
+        definition.push_str(&format!("signature {signature}\n"));
+
+        Ok(LockedMember {
+            kind,
+            interface: interface_name.to_owned(),
+            name: name.to_owned(),
+            signature,
+            hash: format!("{:016x}", fnv1a(definition.as_bytes())),
+        })
+    }
+
+    fn key(&self) -> (MsgType, &str, &str) {
+        (self.kind, &self.interface, &self.name)
+    }
+}
+
+impl fmt::Display for LockedMember {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}.{}", self.kind, self.interface, self.name)
+    }
+}
+
+fn write_args(definition: &mut String, args: &[Arg]) {
+    for arg in args {
+        let direction = match arg.direction() {
+            Some(ArgDirection::In) => "in",
+            Some(ArgDirection::Out) => "out",
+            None => "-",
+        };
+        definition.push_str(&format!(
+            "arg {} {} {direction}\n",
+            arg.name().unwrap_or("-"),
+            arg.ty().to_string()
+        ));
+    }
+}
+
+fn write_annotations(definition: &mut String, annotations: &[Annotation]) {
+    for annotation in annotations {
+        definition.push_str(&format!(
+            "annotation {} {}\n",
+            annotation.name(),
+            annotation.value()
+        ));
+    }
+}
+
+/// 64-bit FNV-1a, stable across platforms and releases, unlike `DefaultHasher`.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// The content of a `lockstep.lock` file.
+///
+/// The file is TOML, listing one `[[member]]` table per member, sorted by kind, interface and
+/// name. Each value is a basic string, with `"`, `\` and control characters escaped.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Lockfile {
+    pub members: Vec<LockedMember>,
+}
+
+impl Lockfile {
+    /// Record `members`, given as kind, interface name and member name, from the XML files in
+    /// `xml_dir`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use zbus_lockstep::{Lockfile, MsgType};
+    ///
+    /// let lockfile = Lockfile::generate(
+    ///     "../xml",
+    ///     &[(MsgType::Signal, "org.example.Node", "RemoveNode")],
+    /// )
+    /// .unwrap();
+    ///
+    /// assert_eq!(lockfile.members[0].signature, "(so)");
+    /// ```
+    pub fn generate(xml_dir: impl AsRef<Path>, members: &[(MsgType, &str, &str)]) -> Result<Self> {
+        let mut nodes = Vec::new();
+        for path in Scanner::new().scan(xml_dir)?.files {
+            nodes.extend(load_nodes(path)?);
+        }
+        let interfaces: Vec<_> = nodes
+            .iter()
+            .flat_map(interfaces_with_paths)
+            .map(|(_, interface)| interface)
+            .collect();
+
+        let mut members = members
+            .iter()
+            .map(|&(kind, interface, name)| {
+                LockedMember::from_xml(&interfaces, kind, interface, name)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        members.sort_by(|a, b| sort_key(a).cmp(&sort_key(b)));
+        members.dedup();
+
+        Ok(Lockfile { members })
+    }
+
+    /// Read a lockfile.
+    pub fn read(path: impl AsRef<Path>) -> Result<Self> {
+        std::fs::read_to_string(path)?.parse()
+    }
+
+    /// Write the lockfile.
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<()> {
+        Ok(std::fs::write(path, self.to_string())?)
+    }
+
+    /// Describe how `current` differs from this lockfile, one line per member.
+    pub fn drift(&self, current: &Lockfile) -> Vec<String> {
+        let mut drift = Vec::new();
+
+        for locked in &self.members {
+            match current.members.iter().find(|m| m.key() == locked.key()) {
+                None => drift.push(format!("{locked}: no longer validated")),
+                Some(member) if member.signature != locked.signature => drift.push(format!(
+                    "{locked}: signature changed from `{}` to `{}`",
+                    locked.signature, member.signature
+                )),
+                Some(member) if member.hash != locked.hash => {
+                    drift.push(format!("{locked}: definition changed"));
+                }
+                Some(_) => {}
+            }
+        }
+
+        for member in &current.members {
+            if !self.members.iter().any(|m| m.key() == member.key()) {
+                drift.push(format!("{member}: not in the lockfile"));
+            }
+        }
+
+        drift
+    }
+}
+
+fn sort_key(member: &LockedMember) -> (u8, &str, &str) {
+    let kind = match member.kind {
+        MsgType::Method => 0,
+        MsgType::Signal => 1,
+        MsgType::Property => 2,
+    };
+    (kind, &member.interface, &member.name)
+}
+
+impl fmt::Display for Lockfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "# This file is generated by zbus-lockstep, do not edit it by hand."
+        )?;
+        writeln!(
+            f,
+            "# Update it by running the tests with {UPDATE_LOCKFILE_ENV}=1."
+        )?;
+        for member in &self.members {
+            writeln!(f)?;
+            writeln!(f, "[[member]]")?;
+            writeln!(f, "kind = \"{}\"", member.kind)?;
+            writeln!(f, "interface = \"{}\"", escape(&member.interface))?;
+            writeln!(f, "name = \"{}\"", escape(&member.name))?;
+            writeln!(f, "signature = \"{}\"", escape(&member.signature))?;
+            writeln!(f, "hash = \"{}\"", escape(&member.hash))?;
+        }
+        Ok(())
+    }
+}
+
+/// Escape `value` for a TOML basic string.
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            '\r' => escaped.push_str("\\r"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", u32::from(c))),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// The value of a TOML basic string, without its quotes, or `None` if it is not valid.
+fn unescape(value: &str) -> Option<String> {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unescaped.push(match chars.next()? {
+                '"' => '"',
+                '\\' => '\\',
+                'n' => '\n',
+                't' => '\t',
+                'r' => '\r',
+                'b' => '\u{8}',
+                'f' => '\u{c}',
+                'u' => {
+                    let hex: String = chars.by_ref().take(4).collect();
+                    char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?
+                }
+                _ => return None,
+            }),
+            '"' => return None,
+            c => unescaped.push(c),
+        }
+    }
+    Some(unescaped)
+}
+
+impl FromStr for Lockfile {
+    type Err = Box<dyn std::error::Error>;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut tables: Vec<Vec<(&str, String)>> = Vec::new();
+
+        for (index, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if line == "[[member]]" {
+                tables.push(Vec::new());
+                continue;
+            }
+
+            let invalid = || format!("Invalid lockfile line {}: {line}", index + 1);
+            let (key, value) = line.split_once('=').ok_or_else(invalid)?;
+            let value = value
+                .trim()
+                .strip_prefix('"')
+                .and_then(|value| value.strip_suffix('"'))
+                .and_then(unescape)
+                .ok_or_else(invalid)?;
+            tables
+                .last_mut()
+                .ok_or_else(invalid)?
+                .push((key.trim(), value));
+        }
+
+        let members = tables
+            .into_iter()
+            .map(|table| {
+                let field = |name| {
+                    table
+                        .iter()
+                        .find(|(key, _)| *key == name)
+                        .map(|(_, value)| value.clone())
+                        .ok_or_else(|| format!("Lockfile member without \"{name}\""))
+                };
+                let kind = match field("kind")?.as_str() {
+                    "method" => MsgType::Method,
+                    "signal" => MsgType::Signal,
+                    "property" => MsgType::Property,
+                    kind => return Err(format!("Unknown member kind \"{kind}\"").into()),
+                };
+                Ok(LockedMember {
+                    kind,
+                    interface: field("interface")?,
+                    name: field("name")?,
+                    signature: field("signature")?,
+                    hash: field("hash")?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Lockfile { members })
+    }
+}
+
+/// Check that the lockfile at `path` matches the XML definitions of `members`.
+///
+/// `members` are given as kind, interface name and member name. Only the members listed are
+/// recorded, so keep the list next to the types validated against them. If the lockfile is
+/// missing, or differs, this fails with [`LockstepError::LockfileOutdated`], listing the changed
+/// members. With the `LOCKSTEP_UPDATE_LOCK` environment variable set to `1` or `true`, the
+/// lockfile is written instead. Other values, such as `0`, leave it alone.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::{check_lockfile, MsgType};
+///
+/// let dir = tempfile::tempdir().unwrap();
+/// let lockfile = dir.path().join("lockstep.lock");
+/// let members = [(MsgType::Property, "org.example.Node", "Features")];
+///
+/// // Without a lockfile, the check fails.
+/// assert!(check_lockfile(&lockfile, "../xml", &members).is_err());
+///
+/// zbus_lockstep::Lockfile::generate("../xml", &members)
+///     .unwrap()
+///     .write(&lockfile)
+///     .unwrap();
+/// assert!(check_lockfile(&lockfile, "../xml", &members).is_ok());
+/// ```
+pub fn check_lockfile(
+    path: impl AsRef<Path>,
+    xml_dir: impl AsRef<Path>,
+    members: &[(MsgType, &str, &str)],
+) -> Result<()> {
+    let path = path.as_ref();
+    let current = Lockfile::generate(xml_dir, members)?;
+
+    if update_requested(std::env::var(UPDATE_LOCKFILE_ENV).ok().as_deref()) {
+        return current.write(path);
+    }
+
+    let drift = match Lockfile::read(path) {
+        Ok(locked) => locked.drift(&current),
+        Err(_) if !path.exists() => vec![String::from("the lockfile does not exist")],
+        Err(e) => return Err(e),
+    };
+
+    if drift.is_empty() {
+        Ok(())
+    } else {
+        Err(LockstepError::LockfileOutdated(path.display().to_string(), drift).into())
+    }
+}
+
+/// Whether the value of [`UPDATE_LOCKFILE_ENV`] asks for the lockfile to be updated.
+fn update_requested(value: Option<&str>) -> bool {
+    matches!(value, Some("1" | "true"))
+}
+
+/// Like [`check_lockfile`], but panics with the list of changes.
+pub fn assert_lockfile(
+    path: impl AsRef<Path>,
+    xml_dir: impl AsRef<Path>,
+    members: &[(MsgType, &str, &str)],
+) {
+    if let Err(e) = check_lockfile(path, xml_dir, members) {
+        panic!("{e}");
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use super::*;
+
+    const XML: &str = r#"<node>
+        <interface name="org.example.Node">
+            <method name="Move">
+                <arg name="x" type="i" direction="in"/>
+                <arg name="ok" type="b" direction="out"/>
+            </method>
+            <signal name="Moved"><arg name="x" type="i"/></signal>
+        </interface>
+    </node>"#;
+
+    const MEMBERS: [(MsgType, &str, &str); 2] = [
+        (MsgType::Signal, "org.example.Node", "Moved"),
+        (MsgType::Method, "org.example.Node", "Move"),
+    ];
+
+    #[test]
+    fn test_lockfile_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("Node.xml"), XML).unwrap();
+
+        let lockfile = Lockfile::generate(dir.path(), &MEMBERS).unwrap();
+        assert_eq!(lockfile.members[0].signature, "i->b");
+        assert_eq!(lockfile.members[1].name, "Moved");
+
+        let parsed: Lockfile = lockfile.to_string().parse().unwrap();
+        assert_eq!(parsed, lockfile);
+    }
+
+    #[test]
+    fn test_lockfile_escapes_values() {
+        let member = LockedMember {
+            kind: MsgType::Property,
+            interface: String::from("org.example.\"Node\" = 1"),
+            name: String::from("Name\nkind = \"method\"\\\u{1}"),
+            signature: String::from("s"),
+            hash: String::from("0123456789abcdef"),
+        };
+        let lockfile = Lockfile {
+            members: vec![member],
+        };
+
+        let written = lockfile.to_string();
+        assert!(written.contains("interface = \"org.example.\\\"Node\\\" = 1\"\n"));
+        assert!(written.contains("name = \"Name\\nkind = \\\"method\\\"\\\\\\u0001\"\n"));
+
+        let parsed: Lockfile = written.parse().unwrap();
+        assert_eq!(parsed, lockfile);
+    }
+
+    #[test]
+    fn test_drift() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("Node.xml"), XML).unwrap();
+        let locked = Lockfile::generate(dir.path(), &MEMBERS).unwrap();
+
+        let fewer = Lockfile::generate(dir.path(), &MEMBERS[..1]).unwrap();
+        assert_eq!(
+            locked.drift(&fewer),
+            ["method org.example.Node.Move: no longer validated"]
+        );
+
+        // Formatting does not count, a renamed argument and a changed type do.
+        let changed = XML
+            .replace('\n', " ")
+            .replace("name=\"ok\"", "name=\"moved\"")
+            .replace(
+                "<arg name=\"x\" type=\"i\"/>",
+                "<arg name=\"x\" type=\"u\"/>",
+            );
+        fs::write(dir.path().join("Node.xml"), changed).unwrap();
+        let current = Lockfile::generate(dir.path(), &MEMBERS).unwrap();
+
+        assert_eq!(
+            locked.drift(&current),
+            [
+                "method org.example.Node.Move: definition changed",
+                "signal org.example.Node.Moved: signature changed from `i` to `u`",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_update_requested() {
+        assert!(update_requested(Some("1")));
+        assert!(update_requested(Some("true")));
+        assert!(!update_requested(Some("0")));
+        assert!(!update_requested(Some("false")));
+        assert!(!update_requested(Some("")));
+        assert!(!update_requested(None));
+    }
+}
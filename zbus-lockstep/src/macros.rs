@@ -119,46 +119,53 @@ pub fn resolve_xml_path(xml: Option<&str>) -> Result<PathBuf> {
 }
 
 /// A generic helper to find the file path and interface name of a member.
+///
+/// With an object path, only interfaces implemented at that path are considered.
 #[doc(hidden)]
 #[macro_export]
 macro_rules! find_definition_in_dbus_xml {
-    ($xml_path_buf:expr, $member:expr, $iface:expr, $msg_type:expr) => {{
+    ($xml_path_buf:expr, $member:expr, $iface:expr, $msg_type:expr) => {
+        $crate::find_definition_in_dbus_xml!($xml_path_buf, $member, $iface, $msg_type, None)
+    };
+
+    ($xml_path_buf:expr, $member:expr, $iface:expr, $msg_type:expr, $object_path:expr) => {{
     use $crate::MsgType;
 
     let xml_path_buf: std::path::PathBuf = $xml_path_buf;
     let member: &str = $member;
     let iface: Option<String> = $iface;
     let msg_type: MsgType = $msg_type;
+    let object_path: Option<&str> = $object_path;
 
     let mut xml_file_path = None;
     let mut interface_name = None;
 
-    let read_dir = std::fs::read_dir(&xml_path_buf).expect("Failed to read XML directory");
-
-    // Walk the XML files in the directory.
-    for entry in read_dir {
-        let entry = entry.expect("Failed to read entry");
+    let scan = $crate::Scanner::new().scan(&xml_path_buf).expect("Failed to read XML directory");
 
-        // Skip directories and non-XML files.
-        if entry.path().is_dir() || entry.path().extension().unwrap() != "xml" {
-            continue;
-        }
-
-        let entry_path = entry.path().clone();
-        let file = std::fs::File::open(entry.path()).expect("Failed to open file");
-        let node = $crate::zbus_xml::Node::from_reader(file).expect("Failed to parse XML file");
+    // Walk the XML files in the directory, in order of their names.
+    for entry_path in &scan.files {
+        let nodes = $crate::load_nodes(entry_path).expect("Failed to load XML file");
 
-        for interface in node.interfaces() {
+        // Interfaces of child nodes, and of every document in the file, are considered too.
+        for (path, interface) in nodes.iter().flat_map(|node| $crate::interfaces_with_paths(node)) {
             // If called with an `iface` arg, skip he interfaces that do not match.
             if iface.is_some() && interface.name().as_str() != iface.clone().unwrap()  {
                 continue;
             }
 
+            // If called with an `object_path` arg, skip the interfaces implemented elsewhere.
+            if object_path.is_some_and(|object_path| {
+                object_path.trim_end_matches('/') != path.trim_end_matches('/')
+            }) {
+                continue;
+            }
+
             match msg_type {
                 MsgType::Method => {
                     for dbus_item in interface.methods() {
                         if dbus_item.name() == member {
-                            if interface_name.is_some() {
+                            // The same interface may be implemented at several object paths.
+                            if interface_name.is_some() && interface_name.as_deref() != Some(interface.name().as_str()) {
                                 panic!(
                                     "Multiple interfaces offer the same {:?} member: {}, please specify the interface name.",
                                     msg_type, member
@@ -173,7 +180,8 @@ macro_rules! find_definition_in_dbus_xml {
                 MsgType::Signal => {
                     for dbus_item in interface.signals() {
                         if dbus_item.name() == member {
-                            if interface_name.is_some() {
+                            // The same interface may be implemented at several object paths.
+                            if interface_name.is_some() && interface_name.as_deref() != Some(interface.name().as_str()) {
                                 panic!(
                                     "Multiple interfaces offer the same {:?} member: {}, please specify the interface name.",
                                     msg_type, member
@@ -188,7 +196,8 @@ macro_rules! find_definition_in_dbus_xml {
                 MsgType::Property => {
                     for dbus_item in interface.properties() {
                         if dbus_item.name() == member {
-                            if interface_name.is_some() {
+                            // The same interface may be implemented at several object paths.
+                            if interface_name.is_some() && interface_name.as_deref() != Some(interface.name().as_str()) {
                                 panic!(
                                     "Multiple interfaces offer the same {:?} member: {}, please specify the interface name.",
                                     msg_type, member
@@ -206,7 +215,9 @@ macro_rules! find_definition_in_dbus_xml {
 
     // If the interface member was not found, return an error.
     if xml_file_path.is_none() {
-        panic!("Member not found in XML files.");
+        // List what was not looked at, in case the member is in a skipped file.
+        let skipped: String = scan.skipped.iter().map(|skipped| format!("\n  skipped {skipped}")).collect();
+        panic!("Member not found in XML files.{skipped}");
     }
 
     (xml_file_path.unwrap(), interface_name.unwrap())
@@ -255,6 +266,16 @@ macro_rules! find_definition_in_dbus_xml {
 ///
 /// let _sig = method_return_signature!(member: "RequestName", interface: "org.example.Node", argument: "grape");
 /// ```
+///
+/// If an interface is implemented differently at several object paths, the named form takes a
+/// `path` to look only at the interfaces implemented there:
+///
+/// ```rust
+/// # use zbus_lockstep::method_return_signature;
+/// # std::env::set_var("LOCKSTEP_XML_PATH", "../xml");
+/// let sig = method_return_signature!(member: "Dim", path: "/org/example/lamp/desk");
+/// assert_eq!(sig, "d");
+/// ```
 #[macro_export]
 macro_rules! method_return_signature {
     ($member:expr) => {{
@@ -272,8 +293,8 @@ macro_rules! method_return_signature {
         let (file_path, interface_name) =
             $crate::find_definition_in_dbus_xml!(xml_path, member, None, MsgType::Method);
 
-        let file = std::fs::File::open(file_path).expect("Failed to open file");
-        $crate::get_method_return_type(file, &interface_name, member, None)
+        let xml = $crate::load_xml(&file_path).expect("Failed to load XML file");
+        $crate::get_method_return_type(xml.as_bytes(), &interface_name, member, None)
             .expect("Failed to get method arguments type signature")
     }};
 
@@ -298,8 +319,8 @@ macro_rules! method_return_signature {
         let (file_path, interface_name) =
             $crate::find_definition_in_dbus_xml!(xml_path, member, interface, MsgType::Method);
 
-        let file = std::fs::File::open(file_path).expect("Failed to open file");
-        $crate::get_method_return_type(file, &interface_name, member, None)
+        let xml = $crate::load_xml(&file_path).expect("Failed to load XML file");
+        $crate::get_method_return_type(xml.as_bytes(), &interface_name, member, None)
             .expect("Failed to get method arguments type signature")
     }};
 
@@ -325,14 +346,49 @@ macro_rules! method_return_signature {
         let (file_path, interface_name) =
             $crate::find_definition_in_dbus_xml!(xml_path, member, interface, MsgType::Method);
 
-        let file = std::fs::File::open(file_path).expect("Failed to open file");
-        $crate::get_method_return_type(file, &interface_name, member, argument)
+        let xml = $crate::load_xml(&file_path).expect("Failed to load XML file");
+        $crate::get_method_return_type(xml.as_bytes(), &interface_name, member, argument)
             .expect("Failed to get method argument(s) type signature")
     }};
 
     (member: $member:expr, interface: $interface:expr, argument: $argument:expr) => {
         $crate::method_return_signature!($member, $interface, $argument)
     };
+
+    (member: $member:expr, path: $path:expr) => {
+        $crate::method_return_signature!(@at $member, None, None, $path)
+    };
+
+    (member: $member:expr, interface: $interface:expr, path: $path:expr) => {
+        $crate::method_return_signature!(@at $member, Some($interface.to_string()), None, $path)
+    };
+
+    (member: $member:expr, interface: $interface:expr, argument: $argument:expr, path: $path:expr) => {
+        $crate::method_return_signature!(@at $member, Some($interface.to_string()), Some($argument), $path)
+    };
+
+    // Looks for the member only among the interfaces implemented at the object path.
+    (@at $member:expr, $interface:expr, $argument:expr, $path:expr) => {{
+        use $crate::MsgType;
+        let member = $member;
+        let interface: Option<String> = $interface;
+        let path: &str = $path;
+
+        // Looking for default path or path specified by environment variable.
+        let current_dir: std::path::PathBuf = std::env::current_dir().unwrap();
+        let xml_path = $crate::resolve_xml_path(None).expect(&format!(
+            "Failed to resolve XML path, current dir: {}",
+            current_dir.to_str().unwrap()
+        ));
+
+        // Find the definition of the member at the object path in the XML specification.
+        let (file_path, interface_name) =
+            $crate::find_definition_in_dbus_xml!(xml_path, member, interface, MsgType::Method, Some(path));
+
+        let xml = $crate::load_xml(&file_path).expect("Failed to load XML file");
+        $crate::get_method_return_type_at(xml.as_bytes(), path, &interface_name, member, $argument)
+            .expect("Failed to get method argument(s) type signature")
+    }};
 }
 
 /// Retrieve the signature of a method's arguments.
@@ -375,6 +431,16 @@ macro_rules! method_return_signature {
 ///     
 /// let _sig = method_args_signature!(member: "RequestName", interface: "org.example.Node", argument: "apple");
 /// ```
+///
+/// If an interface is implemented differently at several object paths, the named form takes a
+/// `path` to look only at the interfaces implemented there:
+///
+/// ```rust
+/// # use zbus_lockstep::method_args_signature;
+/// # std::env::set_var("LOCKSTEP_XML_PATH", "../xml");
+/// let sig = method_args_signature!(member: "Dim", path: "/org/example/lamp/desk");
+/// assert_eq!(sig, "d");
+/// ```
 #[macro_export]
 macro_rules! method_args_signature {
     ($member:expr) => {{
@@ -392,8 +458,8 @@ macro_rules! method_args_signature {
         let (file_path, interface_name) =
             $crate::find_definition_in_dbus_xml!(xml_path, member, None, MsgType::Method);
 
-        let file = std::fs::File::open(file_path).expect("Failed to open file");
-        $crate::get_method_args_type(file, &interface_name, member, None)
+        let xml = $crate::load_xml(&file_path).expect("Failed to load XML file");
+        $crate::get_method_args_type(xml.as_bytes(), &interface_name, member, None)
             .expect("Failed to get method arguments type signature")
     }};
 
@@ -418,8 +484,8 @@ macro_rules! method_args_signature {
         let (file_path, interface_name) =
             $crate::find_definition_in_dbus_xml!(xml_path, member, interface, MsgType::Method);
 
-        let file = std::fs::File::open(file_path).expect("Failed to open file");
-        $crate::get_method_args_type(file, &interface_name, member, None)
+        let xml = $crate::load_xml(&file_path).expect("Failed to load XML file");
+        $crate::get_method_args_type(xml.as_bytes(), &interface_name, member, None)
             .expect("Failed to get method arguments type signature")
     }};
 
@@ -445,14 +511,49 @@ macro_rules! method_args_signature {
         let (file_path, interface_name) =
             $crate::find_definition_in_dbus_xml!(xml_path, member, interface, MsgType::Method);
 
-        let file = std::fs::File::open(file_path).expect("Failed to open file");
-        $crate::get_method_args_type(file, &interface_name, member, argument)
+        let xml = $crate::load_xml(&file_path).expect("Failed to load XML file");
+        $crate::get_method_args_type(xml.as_bytes(), &interface_name, member, argument)
             .expect("Failed to get method argument(s) type signature")
     }};
 
     (member: $member:expr, interface: $interface:expr, argument: $argument:expr) => {
         $crate::method_args_signature!($member, $interface, $argument)
     };
+
+    (member: $member:expr, path: $path:expr) => {
+        $crate::method_args_signature!(@at $member, None, None, $path)
+    };
+
+    (member: $member:expr, interface: $interface:expr, path: $path:expr) => {
+        $crate::method_args_signature!(@at $member, Some($interface.to_string()), None, $path)
+    };
+
+    (member: $member:expr, interface: $interface:expr, argument: $argument:expr, path: $path:expr) => {
+        $crate::method_args_signature!(@at $member, Some($interface.to_string()), Some($argument), $path)
+    };
+
+    // Looks for the member only among the interfaces implemented at the object path.
+    (@at $member:expr, $interface:expr, $argument:expr, $path:expr) => {{
+        use $crate::MsgType;
+        let member = $member;
+        let interface: Option<String> = $interface;
+        let path: &str = $path;
+
+        // Looking for default path or path specified by environment variable.
+        let current_dir: std::path::PathBuf = std::env::current_dir().unwrap();
+        let xml_path = $crate::resolve_xml_path(None).expect(&format!(
+            "Failed to resolve XML path, current dir: {}",
+            current_dir.to_str().unwrap()
+        ));
+
+        // Find the definition of the member at the object path in the XML specification.
+        let (file_path, interface_name) =
+            $crate::find_definition_in_dbus_xml!(xml_path, member, interface, MsgType::Method, Some(path));
+
+        let xml = $crate::load_xml(&file_path).expect("Failed to load XML file");
+        $crate::get_method_args_type_at(xml.as_bytes(), path, &interface_name, member, $argument)
+            .expect("Failed to get method argument(s) type signature")
+    }};
 }
 
 /// Retrieve the signature of a signal's body type.
@@ -492,6 +593,16 @@ macro_rules! method_args_signature {
 ///
 /// let _sig = signal_body_type_signature!(member: "Alert", interface: "org.example.Node", argument: "color");
 /// ```
+///
+/// If an interface is implemented differently at several object paths, the named form takes a
+/// `path` to look only at the interfaces implemented there:
+///
+/// ```rust
+/// # use zbus_lockstep::signal_body_type_signature;
+/// # std::env::set_var("LOCKSTEP_XML_PATH", "../xml");
+/// let sig = signal_body_type_signature!(member: "Switched", path: "/org/example/lamp/desk");
+/// assert_eq!(sig, "bs");
+/// ```
 #[macro_export]
 macro_rules! signal_body_type_signature {
     ($member:expr) => {{
@@ -509,9 +620,9 @@ macro_rules! signal_body_type_signature {
         let (file_path, interface_name) =
             $crate::find_definition_in_dbus_xml!(xml_path, member, None, MsgType::Signal);
 
-        let file = std::fs::File::open(file_path).expect("Failed to open file");
+        let xml = $crate::load_xml(&file_path).expect("Failed to load XML file");
 
-        $crate::get_signal_body_type(file, &interface_name, member, None)
+        $crate::get_signal_body_type(xml.as_bytes(), &interface_name, member, None)
             .expect("Failed to get method arguments type signature")
     }};
 
@@ -535,8 +646,8 @@ macro_rules! signal_body_type_signature {
         let (file_path, interface_name) =
             $crate::find_definition_in_dbus_xml!(xml_path, member, interface, MsgType::Signal);
 
-        let file = std::fs::File::open(file_path).expect("Failed to open file");
-        $crate::get_signal_body_type(file, &interface_name, member, None)
+        let xml = $crate::load_xml(&file_path).expect("Failed to load XML file");
+        $crate::get_signal_body_type(xml.as_bytes(), &interface_name, member, None)
             .expect("Failed to get method arguments type signature")
     }};
 
@@ -563,14 +674,49 @@ macro_rules! signal_body_type_signature {
         let (file_path, interface_name) =
             $crate::find_definition_in_dbus_xml!(xml_path, member, interface, MsgType::Signal);
 
-        let file = std::fs::File::open(file_path).expect("Failed to open file");
-        $crate::get_signal_body_type(file, &interface_name, member, argument)
+        let xml = $crate::load_xml(&file_path).expect("Failed to load XML file");
+        $crate::get_signal_body_type(xml.as_bytes(), &interface_name, member, argument)
             .expect("Failed to get method argument(s) type signature")
     }};
 
     (member: $member:expr, interface: $interface:expr, argument: $argument:expr) => {
         $crate::signal_body_type_signature!($member, $interface, $argument)
     };
+
+    (member: $member:expr, path: $path:expr) => {
+        $crate::signal_body_type_signature!(@at $member, None, None, $path)
+    };
+
+    (member: $member:expr, interface: $interface:expr, path: $path:expr) => {
+        $crate::signal_body_type_signature!(@at $member, Some($interface.to_string()), None, $path)
+    };
+
+    (member: $member:expr, interface: $interface:expr, argument: $argument:expr, path: $path:expr) => {
+        $crate::signal_body_type_signature!(@at $member, Some($interface.to_string()), Some($argument), $path)
+    };
+
+    // Looks for the member only among the interfaces implemented at the object path.
+    (@at $member:expr, $interface:expr, $argument:expr, $path:expr) => {{
+        use $crate::MsgType;
+        let member = $member;
+        let interface: Option<String> = $interface;
+        let path: &str = $path;
+
+        // Looking for default path or path specified by environment variable.
+        let current_dir: std::path::PathBuf = std::env::current_dir().unwrap();
+        let xml_path = $crate::resolve_xml_path(None).expect(&format!(
+            "Failed to resolve XML path, current dir: {}",
+            current_dir.to_str().unwrap()
+        ));
+
+        // Find the definition of the member at the object path in the XML specification.
+        let (file_path, interface_name) =
+            $crate::find_definition_in_dbus_xml!(xml_path, member, interface, MsgType::Signal, Some(path));
+
+        let xml = $crate::load_xml(&file_path).expect("Failed to load XML file");
+        $crate::get_signal_body_type_at(xml.as_bytes(), path, &interface_name, member, $argument)
+            .expect("Failed to get signal body type signature")
+    }};
 }
 
 /// Retrieve the signature of a property's type.
@@ -605,6 +751,16 @@ macro_rules! signal_body_type_signature {
 /// # std::env::set_var("LOCKSTEP_XML_PATH", "../xml");
 /// let _sig = property_type_signature!(member: "Features", interface: "org.example.Node");
 /// ```
+///
+/// If an interface is implemented differently at several object paths, the named form takes a
+/// `path` to look only at the interfaces implemented there:
+///
+/// ```rust
+/// # use zbus_lockstep::property_type_signature;
+/// # std::env::set_var("LOCKSTEP_XML_PATH", "../xml");
+/// let sig = property_type_signature!(member: "Glow", path: "/org/example/lamp/desk");
+/// assert_eq!(sig, "d");
+/// ```
 #[macro_export]
 macro_rules! property_type_signature {
     ($member:expr) => {{
@@ -622,9 +778,9 @@ macro_rules! property_type_signature {
         let (file_path, interface_name) =
             $crate::find_definition_in_dbus_xml!(xml_path, member, None, MsgType::Property);
 
-        let file = std::fs::File::open(file_path).expect("Failed to open file");
+        let xml = $crate::load_xml(&file_path).expect("Failed to load XML file");
 
-        $crate::get_property_type(file, &interface_name, member)
+        $crate::get_property_type(xml.as_bytes(), &interface_name, member)
             .expect("Failed to get property type signature")
     }};
 
@@ -648,14 +804,255 @@ macro_rules! property_type_signature {
         let (file_path, interface_name) =
             $crate::find_definition_in_dbus_xml!(xml_path, member, interface, MsgType::Property);
 
-        let file = std::fs::File::open(file_path).expect("Failed to open file");
-        $crate::get_property_type(file, &interface_name, member)
+        let xml = $crate::load_xml(&file_path).expect("Failed to load XML file");
+        $crate::get_property_type(xml.as_bytes(), &interface_name, member)
             .expect("Failed to get property type signature")
     }};
 
     (member: $member:expr, interface: $interface:expr) => {
         $crate::property_type_signature!($member, $interface)
     };
+
+    (member: $member:expr, path: $path:expr) => {
+        $crate::property_type_signature!(@at $member, None, $path)
+    };
+
+    (member: $member:expr, interface: $interface:expr, path: $path:expr) => {
+        $crate::property_type_signature!(@at $member, Some($interface.to_string()), $path)
+    };
+
+    // Looks for the member only among the interfaces implemented at the object path.
+    (@at $member:expr, $interface:expr, $path:expr) => {{
+        use $crate::MsgType;
+        let member = $member;
+        let interface: Option<String> = $interface;
+        let path: &str = $path;
+
+        // Looking for default path or path specified by environment variable.
+        let current_dir: std::path::PathBuf = std::env::current_dir().unwrap();
+        let xml_path = $crate::resolve_xml_path(None).expect(&format!(
+            "Failed to resolve XML path, current dir: {}",
+            current_dir.to_str().unwrap()
+        ));
+
+        // Find the definition of the member at the object path in the XML specification.
+        let (file_path, interface_name) =
+            $crate::find_definition_in_dbus_xml!(xml_path, member, interface, MsgType::Property, Some(path));
+
+        let xml = $crate::load_xml(&file_path).expect("Failed to load XML file");
+        $crate::get_property_type_at(xml.as_bytes(), path, &interface_name, member)
+            .expect("Failed to get property type signature")
+    }};
+}
+
+/// Retrieve whether a method is annotated `org.freedesktop.DBus.Method.NoReply`.
+///
+/// Essentially a wrapper around [`zbus_lockstep::get_method_no_reply`],
+/// but this macro tries to do with less arguments.
+///
+/// Useful to check that a proxy method is declared `#[zbus(no_reply)]` exactly when
+/// the XML says so.
+///
+/// If multiple interfaces offer the same member, you will need to
+/// specify the interface name as well.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::method_no_reply;
+///
+/// std::env::set_var("LOCKSTEP_XML_PATH", "../xml");
+///
+/// assert!(method_no_reply!("Ping"));
+/// assert!(!method_no_reply!(member: "RequestName", interface: "org.example.Node"));
+/// ```
+#[macro_export]
+macro_rules! method_no_reply {
+    ($member:expr) => {{
+        use $crate::MsgType;
+        let member = $member;
+
+        // Looking for default path or path specified by environment variable.
+        let current_dir: std::path::PathBuf = std::env::current_dir().unwrap();
+        let xml_path = $crate::resolve_xml_path(None).expect(&format!(
+            "Failed to resolve XML path, current dir: {}",
+            current_dir.to_str().unwrap()
+        ));
+
+        // Find the definition of the method in the XML specification.
+        let (file_path, interface_name) =
+            $crate::find_definition_in_dbus_xml!(xml_path, member, None, MsgType::Method);
+
+        let xml = $crate::load_xml(&file_path).expect("Failed to load XML file");
+        $crate::get_method_no_reply(xml.as_bytes(), &interface_name, member)
+            .expect("Failed to get method NoReply annotation")
+    }};
+
+    (member: $member:expr) => {
+        $crate::method_no_reply!($member)
+    };
+
+    ($member:expr, $interface:expr) => {{
+        use $crate::MsgType;
+        let member = $member;
+        let interface = Some($interface.to_string());
+
+        // Looking for default path or path specified by environment variable.
+        let current_dir: std::path::PathBuf = std::env::current_dir().unwrap();
+        let xml_path = $crate::resolve_xml_path(None).expect(&format!(
+            "Failed to resolve XML path, current dir: {}",
+            current_dir.to_str().unwrap()
+        ));
+
+        // Find the definition of the method in the XML specification.
+        let (file_path, interface_name) =
+            $crate::find_definition_in_dbus_xml!(xml_path, member, interface, MsgType::Method);
+
+        let xml = $crate::load_xml(&file_path).expect("Failed to load XML file");
+        $crate::get_method_no_reply(xml.as_bytes(), &interface_name, member)
+            .expect("Failed to get method NoReply annotation")
+    }};
+
+    (member: $member:expr, interface: $interface:expr) => {
+        $crate::method_no_reply!($member, $interface)
+    };
+}
+
+/// Assert that a Rust type has the signature of a member in the XML.
+///
+/// The member is given as `signal`, `method_args`, `method_return` or `property`, then the
+/// type. Like the `*_signature!` macros, it may also take an `interface` and a `path`.
+///
+/// On a mismatch, the panic message explains both signatures and suggests a type that matches
+/// the XML, named like [`codegen::generate_types`](crate::codegen::generate_types) names it.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::assert_signature_eq;
+/// use zvariant::{OwnedObjectPath, Type};
+///
+/// std::env::set_var("LOCKSTEP_XML_PATH", "../xml");
+///
+/// #[derive(Type)]
+/// struct RequestNameArgs {
+///     apple: String,
+///     orange: u32,
+/// }
+///
+/// assert_signature_eq!(method_args: "RequestName", RequestNameArgs);
+/// assert_signature_eq!(method_return: "RequestName", interface: "org.example.Node", u32);
+/// assert_signature_eq!(signal: "RemoveNode", (String, OwnedObjectPath));
+/// assert_signature_eq!(property: "Glow", path: "/org/example/lamp/desk", f64);
+/// ```
+///
+/// A mismatch panics with a type to paste:
+///
+/// ```rust,should_panic
+/// # use zbus_lockstep::assert_signature_eq;
+/// # use zvariant::Type;
+/// # std::env::set_var("LOCKSTEP_XML_PATH", "../xml");
+/// #[derive(Type)]
+/// struct Alert {
+///     urgent: bool,
+/// }
+///
+/// // The message ends with `pub struct AlertSignal { pub urgent: bool, pub color: String, ...`
+/// assert_signature_eq!(signal: "Alert", Alert);
+/// ```
+#[macro_export]
+macro_rules! assert_signature_eq {
+    ($kind:ident: $member:expr, interface: $interface:expr, path: $path:expr, $ty:ty) => {
+        $crate::assert_signature_eq!(@check $kind, $member, Some($interface.to_string()), Some($path), $ty)
+    };
+
+    ($kind:ident: $member:expr, interface: $interface:expr, $ty:ty) => {
+        $crate::assert_signature_eq!(@check $kind, $member, Some($interface.to_string()), None, $ty)
+    };
+
+    ($kind:ident: $member:expr, path: $path:expr, $ty:ty) => {
+        $crate::assert_signature_eq!(@check $kind, $member, None, Some($path), $ty)
+    };
+
+    ($kind:ident: $member:expr, $ty:ty) => {
+        $crate::assert_signature_eq!(@check $kind, $member, None, None, $ty)
+    };
+
+    (@source signal, $member:expr) => {
+        ($crate::MsgType::Signal, $crate::codegen::TypeSource::SignalBody($member.to_string()))
+    };
+
+    (@source method_args, $member:expr) => {
+        ($crate::MsgType::Method, $crate::codegen::TypeSource::MethodArgs($member.to_string()))
+    };
+
+    (@source method_return, $member:expr) => {
+        ($crate::MsgType::Method, $crate::codegen::TypeSource::MethodReply($member.to_string()))
+    };
+
+    (@source property, $member:expr) => {
+        ($crate::MsgType::Property, $crate::codegen::TypeSource::Property($member.to_string()))
+    };
+
+    (@check $kind:ident, $member:expr, $interface:expr, $path:expr, $ty:ty) => {{
+        use $crate::codegen::TypeSource;
+        let member: &str = $member;
+        let interface: Option<String> = $interface;
+        let path: Option<&str> = $path;
+        let (msg_type, source) = $crate::assert_signature_eq!(@source $kind, member);
+
+        // Looking for default path or path specified by environment variable.
+        let current_dir: std::path::PathBuf = std::env::current_dir().unwrap();
+        let xml_path = $crate::resolve_xml_path(None).expect(&format!(
+            "Failed to resolve XML path, current dir: {}",
+            current_dir.to_str().unwrap()
+        ));
+
+        // Find the definition of the member in the XML specification.
+        let (file_path, interface_name) =
+            $crate::find_definition_in_dbus_xml!(xml_path, member, interface, msg_type, path);
+
+        let xml = $crate::load_xml(&file_path).expect("Failed to load XML file");
+        let xml = xml.as_bytes();
+        let signature = match (&source, path) {
+            (TypeSource::SignalBody(_), None) => {
+                $crate::get_signal_body_type(xml, &interface_name, member, None)
+            }
+            (TypeSource::SignalBody(_), Some(path)) => {
+                $crate::get_signal_body_type_at(xml, path, &interface_name, member, None)
+            }
+            (TypeSource::MethodArgs(_), None) => {
+                $crate::get_method_args_type(xml, &interface_name, member, None)
+            }
+            (TypeSource::MethodArgs(_), Some(path)) => {
+                $crate::get_method_args_type_at(xml, path, &interface_name, member, None)
+            }
+            (TypeSource::MethodReply(_), None) => {
+                $crate::get_method_return_type(xml, &interface_name, member, None)
+            }
+            (TypeSource::MethodReply(_), Some(path)) => {
+                $crate::get_method_return_type_at(xml, path, &interface_name, member, None)
+            }
+            (TypeSource::Property(_), None) => {
+                $crate::get_property_type(xml, &interface_name, member)
+            }
+            (TypeSource::Property(_), Some(path)) => {
+                $crate::get_property_type_at(xml, path, &interface_name, member)
+            }
+        }
+        .expect("Failed to get type signature from XML file");
+        let signature_of_type = <$ty as ::zvariant::Type>::SIGNATURE;
+
+        if &signature != signature_of_type {
+            panic!(
+                "{source} in XML is `{signature}`, but `{}` is `{signature_of_type}`.\n{}\nA type that matches the XML:\n\n{}",
+                stringify!($ty),
+                $crate::explain_mismatch(&signature, signature_of_type),
+                $crate::codegen::suggest_type(xml, &interface_name, path, &source, &source.type_name())
+                    .unwrap_or_else(|e| format!("None, {e}\n"))
+            );
+        }
+    }};
 }
 
 #[cfg(test)]
@@ -883,4 +1280,43 @@ mod test {
             Signature::from_str("as").expect("Vlaid signature pattern")
         );
     }
+
+    #[test]
+    fn test_method_no_reply_macro() {
+        assert!(crate::method_no_reply!("Ping"));
+        assert!(!crate::method_no_reply!("RequestName"));
+    }
+
+    #[test]
+    fn test_method_no_reply_macro_with_interface_and_identifiers() {
+        assert!(crate::method_no_reply!(member: "Ping", interface: "org.example.Node"));
+    }
+
+    #[test]
+    fn test_assert_signature_eq_macro() {
+        crate::assert_signature_eq!(method_args: "RequestName", (String, u32));
+        crate::assert_signature_eq!(signal: "Alert", interface: "org.example.Node", (bool, String, f64));
+        crate::assert_signature_eq!(property: "Features", Vec<String>);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "A type that matches the XML:\n\n/// Reply of the `RequestName` method"
+    )]
+    fn test_assert_signature_eq_macro_suggests_type() {
+        crate::assert_signature_eq!(method_return: "RequestName", String);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "pub struct AlertSignal {\n    pub urgent: bool,\n    pub color: String,"
+    )]
+    fn test_assert_signature_eq_macro_suggests_struct() {
+        #[derive(zvariant::Type)]
+        struct Alert {
+            urgent: bool,
+        }
+
+        crate::assert_signature_eq!(signal: "Alert", Alert);
+    }
 }
@@ -0,0 +1,379 @@
+//! Validation of structs holding the properties of an interface, as returned by `GetAll`.
+//!
+//! `org.freedesktop.DBus.Properties.GetAll` returns an `a{sv}`, which clients often deserialize
+//! into a struct with a field per property, using zvariant's `dict` signature. Such a struct is
+//! in lockstep with the XML when each field corresponds to a readable property of the same
+//! type, and each readable property has a field.
+
+use std::{fmt, io::Read, str::FromStr};
+
+use zbus_xml::PropertyAccess;
+use zvariant::Signature;
+
+use crate::{
+    access_str, load, tree, EmitsChangedSignal, LockstepError::PropertyBundleMismatch, Result,
+};
+
+/// How field and variant names map to names in XML, like serde's `rename_all`.
+///
+/// Field names are expected in `snake_case`, variant names in `PascalCase`. `DBus` property
+/// names are usually in `PascalCase`.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum RenameRule {
+    /// Field names are used as they are.
+    #[default]
+    None,
+    /// `lowercase`
+    LowerCase,
+    /// `UPPERCASE`
+    UpperCase,
+    /// `PascalCase`
+    PascalCase,
+    /// `camelCase`
+    CamelCase,
+    /// `snake_case`
+    SnakeCase,
+    /// `SCREAMING_SNAKE_CASE`
+    ScreamingSnakeCase,
+    /// `kebab-case`
+    KebabCase,
+    /// `SCREAMING-KEBAB-CASE`
+    ScreamingKebabCase,
+}
+
+impl RenameRule {
+    /// The name of the field named `field`, expected in `snake_case`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use zbus_lockstep::RenameRule;
+    ///
+    /// assert_eq!(RenameRule::PascalCase.apply("power_saving"), "PowerSaving");
+    /// assert_eq!(RenameRule::CamelCase.apply("power_saving"), "powerSaving");
+    /// assert_eq!(RenameRule::None.apply("power_saving"), "power_saving");
+    /// ```
+    pub fn apply(&self, field: &str) -> String {
+        match self {
+            RenameRule::None | RenameRule::LowerCase | RenameRule::SnakeCase => field.to_owned(),
+            RenameRule::UpperCase | RenameRule::ScreamingSnakeCase => field.to_ascii_uppercase(),
+            RenameRule::PascalCase => field
+                .split('_')
+                .map(|word| {
+                    let mut chars = word.chars();
+                    chars.next().map_or_else(String::new, |first| {
+                        first.to_ascii_uppercase().to_string() + chars.as_str()
+                    })
+                })
+                .collect(),
+            RenameRule::CamelCase => {
+                let pascal = RenameRule::PascalCase.apply(field);
+                let mut chars = pascal.chars();
+                chars.next().map_or_else(String::new, |first| {
+                    first.to_ascii_lowercase().to_string() + chars.as_str()
+                })
+            }
+            RenameRule::KebabCase => field.replace('_', "-"),
+            RenameRule::ScreamingKebabCase => field.replace('_', "-").to_ascii_uppercase(),
+        }
+    }
+
+    /// The name of the enum variant named `variant`, expected in `PascalCase`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use zbus_lockstep::RenameRule;
+    ///
+    /// assert_eq!(RenameRule::ScreamingSnakeCase.apply_to_variant("TitleBar"), "TITLE_BAR");
+    /// assert_eq!(RenameRule::CamelCase.apply_to_variant("TitleBar"), "titleBar");
+    /// ```
+    pub fn apply_to_variant(&self, variant: &str) -> String {
+        match self {
+            RenameRule::None | RenameRule::PascalCase => variant.to_owned(),
+            RenameRule::LowerCase => variant.to_ascii_lowercase(),
+            RenameRule::UpperCase => variant.to_ascii_uppercase(),
+            RenameRule::CamelCase => {
+                let mut chars = variant.chars();
+                chars.next().map_or_else(String::new, |first| {
+                    first.to_ascii_lowercase().to_string() + chars.as_str()
+                })
+            }
+            RenameRule::SnakeCase => {
+                let mut snake = String::new();
+                for (index, c) in variant.char_indices() {
+                    if index > 0 && c.is_uppercase() {
+                        snake.push('_');
+                    }
+                    snake.push(c.to_ascii_lowercase());
+                }
+                snake
+            }
+            RenameRule::ScreamingSnakeCase => RenameRule::SnakeCase
+                .apply_to_variant(variant)
+                .to_ascii_uppercase(),
+            RenameRule::KebabCase => RenameRule::SnakeCase
+                .apply_to_variant(variant)
+                .replace('_', "-"),
+            RenameRule::ScreamingKebabCase => RenameRule::ScreamingSnakeCase
+                .apply_to_variant(variant)
+                .replace('_', "-"),
+        }
+    }
+
+    /// The rule as written in serde's `rename_all`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RenameRule::None => "none",
+            RenameRule::LowerCase => "lowercase",
+            RenameRule::UpperCase => "UPPERCASE",
+            RenameRule::PascalCase => "PascalCase",
+            RenameRule::CamelCase => "camelCase",
+            RenameRule::SnakeCase => "snake_case",
+            RenameRule::ScreamingSnakeCase => "SCREAMING_SNAKE_CASE",
+            RenameRule::KebabCase => "kebab-case",
+            RenameRule::ScreamingKebabCase => "SCREAMING-KEBAB-CASE",
+        }
+    }
+}
+
+impl FromStr for RenameRule {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(RenameRule::None),
+            "lowercase" => Ok(RenameRule::LowerCase),
+            "UPPERCASE" => Ok(RenameRule::UpperCase),
+            "PascalCase" => Ok(RenameRule::PascalCase),
+            "camelCase" => Ok(RenameRule::CamelCase),
+            "snake_case" => Ok(RenameRule::SnakeCase),
+            "SCREAMING_SNAKE_CASE" => Ok(RenameRule::ScreamingSnakeCase),
+            "kebab-case" => Ok(RenameRule::KebabCase),
+            "SCREAMING-KEBAB-CASE" => Ok(RenameRule::ScreamingKebabCase),
+            _ => Err(format!("Unknown case conversion \"{s}\"")),
+        }
+    }
+}
+
+impl fmt::Display for RenameRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A field of a struct holding the properties of an interface.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PropertyField<'a> {
+    /// The name of the field.
+    pub field: &'a str,
+    /// The name of the property the field holds.
+    pub property: &'a str,
+    /// The signature of the field's type, or of `T` for an `Option<T>`.
+    pub signature: &'a Signature,
+    /// Whether the field is an `Option`, which may be absent from the dictionary.
+    pub optional: bool,
+}
+
+/// Check the fields of a struct against the readable properties of an interface.
+///
+/// Each field must hold a readable property, with the property's type. Optional fields are
+/// allowed for properties which may change, not for `const` properties, which `GetAll` always
+/// returns. Every readable property must have a field.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::{validate_properties, PropertyField};
+/// use zvariant::Type;
+///
+/// let xml = r#"
+/// <node>
+/// <interface name="org.example.Device">
+///   <property name="Name" type="s" access="read"/>
+///   <property name="Brightness" type="u" access="readwrite"/>
+/// </interface>
+/// </node>
+/// "#;
+///
+/// let fields = [
+///     PropertyField {
+///         field: "name",
+///         property: "Name",
+///         signature: String::SIGNATURE,
+///         optional: false,
+///     },
+///     PropertyField {
+///         field: "brightness",
+///         property: "Brightness",
+///         signature: u32::SIGNATURE,
+///         optional: true,
+///     },
+/// ];
+///
+/// assert!(validate_properties(xml.as_bytes(), "org.example.Device", None, &fields).is_ok());
+/// assert!(validate_properties(xml.as_bytes(), "org.example.Device", None, &fields[..1]).is_err());
+/// ```
+pub fn validate_properties(
+    mut xml: impl Read,
+    interface_name: &str,
+    object_path: Option<&str>,
+    fields: &[PropertyField<'_>],
+) -> Result<()> {
+    let nodes = load::read_nodes(&mut xml)?;
+
+    let (_, interface) = tree::find_interface_in(&nodes, interface_name, object_path)?;
+
+    let mut problems = Vec::new();
+    for field in fields {
+        let Some(property) = interface
+            .properties()
+            .iter()
+            .find(|property| property.name() == field.property)
+        else {
+            problems.push(format!(
+                "Field \"{}\" holds property \"{}\", which the interface does not have.",
+                field.field, field.property
+            ));
+            continue;
+        };
+
+        if property.access() == PropertyAccess::Write {
+            problems.push(format!(
+                "Field \"{}\" holds property \"{}\", which is \"{}\" and not returned by `GetAll`.",
+                field.field,
+                field.property,
+                access_str(property.access())
+            ));
+            continue;
+        }
+
+        let ty = property.ty().to_string();
+        if !Signature::from_str(&ty).is_ok_and(|signature| signature == *field.signature) {
+            problems.push(format!(
+                "Field \"{}\" has type \"{}\", but property \"{}\" has type \"{ty}\".",
+                field.field, field.signature, field.property
+            ));
+        }
+
+        let emits =
+            EmitsChangedSignal::from_annotations(property.annotations(), interface.annotations())?;
+        if field.optional && emits == EmitsChangedSignal::Const {
+            problems.push(format!(
+                "Field \"{}\" is optional, but property \"{}\" is const.",
+                field.field, field.property
+            ));
+        }
+    }
+
+    for property in interface.properties() {
+        if property.access() != PropertyAccess::Write
+            && !fields.iter().any(|field| property.name() == field.property)
+        {
+            problems.push(format!(
+                "Property \"{}\" of type \"{}\" has no field.",
+                property.name(),
+                property.ty().to_string()
+            ));
+        }
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(PropertyBundleMismatch(interface_name.to_owned(), problems).into())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use zvariant::{OwnedObjectPath, Type};
+
+    use super::*;
+    use crate::LockstepError;
+
+    const XML: &str = r#"
+<node>
+  <interface name="org.example.Device">
+    <property name="Name" type="s" access="read">
+      <annotation name="org.freedesktop.DBus.Property.EmitsChangedSignal" value="const"/>
+    </property>
+    <property name="PowerSaving" type="b" access="readwrite"/>
+    <property name="Secret" type="s" access="write"/>
+  </interface>
+</node>"#;
+
+    fn field<'a>(field: &'a str, property: &'a str, signature: &'a Signature) -> PropertyField<'a> {
+        PropertyField {
+            field,
+            property,
+            signature,
+            optional: false,
+        }
+    }
+
+    fn problems(fields: &[PropertyField<'_>]) -> Vec<String> {
+        let e =
+            validate_properties(XML.as_bytes(), "org.example.Device", None, fields).unwrap_err();
+        match e.downcast_ref::<LockstepError>() {
+            Some(PropertyBundleMismatch(_, problems)) => problems.clone(),
+            _ => panic!("Unexpected error: {e}"),
+        }
+    }
+
+    #[test]
+    fn test_validate_properties() {
+        let fields = [
+            field("name", "Name", String::SIGNATURE),
+            PropertyField {
+                optional: true,
+                ..field("power_saving", "PowerSaving", bool::SIGNATURE)
+            },
+        ];
+        assert!(validate_properties(XML.as_bytes(), "org.example.Device", None, &fields).is_ok());
+    }
+
+    #[test]
+    fn test_validate_properties_reports_all_problems() {
+        let fields = [
+            PropertyField {
+                optional: true,
+                ..field("name", "Name", OwnedObjectPath::SIGNATURE)
+            },
+            field("secret", "Secret", String::SIGNATURE),
+            field("color", "Color", String::SIGNATURE),
+        ];
+
+        assert_eq!(
+            problems(&fields),
+            [
+                "Field \"name\" has type \"o\", but property \"Name\" has type \"s\".",
+                "Field \"name\" is optional, but property \"Name\" is const.",
+                "Field \"secret\" holds property \"Secret\", which is \"write\" and not returned by `GetAll`.",
+                "Field \"color\" holds property \"Color\", which the interface does not have.",
+                "Property \"PowerSaving\" of type \"b\" has no field.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rename_rule() {
+        let rules = [
+            ("lowercase", "power_saving", "powersaving"),
+            ("UPPERCASE", "POWER_SAVING", "POWERSAVING"),
+            ("PascalCase", "PowerSaving", "PowerSaving"),
+            ("camelCase", "powerSaving", "powerSaving"),
+            ("snake_case", "power_saving", "power_saving"),
+            ("SCREAMING_SNAKE_CASE", "POWER_SAVING", "POWER_SAVING"),
+            ("kebab-case", "power-saving", "power-saving"),
+            ("SCREAMING-KEBAB-CASE", "POWER-SAVING", "POWER-SAVING"),
+        ];
+        for (rule, field, variant) in rules {
+            let rule: RenameRule = rule.parse().unwrap();
+            assert_eq!(rule.apply("power_saving"), field);
+            assert_eq!(rule.apply_to_variant("PowerSaving"), variant);
+            assert_eq!(rule.to_string().parse(), Ok(rule));
+        }
+        assert!("Title Case".parse::<RenameRule>().is_err());
+    }
+}
@@ -0,0 +1,301 @@
+//! An index of the signatures of every member in a set of XML files.
+//!
+//! Interfaces such as AT-SPI reuse a few signatures, `(so)` or `a(so)`, in many places. A
+//! [`Registry`] finds every member with a signature, so one Rust type can serve all of them,
+//! and reports which signatures are used the most.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use zbus_xml::{ArgDirection, Node};
+use zvariant::Signature;
+
+use crate::{
+    args_signature, codegen::TypeSource, interfaces_with_paths, lint::split_signature, load_nodes,
+    Result, Scanner,
+};
+
+/// A signal body, a method's arguments or reply, or a property, with its signature.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Member {
+    /// The file defining the member.
+    pub file: PathBuf,
+    /// The object path of the node the interface belongs to.
+    pub path: String,
+    pub interface: String,
+    pub source: TypeSource,
+    /// The signature, as written in the XML.
+    pub signature: String,
+}
+
+impl fmt::Display for Member {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let what = match self.source {
+            TypeSource::SignalBody(_) => "signal body",
+            TypeSource::MethodArgs(_) => "method args",
+            TypeSource::MethodReply(_) => "method return",
+            TypeSource::Property(_) => "property",
+        };
+        let file = self.file.file_name().unwrap_or(self.file.as_os_str());
+        write!(
+            f,
+            "{}.{} ({what}, {})",
+            self.interface,
+            self.source.member(),
+            file.to_string_lossy()
+        )
+    }
+}
+
+/// The members of a set of XML files, indexed by signature.
+///
+/// Members without arguments, e.g. a method without reply, are not indexed.
+///
+/// Signatures are compared structurally, the way `zvariant` compares them: the arguments of a
+/// member with several, `(so)u`, have the same signature as a struct of them, `((so)u)`, so a
+/// method taking a string and an object path shares its signature with a signal whose single
+/// argument is `(so)`.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::registry::Registry;
+/// use zvariant::Signature;
+///
+/// let registry = Registry::load("../xml").unwrap();
+/// let wanted: Signature = "(so)".parse().unwrap();
+///
+/// let members: Vec<_> = registry
+///     .members_with_signature(&wanted)
+///     .map(|member| member.source.member())
+///     .collect();
+///
+/// assert!(members.contains(&"AddNode"));
+/// assert!(members.contains(&"RemoveNode"));
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct Registry {
+    members: Vec<Member>,
+    by_signature: HashMap<String, Vec<usize>>,
+}
+
+impl Registry {
+    /// An empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A registry of the members in the XML files in `xml_dir`, as selected by [`Scanner`].
+    pub fn load(xml_dir: impl AsRef<Path>) -> Result<Self> {
+        let mut registry = Self::new();
+        for file in Scanner::new().scan(xml_dir)?.files {
+            let nodes = load_nodes(&file)?;
+            registry.add_nodes(file, &nodes);
+        }
+        Ok(registry)
+    }
+
+    /// Add the members of the nodes of `file`.
+    pub fn add_nodes(&mut self, file: impl Into<PathBuf>, nodes: &[Node<'_>]) {
+        let file = file.into();
+        for (path, interface) in nodes.iter().flat_map(interfaces_with_paths) {
+            let mut add = |source: TypeSource, signature: String| {
+                if signature.is_empty() {
+                    return;
+                }
+                self.by_signature
+                    .entry(canonical(&signature))
+                    .or_default()
+                    .push(self.members.len());
+                self.members.push(Member {
+                    file: file.clone(),
+                    path: path.clone(),
+                    interface: interface.name().to_string(),
+                    source,
+                    signature,
+                });
+            };
+
+            for method in interface.methods() {
+                let name = method.name().to_string();
+                let signature = |direction| args_signature(method.args(), Some(direction));
+                add(
+                    TypeSource::MethodArgs(name.clone()),
+                    signature(ArgDirection::In),
+                );
+                add(TypeSource::MethodReply(name), signature(ArgDirection::Out));
+            }
+            for signal in interface.signals() {
+                let signature = args_signature(signal.args(), None);
+                add(TypeSource::SignalBody(signal.name().to_string()), signature);
+            }
+            for property in interface.properties() {
+                let source = TypeSource::Property(property.name().to_string());
+                add(source, property.ty().to_string());
+            }
+        }
+    }
+
+    /// Every indexed member, in the order they were added.
+    pub fn members(&self) -> &[Member] {
+        &self.members
+    }
+
+    /// The members whose signature is structurally equal to `signature`.
+    pub fn members_with_signature<'r>(
+        &'r self,
+        signature: &Signature,
+    ) -> impl Iterator<Item = &'r Member> + 'r {
+        self.by_signature
+            .get(&canonical(&signature.to_string()))
+            .into_iter()
+            .flatten()
+            .map(|&index| &self.members[index])
+    }
+
+    /// The signatures shared by at least `min_members` members, the most used first.
+    ///
+    /// Signatures of several arguments are reported as the struct of them, e.g. `((so)u)`.
+    /// Signatures used equally often are sorted by signature.
+    pub fn most_common_signatures(&self, min_members: usize) -> Vec<(&str, Vec<&Member>)> {
+        let mut common: Vec<_> = self
+            .by_signature
+            .iter()
+            .filter(|(_, indices)| indices.len() >= min_members)
+            .map(|(signature, indices)| {
+                let members: Vec<_> = indices.iter().map(|&index| &self.members[index]).collect();
+                (signature.as_str(), members)
+            })
+            .collect();
+        common.sort_by(|(a, a_members), (b, b_members)| {
+            b_members.len().cmp(&a_members.len()).then(a.cmp(b))
+        });
+        common
+    }
+}
+
+/// The signature of a single complete type equal to `signature`, which wraps several complete
+/// types in a struct.
+fn canonical(signature: &str) -> String {
+    match split_signature(signature) {
+        Ok(types) if types.len() > 1 => format!("({signature})"),
+        _ => signature.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn registry(xml: &str) -> Registry {
+        let nodes = vec![Node::from_reader(xml.as_bytes()).unwrap()];
+        let mut registry = Registry::new();
+        registry.add_nodes("test.xml", &nodes);
+        registry
+    }
+
+    #[test]
+    fn test_members_with_signature() {
+        let registry = registry(
+            r#"
+<node>
+  <interface name="org.example.Tree">
+    <method name="Graft">
+      <arg name="name" type="s" direction="in"/>
+      <arg name="branch" type="o" direction="in"/>
+    </method>
+    <signal name="Grafted">
+      <arg name="branch" type="(so)"/>
+    </signal>
+    <property name="Root" type="(so)" access="read"/>
+    <property name="Name" type="s" access="read"/>
+  </interface>
+</node>"#,
+        );
+
+        let so: Signature = "(so)".parse().unwrap();
+        let found: Vec<_> = registry
+            .members_with_signature(&so)
+            .map(|member| member.source.clone())
+            .collect();
+        assert_eq!(
+            found,
+            [
+                TypeSource::MethodArgs("Graft".to_string()),
+                TypeSource::SignalBody("Grafted".to_string()),
+                TypeSource::Property("Root".to_string()),
+            ]
+        );
+
+        // The method has no reply, so nothing has an empty signature.
+        let empty: Signature = "".parse().unwrap();
+        assert_eq!(registry.members_with_signature(&empty).count(), 0);
+
+        assert_eq!(
+            registry.members()[0].to_string(),
+            "org.example.Tree.Graft (method args, test.xml)"
+        );
+    }
+
+    #[test]
+    fn test_most_common_signatures() {
+        let registry = registry(
+            r#"
+<node>
+  <interface name="org.example.Tree">
+    <signal name="Grown"><arg type="a(so)"/></signal>
+    <signal name="Pruned"><arg type="a(so)"/></signal>
+    <property name="Leaves" type="a(so)" access="read"/>
+    <property name="Name" type="s" access="read"/>
+    <property name="Kind" type="s" access="read"/>
+    <property name="Age" type="u" access="read"/>
+  </interface>
+</node>"#,
+        );
+
+        let common: Vec<_> = registry
+            .most_common_signatures(2)
+            .into_iter()
+            .map(|(signature, members)| (signature, members.len()))
+            .collect();
+        assert_eq!(common, [("a(so)", 3), ("s", 2)]);
+        assert_eq!(registry.most_common_signatures(1).len(), 3);
+    }
+
+    #[test]
+    fn test_method_signatures_match_get_functions() {
+        let xml = std::fs::read_to_string("../xml/directions.xml").unwrap();
+        let registry = registry(&xml);
+
+        let signature = |source| {
+            registry
+                .members()
+                .iter()
+                .find(|member| member.source == source)
+                .map(|member| member.signature.parse::<Signature>().unwrap())
+                .unwrap()
+        };
+        let turn = || "Turn".to_string();
+
+        // Arguments without a direction are input arguments, as for `get_method_args_type`.
+        assert_eq!(
+            signature(TypeSource::MethodArgs(turn())),
+            crate::get_method_args_type(xml.as_bytes(), "org.example.Dial", "Turn", None).unwrap()
+        );
+        assert_eq!(
+            signature(TypeSource::MethodReply(turn())),
+            crate::get_method_return_type(xml.as_bytes(), "org.example.Dial", "Turn", None)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_canonical() {
+        assert_eq!(canonical("(so)u"), "((so)u)");
+        assert_eq!(canonical("(so)"), "(so)");
+        assert_eq!(canonical("a{sv}"), "a{sv}");
+    }
+}
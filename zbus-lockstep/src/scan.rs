@@ -0,0 +1,340 @@
+//! Scanning of a directory for XML files.
+//!
+//! Shared by the macros, so that `#[validate]` and the `*_signature!` macros agree on which
+//! files to look at, and in which order.
+
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use crate::{is_xml_file, Result};
+
+/// Name of the file, in the scanned directory, listing globs of files to ignore.
+pub const IGNORE_FILE: &str = ".lockstepignore";
+
+/// Selects the XML files in a directory.
+///
+/// Only the directory itself is scanned, subdirectories are not. Files must have an XML
+/// extension, see [`is_xml_file`], and are matched by name against the globs. Globs support
+/// `*`, any run of characters, and `?`, any single character.
+///
+/// Besides the excludes added here, globs listed in a [`IGNORE_FILE`] in the directory are
+/// excluded, one per line. Blank lines and lines starting with `#` are skipped.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::Scanner;
+///
+/// let scan = Scanner::new().exclude("nested_*").scan("../xml").unwrap();
+/// let names: Vec<_> = scan.files.iter().map(|f| f.file_name().unwrap()).collect();
+///
+/// assert!(names.contains(&"test_definition_file.xml".as_ref()));
+/// assert!(!names.contains(&"nested_nodes.xml".as_ref()));
+/// assert!(names.is_sorted());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scanner {
+    include: Vec<String>,
+    exclude: Vec<String>,
+    follow_symlinks: bool,
+}
+
+impl Default for Scanner {
+    fn default() -> Self {
+        Scanner {
+            include: Vec::new(),
+            exclude: Vec::new(),
+            follow_symlinks: true,
+        }
+    }
+}
+
+impl Scanner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only select files matching `glob`, or any other included glob.
+    #[must_use]
+    pub fn include(mut self, glob: impl Into<String>) -> Self {
+        self.include.push(glob.into());
+        self
+    }
+
+    /// Skip files matching `glob`.
+    #[must_use]
+    pub fn exclude(mut self, glob: impl Into<String>) -> Self {
+        self.exclude.push(glob.into());
+        self
+    }
+
+    /// Whether symbolic links to files are read, `true` by default.
+    #[must_use]
+    pub fn follow_symlinks(mut self, follow: bool) -> Self {
+        self.follow_symlinks = follow;
+        self
+    }
+
+    /// Scan `dir`, selected files are sorted by name.
+    pub fn scan(&self, dir: impl AsRef<Path>) -> Result<Scan> {
+        let dir = dir.as_ref();
+
+        let mut exclude = self.exclude.clone();
+        match std::fs::read_to_string(dir.join(IGNORE_FILE)) {
+            Ok(ignore) => exclude.extend(
+                ignore
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                    .map(String::from),
+            ),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(format!("Failed to read \"{IGNORE_FILE}\": {e}").into()),
+        }
+
+        let mut paths = std::fs::read_dir(dir)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<std::io::Result<Vec<_>>>()?;
+        paths.sort();
+
+        let mut scan = Scan::default();
+        // Canonical paths of selected files, to read a file linked to twice only once.
+        let mut canonical_paths: Vec<PathBuf> = Vec::new();
+
+        for path in paths {
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+
+            let reason = if name == IGNORE_FILE {
+                continue;
+            } else if path.is_symlink() && !self.follow_symlinks {
+                Some(SkipReason::Symlink)
+            } else if path.is_symlink() && !path.exists() {
+                Some(SkipReason::BrokenSymlink)
+            } else if path.is_dir() {
+                Some(SkipReason::Directory)
+            } else if !is_xml_file(&path) {
+                Some(SkipReason::NotXml)
+            } else if exclude.iter().any(|glob| glob_matches(glob, &name)) {
+                Some(SkipReason::Excluded)
+            } else if !self.include.is_empty()
+                && !self.include.iter().any(|glob| glob_matches(glob, &name))
+            {
+                Some(SkipReason::NotIncluded)
+            } else {
+                let canonical = path.canonicalize()?;
+                match scan
+                    .files
+                    .iter()
+                    .zip(&canonical_paths)
+                    .find(|(_, other)| **other == canonical)
+                {
+                    Some((original, _)) => Some(SkipReason::Duplicate(original.clone())),
+                    None => {
+                        canonical_paths.push(canonical);
+                        None
+                    }
+                }
+            };
+
+            match reason {
+                Some(reason) => scan.skipped.push(Skipped { path, reason }),
+                None => scan.files.push(path),
+            }
+        }
+
+        Ok(scan)
+    }
+}
+
+/// The result of scanning a directory.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Scan {
+    /// The selected XML files, sorted by name.
+    pub files: Vec<PathBuf>,
+    /// Everything else in the directory, sorted by name.
+    pub skipped: Vec<Skipped>,
+}
+
+/// A directory entry that was not selected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Skipped {
+    pub path: PathBuf,
+    pub reason: SkipReason,
+}
+
+impl fmt::Display for Skipped {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.reason)
+    }
+}
+
+/// Why a directory entry was not selected.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SkipReason {
+    /// Subdirectories are not scanned.
+    Directory,
+    /// The file name has no XML extension.
+    NotXml,
+    /// The file matches an exclude glob, or a glob in the ignore file.
+    Excluded,
+    /// The file matches none of the include globs.
+    NotIncluded,
+    /// Symbolic links are not followed.
+    Symlink,
+    /// The symbolic link points to nothing.
+    BrokenSymlink,
+    /// The file is a link to a file that was already selected.
+    Duplicate(PathBuf),
+}
+
+impl fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SkipReason::Directory => f.write_str("directory"),
+            SkipReason::NotXml => f.write_str("not an XML file"),
+            SkipReason::Excluded => f.write_str("excluded"),
+            SkipReason::NotIncluded => f.write_str("not included"),
+            SkipReason::Symlink => f.write_str("symbolic link"),
+            SkipReason::BrokenSymlink => f.write_str("broken symbolic link"),
+            SkipReason::Duplicate(original) => write!(f, "same file as {}", original.display()),
+        }
+    }
+}
+
+/// Match `name` against a glob of `*` and `?` wildcards.
+fn glob_matches(glob: &str, name: &str) -> bool {
+    let glob: Vec<char> = glob.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+
+    let (mut g, mut n) = (0, 0);
+    // Position of the last `*` in the glob and the position in the name it was tried at.
+    let mut backtrack = None;
+
+    while n < name.len() {
+        match glob.get(g) {
+            Some('*') => {
+                backtrack = Some((g, n));
+                g += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                g += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                // Let the last `*` consume one more character.
+                Some((star, tried)) => {
+                    backtrack = Some((star, tried + 1));
+                    g = star + 1;
+                    n = tried + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+
+    glob[g..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches("*.xml", "Cache.xml"));
+        assert!(glob_matches("org.*.Cache.xml", "org.a11y.atspi.Cache.xml"));
+        assert!(glob_matches("Cach?.xml", "Cache.xml"));
+        assert!(glob_matches("*", ""));
+        assert!(glob_matches("*a*b", "xxaxxb"));
+        assert!(!glob_matches("*.xml", "Cache.xml.gz"));
+        assert!(!glob_matches("Cache", "Cache.xml"));
+        assert!(!glob_matches("*a*b", "xxaxxbx"));
+    }
+
+    #[test]
+    fn test_scan_skips_and_orders_files() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in [
+            "b.xml",
+            "a.xml",
+            "old.xml",
+            "README",
+            ".gitkeep",
+            "notes.txt",
+        ] {
+            fs::write(dir.path().join(name), "<node/>").unwrap();
+        }
+        fs::create_dir(dir.path().join("archive")).unwrap();
+        fs::write(dir.path().join(IGNORE_FILE), "# Superseded\nold.xml\n").unwrap();
+
+        let scan = Scanner::new().scan(dir.path()).unwrap();
+
+        let files: Vec<_> = scan.files.iter().map(|f| f.file_name().unwrap()).collect();
+        assert_eq!(files, ["a.xml", "b.xml"]);
+
+        let skipped: Vec<_> = scan
+            .skipped
+            .iter()
+            .map(|s| (s.path.file_name().unwrap().to_str().unwrap(), &s.reason))
+            .collect();
+        assert_eq!(
+            skipped,
+            [
+                (".gitkeep", &SkipReason::NotXml),
+                ("README", &SkipReason::NotXml),
+                ("archive", &SkipReason::Directory),
+                ("notes.txt", &SkipReason::NotXml),
+                ("old.xml", &SkipReason::Excluded),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scan_include() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["org.example.A.xml", "org.other.B.xml"] {
+            fs::write(dir.path().join(name), "<node/>").unwrap();
+        }
+
+        let scan = Scanner::new()
+            .include("org.example.*")
+            .scan(dir.path())
+            .unwrap();
+
+        assert_eq!(scan.files, [dir.path().join("org.example.A.xml")]);
+        assert_eq!(scan.skipped[0].reason, SkipReason::NotIncluded);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_scan_symlinks() {
+        use std::os::unix::fs::symlink;
+
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.xml"), "<node/>").unwrap();
+        symlink(dir.path().join("a.xml"), dir.path().join("b.xml")).unwrap();
+        symlink(dir.path().join("missing.xml"), dir.path().join("c.xml")).unwrap();
+
+        let scan = Scanner::new().scan(dir.path()).unwrap();
+        assert_eq!(scan.files, [dir.path().join("a.xml")]);
+        assert_eq!(
+            scan.skipped[0].reason,
+            SkipReason::Duplicate(dir.path().join("a.xml"))
+        );
+        assert_eq!(scan.skipped[1].reason, SkipReason::BrokenSymlink);
+
+        let scan = Scanner::new()
+            .follow_symlinks(false)
+            .scan(dir.path())
+            .unwrap();
+        assert_eq!(scan.skipped[0].reason, SkipReason::Symlink);
+    }
+}
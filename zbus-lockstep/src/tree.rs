@@ -0,0 +1,222 @@
+//! Traversal of `<node>` trees.
+//!
+//! Introspection data of a real service, e.g. from `busctl introspect --xml`, nests `<node>`s,
+//! each implementing interfaces at its own object path.
+
+use zbus_xml::{Interface, Node};
+
+use crate::LockstepError::{self, InterfaceNotFound, ObjectPathNotFound};
+
+/// Every interface in a tree of nodes, along with the object path it was found at.
+///
+/// The root node is at its `name`, if that is an absolute path, or at `/` otherwise.
+/// A child node's relative `name` is appended to the path of its parent.
+///
+/// Interfaces are listed depth-first, in document order.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::{interfaces_with_paths, Node};
+///
+/// let xml = r#"
+/// <node>
+///   <interface name="org.freedesktop.DBus.Introspectable">
+///     <method name="Introspect"><arg name="xml" type="s" direction="out"/></method>
+///   </interface>
+///   <node name="org/a11y/atspi/accessible/root">
+///     <interface name="org.a11y.atspi.Accessible">
+///       <property name="Name" type="s" access="read"/>
+///     </interface>
+///   </node>
+/// </node>
+/// "#;
+///
+/// let node = Node::from_reader(xml.as_bytes()).unwrap();
+/// let paths: Vec<_> = interfaces_with_paths(&node)
+///     .into_iter()
+///     .map(|(path, iface)| (path, iface.name().to_string()))
+///     .collect();
+///
+/// assert_eq!(paths[0], ("/".to_string(), "org.freedesktop.DBus.Introspectable".to_string()));
+/// assert_eq!(
+///     paths[1],
+///     ("/org/a11y/atspi/accessible/root".to_string(), "org.a11y.atspi.Accessible".to_string())
+/// );
+/// ```
+pub fn interfaces_with_paths<'n, 'a>(node: &'n Node<'a>) -> Vec<(String, &'n Interface<'a>)> {
+    let mut interfaces = Vec::new();
+    collect_interfaces(node, root_path(node), &mut interfaces);
+    interfaces
+}
+
+fn collect_interfaces<'n, 'a>(
+    node: &'n Node<'a>,
+    path: String,
+    interfaces: &mut Vec<(String, &'n Interface<'a>)>,
+) {
+    for interface in node.interfaces() {
+        interfaces.push((path.clone(), interface));
+    }
+
+    for child in node.nodes() {
+        collect_interfaces(child, child_path(&path, child), interfaces);
+    }
+}
+
+fn root_path(node: &Node<'_>) -> String {
+    match node.name() {
+        Some(name) if name.starts_with('/') => normalize(name),
+        _ => String::from("/"),
+    }
+}
+
+fn child_path(parent: &str, child: &Node<'_>) -> String {
+    match child.name() {
+        Some(name) if name.starts_with('/') => normalize(name),
+        Some(name) => normalize(&format!("{}/{name}", parent.trim_end_matches('/'))),
+        // Unnamed children are not valid introspection data, treat them as part of the parent.
+        None => parent.to_string(),
+    }
+}
+
+/// Find an interface in a tree of nodes, optionally only at a given object path.
+///
+/// Returns the object path the interface was found at, along with the interface. Without an
+/// object path, the first node implementing the interface is used.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::{find_interface, Node};
+///
+/// let xml = r#"
+/// <node name="/org/a11y/atspi/accessible">
+///   <node name="root">
+///     <interface name="org.a11y.atspi.Accessible">
+///       <property name="Name" type="s" access="read"/>
+///     </interface>
+///   </node>
+/// </node>
+/// "#;
+///
+/// let node = Node::from_reader(xml.as_bytes()).unwrap();
+///
+/// let (path, _) = find_interface(&node, "org.a11y.atspi.Accessible", None).unwrap();
+/// assert_eq!(path, "/org/a11y/atspi/accessible/root");
+///
+/// let scoped = find_interface(&node, "org.a11y.atspi.Accessible", Some("/org/a11y/atspi/accessible"));
+/// assert!(scoped.is_err());
+/// ```
+pub fn find_interface<'n, 'a>(
+    node: &'n Node<'a>,
+    interface_name: &str,
+    object_path: Option<&str>,
+) -> Result<(String, &'n Interface<'a>), LockstepError> {
+    let interfaces = interfaces_with_paths(node);
+
+    if let Some(object_path) = object_path {
+        let object_path = normalize(object_path);
+        let mut at_path = interfaces
+            .into_iter()
+            .filter(|(path, _)| *path == object_path)
+            .peekable();
+
+        if at_path.peek().is_none() && !has_node_at(node, &object_path) {
+            return Err(ObjectPathNotFound(object_path));
+        }
+
+        return at_path
+            .find(|(_, iface)| iface.name() == interface_name)
+            .ok_or(InterfaceNotFound(interface_name.to_owned()));
+    }
+
+    interfaces
+        .into_iter()
+        .find(|(_, iface)| iface.name() == interface_name)
+        .ok_or(InterfaceNotFound(interface_name.to_owned()))
+}
+
+/// Find an interface in the nodes of a file holding several documents, optionally only at a
+/// given object path.
+///
+/// If a document has no node at `object_path`, the path is reported as not found, rather than
+/// the interface.
+pub(crate) fn find_interface_in<'n, 'a>(
+    nodes: &'n [Node<'a>],
+    interface_name: &str,
+    object_path: Option<&str>,
+) -> Result<(String, &'n Interface<'a>), LockstepError> {
+    let mut error = None;
+    for node in nodes {
+        match find_interface(node, interface_name, object_path) {
+            Ok(found) => return Ok(found),
+            Err(e @ InterfaceNotFound(_)) => {
+                error.get_or_insert(e);
+            }
+            Err(e) => {
+                if matches!(error, None | Some(InterfaceNotFound(_))) {
+                    error = Some(e);
+                }
+            }
+        }
+    }
+
+    Err(error.unwrap_or(InterfaceNotFound(interface_name.to_owned())))
+}
+
+/// Whether a node exists at `object_path`.
+fn has_node_at(node: &Node<'_>, object_path: &str) -> bool {
+    fn visit(node: &Node<'_>, path: String, object_path: &str) -> bool {
+        path == object_path
+            || node
+                .nodes()
+                .iter()
+                .any(|child| visit(child, child_path(&path, child), object_path))
+    }
+
+    visit(node, root_path(node), object_path)
+}
+
+/// Object paths without a trailing slash, except for the root path.
+fn normalize(object_path: &str) -> String {
+    match object_path.trim_end_matches('/') {
+        "" => String::from("/"),
+        path => path.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::load::nodes_from_str;
+
+    #[test]
+    fn test_find_interface_in_prefers_missing_path() {
+        let nodes = nodes_from_str(
+            r#"<node name="/org/example/a"><interface name="org.example.A"/></node>
+            <node name="/org/example/b"><interface name="org.example.B"/></node>"#,
+        )
+        .unwrap();
+
+        let found = find_interface_in(&nodes, "org.example.B", Some("/org/example/b")).unwrap();
+        assert_eq!(found.0, "/org/example/b");
+
+        // The first document has no node at the path, which is reported over the interface
+        // missing from the second one.
+        for (path, expected) in [
+            (
+                Some("/org/example/b"),
+                "Object path \"/org/example/b\" not found.",
+            ),
+            (
+                Some("/org/example/c"),
+                "Object path \"/org/example/c\" not found.",
+            ),
+            (None, "Interface \"org.example.C\" not found."),
+        ] {
+            let error = find_interface_in(&nodes, "org.example.C", path).unwrap_err();
+            assert_eq!(error.to_string(), expected);
+        }
+    }
+}
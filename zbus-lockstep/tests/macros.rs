@@ -4,6 +4,8 @@
 // - `method_args_signature`
 // - `signal_body_type_signature`
 // - `property_type_signature`
+//
+// And their `path:` forms, to look only at the interfaces implemented at an object path.
 
 use zbus_lockstep::{
     method_args_signature, method_return_signature, property_type_signature,
@@ -41,3 +43,65 @@ fn test_property_type_signature() {
     let signature = property_type_signature!("Features");
     assert_eq!(signature, "as");
 }
+
+#[test]
+fn test_signal_body_type_signature_in_child_nodes() {
+    std::env::set_var("LOCKSTEP_XML_PATH", "../xml");
+
+    // `org.example.Tree` is implemented at two object paths, which is not ambiguous.
+    let signature = signal_body_type_signature!("BranchGrown");
+    assert_eq!(signature, "(so)u");
+}
+
+#[test]
+fn test_signal_body_type_signature_from_included_file() {
+    std::env::set_var("LOCKSTEP_XML_PATH", "../xml");
+
+    let signature = signal_body_type_signature!("Germinated");
+    assert_eq!(signature, "(so)q");
+}
+
+#[test]
+fn test_method_return_signature_from_second_document() {
+    std::env::set_var("LOCKSTEP_XML_PATH", "../xml");
+
+    let signature = method_return_signature!("Water");
+    assert_eq!(signature, "b");
+
+    let signature = signal_body_type_signature!("Dried");
+    assert_eq!(signature, "y");
+}
+
+#[test]
+fn test_signatures_at_object_path() {
+    std::env::set_var("LOCKSTEP_XML_PATH", "../xml");
+
+    // `org.example.Lamp` is implemented differently at two object paths.
+    let signature = signal_body_type_signature!(member: "Switched", path: "/org/example/lamp/desk");
+    assert_eq!(signature, "bs");
+
+    let signature = signal_body_type_signature!(
+        member: "Switched",
+        interface: "org.example.Lamp",
+        path: "/org/example/lamp/ceiling"
+    );
+    assert_eq!(signature, "bu");
+
+    let signature = method_args_signature!(
+        member: "Dim",
+        interface: "org.example.Lamp",
+        argument: "level",
+        path: "/org/example/lamp/desk"
+    );
+    assert_eq!(signature, "d");
+
+    let signature = method_return_signature!(member: "Dim", path: "/org/example/lamp/desk");
+    assert_eq!(signature, "d");
+
+    let signature = property_type_signature!(
+        member: "Glow",
+        interface: "org.example.Lamp",
+        path: "/org/example/lamp/ceiling"
+    );
+    assert_eq!(signature, "u");
+}
@@ -0,0 +1,28 @@
+// Tests for the proxy generated by `zbus_lockstep::codegen`.
+//
+// `proxy/node.rs` holds what `generate_proxy` and `generate_proxy_tests` produce for
+// `org.example.Node`. It is checked in and included here, so the proxy is compiled against
+// `zbus` and its tests run along with these.
+
+use std::path::Path;
+
+use zbus_lockstep::codegen::{generate_proxy, generate_proxy_tests};
+
+include!("proxy/node.rs");
+
+const XML_FILE: &str = "../xml/test_definition_file.xml";
+
+#[test]
+fn test_generated_proxy_is_up_to_date() {
+    let nodes = zbus_lockstep::load_nodes(XML_FILE).unwrap();
+    let interface = &nodes[0].interfaces()[0];
+
+    let source = format!(
+        "{}\n{}",
+        generate_proxy(interface).unwrap(),
+        generate_proxy_tests(interface, Path::new(XML_FILE)).unwrap()
+    );
+    if source != include_str!("proxy/node.rs") {
+        panic!("`tests/proxy/node.rs` is outdated, replace it with:\n{source}");
+    }
+}
@@ -0,0 +1,125 @@
+/// Proxy for `org.example.Node`.
+#[::zbus::proxy(interface = "org.example.Node")]
+pub trait Node {
+    /// `RequestName` method.
+    #[zbus(name = "RequestName")]
+    fn request_name(&self, apple: String, orange: u32) -> ::zbus::Result<u32>;
+
+    /// `Ping` method.
+    #[zbus(name = "Ping", no_reply)]
+    fn ping(&self) -> ::zbus::Result<()>;
+
+    /// `AddNode` signal.
+    #[zbus(signal, name = "AddNode")]
+    fn add_node(&self, node_added: (String, ::zvariant::OwnedObjectPath)) -> ::zbus::Result<()>;
+
+    /// `Alert` signal.
+    #[zbus(signal, name = "Alert")]
+    fn alert(&self, urgent: bool, color: String, volume: f64) -> ::zbus::Result<()>;
+
+    /// `RemoveNode` signal.
+    #[zbus(signal, name = "RemoveNode")]
+    fn remove_node(&self, node_removed: (String, ::zvariant::OwnedObjectPath)) -> ::zbus::Result<()>;
+
+    /// `Features` property.
+    #[zbus(property, name = "Features")]
+    fn features(&self) -> ::zbus::Result<Vec<String>>;
+}
+
+#[cfg(test)]
+mod node_lockstep {
+    use ::zvariant::Type;
+
+    const XML_FILE: &str = "../xml/test_definition_file.xml";
+    const INTERFACE: &str = "org.example.Node";
+
+    #[test]
+    fn request_name_args() {
+        let _: fn(&super::NodeProxyBlocking<'static>, String, u32) -> ::zbus::Result<u32> = super::NodeProxyBlocking::request_name;
+        let xml = ::zbus_lockstep::load_xml(XML_FILE).expect("Failed to load XML file.");
+        let signature = ::zbus_lockstep::get_method_args_type(xml.as_bytes(), INTERFACE, "RequestName", None)
+            .expect("Failed to get type signature from XML file.");
+        let signature_of_type = <(String, u32) as Type>::SIGNATURE;
+        assert_eq!(
+            &signature,
+            signature_of_type,
+            "{}",
+            ::zbus_lockstep::explain_mismatch(&signature, signature_of_type)
+        );
+    }
+
+    #[test]
+    fn request_name_reply() {
+        let _: fn(&super::NodeProxyBlocking<'static>, String, u32) -> ::zbus::Result<u32> = super::NodeProxyBlocking::request_name;
+        let xml = ::zbus_lockstep::load_xml(XML_FILE).expect("Failed to load XML file.");
+        let signature = ::zbus_lockstep::get_method_return_type(xml.as_bytes(), INTERFACE, "RequestName", None)
+            .expect("Failed to get type signature from XML file.");
+        let signature_of_type = <u32 as Type>::SIGNATURE;
+        assert_eq!(
+            &signature,
+            signature_of_type,
+            "{}",
+            ::zbus_lockstep::explain_mismatch(&signature, signature_of_type)
+        );
+    }
+
+    #[test]
+    fn add_node_signal() {
+        let _ = super::NodeProxyBlocking::receive_add_node;
+        let xml = ::zbus_lockstep::load_xml(XML_FILE).expect("Failed to load XML file.");
+        let signature = ::zbus_lockstep::get_signal_body_type(xml.as_bytes(), INTERFACE, "AddNode", None)
+            .expect("Failed to get type signature from XML file.");
+        let signature_of_type = <(String, ::zvariant::OwnedObjectPath) as Type>::SIGNATURE;
+        assert_eq!(
+            &signature,
+            signature_of_type,
+            "{}",
+            ::zbus_lockstep::explain_mismatch(&signature, signature_of_type)
+        );
+    }
+
+    #[test]
+    fn alert_signal() {
+        let _ = super::NodeProxyBlocking::receive_alert;
+        let xml = ::zbus_lockstep::load_xml(XML_FILE).expect("Failed to load XML file.");
+        let signature = ::zbus_lockstep::get_signal_body_type(xml.as_bytes(), INTERFACE, "Alert", None)
+            .expect("Failed to get type signature from XML file.");
+        let signature_of_type = <(bool, String, f64) as Type>::SIGNATURE;
+        assert_eq!(
+            &signature,
+            signature_of_type,
+            "{}",
+            ::zbus_lockstep::explain_mismatch(&signature, signature_of_type)
+        );
+    }
+
+    #[test]
+    fn remove_node_signal() {
+        let _ = super::NodeProxyBlocking::receive_remove_node;
+        let xml = ::zbus_lockstep::load_xml(XML_FILE).expect("Failed to load XML file.");
+        let signature = ::zbus_lockstep::get_signal_body_type(xml.as_bytes(), INTERFACE, "RemoveNode", None)
+            .expect("Failed to get type signature from XML file.");
+        let signature_of_type = <(String, ::zvariant::OwnedObjectPath) as Type>::SIGNATURE;
+        assert_eq!(
+            &signature,
+            signature_of_type,
+            "{}",
+            ::zbus_lockstep::explain_mismatch(&signature, signature_of_type)
+        );
+    }
+
+    #[test]
+    fn features_property() {
+        let _: fn(&super::NodeProxyBlocking<'static>) -> ::zbus::Result<Vec<String>> = super::NodeProxyBlocking::features;
+        let xml = ::zbus_lockstep::load_xml(XML_FILE).expect("Failed to load XML file.");
+        let signature = ::zbus_lockstep::get_property_type(xml.as_bytes(), INTERFACE, "Features")
+            .expect("Failed to get type signature from XML file.");
+        let signature_of_type = <Vec<String> as Type>::SIGNATURE;
+        assert_eq!(
+            &signature,
+            signature_of_type,
+            "{}",
+            ::zbus_lockstep::explain_mismatch(&signature, signature_of_type)
+        );
+    }
+}